@@ -0,0 +1,90 @@
+use crate::{idl::Idl, prelude::*};
+
+const NAME: &str = "anchor_instruction";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str =
+        include_str!("../../../node-definitions/solana/anchor_instruction.json");
+    static CACHE: BuilderCache = BuilderCache::new(|| {
+        CmdBuilder::new(DEFINITION)?
+            .check_name(NAME)?
+            .simple_instruction_info("signature")
+    });
+    Ok(CACHE.clone()?.build(run))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    #[serde(with = "value::keypair")]
+    fee_payer: Keypair,
+    #[serde(with = "value::pubkey")]
+    program_id: Pubkey,
+    /// IDL as JSON or a JSON string, fetched from the IDL account of the program if not set.
+    #[serde(default)]
+    idl: Option<Value>,
+    instruction: String,
+    #[serde(default)]
+    args: value::Map,
+    /// Public keys or keypairs of accounts by name.
+    #[serde(default)]
+    accounts: value::Map,
+    #[serde(default = "value::default::bool_true")]
+    submit: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    instruction: Value,
+    #[serde(default, with = "value::signature::opt")]
+    signature: Option<Signature>,
+}
+
+async fn run(mut ctx: Context, input: Input) -> Result<Output, CommandError> {
+    let idl = match input.idl {
+        None | Some(Value::Null) => {
+            crate::idl::fetch_idl(&ctx.solana_client, &input.program_id).await?
+        }
+        Some(idl) => Idl::from_value(idl)?,
+    };
+    let (instruction, mut signers) = idl.build_instruction(
+        input.program_id,
+        &input.instruction,
+        &input.args,
+        &input.accounts,
+    )?;
+    let output = value::to_value(&instruction)?;
+
+    let instructions = if input.submit {
+        signers.retain(|k| k.pubkey() != input.fee_payer.pubkey());
+        signers.push(input.fee_payer.clone_keypair());
+        Instructions {
+            fee_payer: input.fee_payer.pubkey(),
+            signers,
+            minimum_balance_for_rent_exemption: 0,
+            instructions: [instruction].into(),
+        }
+    } else {
+        Instructions::default()
+    };
+    let signature = ctx
+        .execute(instructions, Default::default())
+        .await?
+        .signature;
+
+    Ok(Output {
+        instruction: output,
+        signature,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        build().unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+//! Create or update node-definition files of commands in this crate.
+//!
+//! ```text
+//! cargo run -p cmds-solana --bin gen-node-definitions -- [--check] [NODE_DEFINITIONS_DIR]
+//! ```
+//!
+//! With `--check`, files are not written and the exit code is 1 if any file is out of date.
+
+use cmds_solana as _;
+use flow_lib::command::generate::generate;
+use std::path::PathBuf;
+
+fn main() {
+    let mut check = false;
+    let mut root = PathBuf::from(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../node-definitions"
+    ));
+    for arg in std::env::args().skip(1) {
+        if arg == "--check" {
+            check = true;
+        } else {
+            root = arg.into();
+        }
+    }
+
+    let results = generate(&root).expect("failed to read node-definitions");
+    let mut outdated = 0;
+    for result in results {
+        match result {
+            Ok(g) if g.changed => {
+                outdated += 1;
+                if check {
+                    println!("outdated: {}", g.path.display());
+                } else {
+                    if let Some(parent) = g.path.parent() {
+                        std::fs::create_dir_all(parent).unwrap();
+                    }
+                    std::fs::write(&g.path, g.content()).unwrap();
+                    println!("updated: {}", g.path.display());
+                }
+            }
+            Ok(_) => {}
+            Err((name, error)) => eprintln!("skipped {name}: {error}"),
+        }
+    }
+
+    if check && outdated > 0 {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,318 @@
+//! Generate error tables of [`flow_lib::solana::program_errors`] from the error enums of program
+//! crates that this crate depends on.
+//!
+//! ```text
+//! cargo run -p cmds-solana --features gen-program-errors --bin gen-program-errors -- [--check]
+//! ```
+//!
+//! Sources of dependencies are found with `cargo metadata`. With `--check`, the file is not
+//! written and the exit code is 1 if it is out of date.
+
+use std::{collections::HashMap, path::PathBuf, process::Command};
+
+const OUTPUT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../flow-lib/src/solana/program_errors/generated.rs"
+);
+
+const HEADER: &str =
+    "//! Generated by `cargo run -p cmds-solana --features gen-program-errors --bin gen-program-errors`, do not edit.
+
+use super::ErrorEntry;
+";
+
+/// Offset of `#[error_code]` enums without an `offset` argument.
+const ANCHOR_ERROR_OFFSET: u32 = 6000;
+
+struct Source {
+    /// Name of the generated table.
+    table: &'static str,
+    /// Name of the dependency in `Cargo.toml` of this crate.
+    dependency: &'static str,
+    /// File of the error enum, relative to the dependency's root.
+    file: &'static str,
+    name: &'static str,
+}
+
+const SOURCES: &[Source] = &[
+    Source {
+        table: "TOKEN_ERRORS",
+        dependency: "spl-token",
+        file: "src/error.rs",
+        name: "TokenError",
+    },
+    Source {
+        table: "TOKEN_2022_ERRORS",
+        dependency: "spl-token-2022",
+        file: "src/error.rs",
+        name: "TokenError",
+    },
+    Source {
+        table: "ASSOCIATED_TOKEN_ACCOUNT_ERRORS",
+        dependency: "spl-associated-token-account",
+        file: "src/error.rs",
+        name: "AssociatedTokenAccountError",
+    },
+    Source {
+        table: "TOKEN_METADATA_ERRORS",
+        dependency: "mpl-token-metadata",
+        file: "src/generated/errors/mpl_token_metadata.rs",
+        name: "MplTokenMetadataError",
+    },
+    Source {
+        table: "CANDY_MACHINE_CORE_ERRORS",
+        dependency: "mpl-candy-machine-core",
+        file: "src/errors.rs",
+        name: "CandyError",
+    },
+    Source {
+        table: "CANDY_GUARD_ERRORS",
+        dependency: "mpl-candy-guard",
+        file: "src/errors.rs",
+        name: "CandyGuardError",
+    },
+    Source {
+        table: "BUBBLEGUM_ERRORS",
+        dependency: "mpl-bubblegum",
+        file: "src/error.rs",
+        name: "BubblegumError",
+    },
+    Source {
+        table: "ACCOUNT_COMPRESSION_ERRORS",
+        dependency: "spl-account-compression",
+        file: "src/error.rs",
+        name: "AccountCompressionError",
+    },
+    Source {
+        table: "ANCHOR_ERRORS",
+        dependency: "anchor-lang",
+        file: "src/error.rs",
+        name: "ErrorCode",
+    },
+];
+
+/// `(name, version, root)` of each dependency of this crate, by its name in `Cargo.toml`.
+fn dependencies() -> HashMap<String, (String, String, PathBuf)> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned());
+    let output = Command::new(cargo)
+        .args(["metadata", "--format-version", "1", "--manifest-path"])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+        .output()
+        .expect("failed to run cargo metadata");
+    assert!(
+        output.status.success(),
+        "cargo metadata failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let packages = metadata["packages"].as_array().unwrap();
+    let this = packages
+        .iter()
+        .find(|p| p["name"] == env!("CARGO_PKG_NAME"))
+        .unwrap();
+    let id_of = |dependency: &serde_json::Value| {
+        // renamed dependencies are resolved by their new name
+        let name = dependency["rename"]
+            .as_str()
+            .or(dependency["name"].as_str())
+            .unwrap();
+        (
+            name.to_owned(),
+            dependency["name"].as_str().unwrap().to_owned(),
+        )
+    };
+    let node = metadata["resolve"]["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|n| n["id"] == this["id"])
+        .unwrap();
+
+    let mut result = HashMap::new();
+    for dependency in this["dependencies"].as_array().unwrap() {
+        let (name, package) = id_of(dependency);
+        let extern_name = name.replace('-', "_");
+        let Some(id) = node["deps"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|d| d["name"] == extern_name.as_str())
+            .map(|d| &d["pkg"])
+        else {
+            continue;
+        };
+        let Some(p) = packages.iter().find(|p| p["id"] == *id) else {
+            continue;
+        };
+        let root = PathBuf::from(p["manifest_path"].as_str().unwrap())
+            .parent()
+            .unwrap()
+            .to_owned();
+        let version = p["version"].as_str().unwrap().to_owned();
+        result.insert(name, (package, version, root));
+    }
+    result
+}
+
+/// First string argument of attribute `name`, such as `#[msg("...")]` or `#[error("...")]`.
+fn attr_str(attrs: &[syn::Attribute], name: &str) -> Option<String> {
+    let attr = attrs.iter().find(|a| a.path().is_ident(name))?;
+    let args = attr
+        .parse_args_with(syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated)
+        .ok()?;
+    match args.first()? {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(s),
+            ..
+        }) => Some(s.value()),
+        _ => None,
+    }
+}
+
+/// `(code, name, message)` of each variant of enum `name` in `file`.
+fn parse_enum(file: &syn::File, name: &str) -> Result<Vec<(u32, String, String)>, String> {
+    let item = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Enum(e) if e.ident == name => Some(e),
+            _ => None,
+        })
+        .ok_or_else(|| format!("enum {name} not found"))?;
+
+    let offset = match item.attrs.iter().find(|a| a.path().is_ident("error_code")) {
+        None => 0,
+        Some(attr) if matches!(attr.meta, syn::Meta::Path(_)) => ANCHOR_ERROR_OFFSET,
+        Some(attr) => {
+            let mut offset = ANCHOR_ERROR_OFFSET;
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("offset") {
+                    offset = meta.value()?.parse::<syn::LitInt>()?.base10_parse()?;
+                }
+                Ok(())
+            })
+            .map_err(|e| e.to_string())?;
+            offset
+        }
+    };
+
+    let mut next = 0u32;
+    let mut result = Vec::new();
+    for variant in &item.variants {
+        let value = match &variant.discriminant {
+            Some((
+                _,
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(i),
+                    ..
+                }),
+            )) => i.base10_parse::<u32>().map_err(|e| e.to_string())?,
+            Some(_) => return Err(format!("unsupported discriminant of {}", variant.ident)),
+            None => next,
+        };
+        next = value + 1;
+        let message = attr_str(&variant.attrs, "msg")
+            .or_else(|| attr_str(&variant.attrs, "error"))
+            .unwrap_or_default();
+        result.push((
+            offset + value,
+            variant.ident.to_string(),
+            message.trim().to_owned(),
+        ));
+    }
+    Ok(result)
+}
+
+fn generate() -> String {
+    let dependencies = dependencies();
+    let mut content = HEADER.to_owned();
+    for source in SOURCES {
+        let (package, version, root) = dependencies
+            .get(source.dependency)
+            .unwrap_or_else(|| panic!("{} is not a dependency", source.dependency));
+        let path = root.join(source.file);
+        let text = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("reading {}: {e}", path.display()));
+        let file =
+            syn::parse_file(&text).unwrap_or_else(|e| panic!("parsing {}: {e}", path.display()));
+        let errors =
+            parse_enum(&file, source.name).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+
+        content += &format!(
+            "\n/// `{}` of {package} {version}.\npub(super) const {}: &[ErrorEntry] = &[\n",
+            source.name, source.table
+        );
+        for (code, name, message) in errors {
+            content += &format!("    ({code}, {name:?}, {message:?}),\n");
+        }
+        content += "];\n";
+    }
+    content
+}
+
+fn main() {
+    let check = std::env::args().skip(1).any(|arg| arg == "--check");
+    let content = generate();
+    let current = std::fs::read_to_string(OUTPUT).unwrap_or_default();
+    if content == current {
+        return;
+    }
+    if check {
+        println!("outdated: {OUTPUT}");
+        std::process::exit(1);
+    }
+    std::fs::write(OUTPUT, content).unwrap();
+    println!("updated: {OUTPUT}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_lib::solana::program_errors::lookup;
+    use num_traits::FromPrimitive;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_up_to_date() {
+        let current = std::fs::read_to_string(OUTPUT).unwrap();
+        assert!(
+            generate() == current,
+            "{OUTPUT} is outdated, run `cargo run -p cmds-solana --features gen-program-errors --bin gen-program-errors`"
+        );
+    }
+
+    /// Check the tables against enums that can be listed with [`FromPrimitive`].
+    fn check<E>(program_id: &Pubkey)
+    where
+        E: FromPrimitive + std::fmt::Debug + std::fmt::Display,
+    {
+        let mut code = 0;
+        while let Some(e) = E::from_u32(code) {
+            let info = lookup(program_id, code)
+                .unwrap_or_else(|| panic!("{program_id} error {code} not found"));
+            assert_eq!(info.name, format!("{e:?}"));
+            assert_eq!(info.message, e.to_string().trim());
+            code += 1;
+        }
+        assert!(code > 0);
+    }
+
+    #[test]
+    fn test_enums() {
+        check::<spl_token::error::TokenError>(&spl_token::ID);
+        check::<spl_token_2022::error::TokenError>(&spl_token_2022::ID);
+        check::<spl_associated_token_account::error::AssociatedTokenAccountError>(
+            &spl_associated_token_account::ID,
+        );
+        check::<mpl_token_metadata::errors::MplTokenMetadataError>(&mpl_token_metadata::ID);
+
+        // Anchor enums can not be listed, check the last variants
+        let candy_guard = mpl_candy_guard::errors::CandyGuardError::ExceededProgramListSize;
+        let info = lookup(&mpl_candy_guard::ID, candy_guard.into()).unwrap();
+        assert_eq!(info.name, candy_guard.name());
+        let anchor = anchor_lang::error::ErrorCode::Deprecated;
+        let info = lookup(&mpl_candy_guard::ID, anchor.into()).unwrap();
+        assert_eq!(info.name, anchor.name());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::prelude::*;
 use anyhow::anyhow;
-use flow_lib::config::node::Permissions;
+use flow_lib::{config::node::Permissions, FlowError};
 use reqwest::{header::AUTHORIZATION, StatusCode};
 
 // Command Name
@@ -23,6 +23,10 @@ inventory::submit!(CommandDescription::new(NAME, |_| { build() }));
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Input {
     pub string: serde_json::Value,
+    /// Extra headers, values can reference secrets with `{{secrets.NAME}}`.
+    /// `Authorization` is always the user's JWT and can't be overridden.
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -52,6 +56,17 @@ async fn run(mut ctx: Context, input: Input) -> Result<Output, CommandError> {
 
     req = req.header(AUTHORIZATION, ctx.get_jwt_header().await?);
 
+    for (k, v) in &input.headers {
+        if k.trim().eq_ignore_ascii_case(AUTHORIZATION.as_str()) {
+            return Err(FlowError::input_validation(
+                Some("headers"),
+                "Authorization header can't be overridden",
+            )
+            .into());
+        }
+        req = req.header(k, ctx.expand_secrets(v).await?);
+    }
+
     let resp = req.send().await.map_err(|e| anyhow!("HTTP error: {}", e))?;
 
     match resp.status() {
@@ -65,3 +65,94 @@ impl Error {
         Error::Any(e.into())
     }
 }
+
+// errors returned by commands are classified with this
+inventory::submit!(flow_lib::error::ErrorClassifier::new(|e| {
+    e.downcast_ref::<Error>().map(Into::into)
+}));
+
+impl From<Error> for flow_lib::FlowError {
+    fn from(e: Error) -> Self {
+        (&e).into()
+    }
+}
+
+impl From<&Error> for flow_lib::FlowError {
+    fn from(e: &Error) -> Self {
+        use flow_lib::FlowError;
+        match e {
+            Error::Any(e) => FlowError::from_command_error(e),
+            Error::SolanaClient(e) => e.into(),
+            Error::SolanaProgram(p) => FlowError::ProgramError {
+                message: e.to_string(),
+                instruction_index: None,
+                custom_code: match p {
+                    solana_sdk::program_error::ProgramError::Custom(code) => Some(*code),
+                    _ => None,
+                },
+                program_id: None,
+                error_name: None,
+            },
+            Error::Http(e) => e.into(),
+            Error::Bundlr(_)
+            | Error::BundlrApiInvalidResponse(_)
+            | Error::BundlrTxRegisterFailed(_) => FlowError::Http {
+                message: e.to_string(),
+                status: None,
+            },
+            Error::InsufficientSolanaBalance { needed, balance } => FlowError::InsufficientFunds {
+                message: e.to_string(),
+                needed: Some(*needed),
+                balance: Some(*balance),
+            },
+            Error::SignatureTimeout => FlowError::SignatureTimeout,
+            Error::ValueNotFound(name) => FlowError::input_validation(Some(name), e),
+            Error::Signer(_)
+            | Error::Value(_)
+            | Error::AssociatedTokenAccountDoesntExist
+            | Error::BundlrNotAvailableOnTestnet
+            | Error::CantGetMnemonicFromPhrase
+            | Error::MimeTypeNotFound
+            | Error::KeypairFromSeed(_)
+            | Error::UnsupportedRecipientAddress(_)
+            | Error::RecipientAddressNotFunded
+            | Error::NotTokenAccount(_) => FlowError::input_validation(None, e),
+            Error::Json(_)
+            | Error::ErrorSnapshottingMints(_)
+            | Error::FailedToFetchMintSnapshot
+            | Error::WorkerStopped
+            | Error::RhaiExecutionError(_) => FlowError::other(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_lib::{ErrorCode, FlowError};
+
+    #[test]
+    fn test_classify() {
+        let error = anyhow::Error::new(Error::InsufficientSolanaBalance {
+            needed: 10,
+            balance: 1,
+        })
+        .context("transfer");
+        let error = FlowError::from_command_error(&error);
+        assert_eq!(error.code(), ErrorCode::InsufficientFunds);
+        assert!(matches!(
+            error,
+            FlowError::InsufficientFunds {
+                needed: Some(10),
+                balance: Some(1),
+                ..
+            }
+        ));
+
+        let error = anyhow::Error::new(Error::ValueNotFound("amount".to_owned()));
+        assert_eq!(
+            FlowError::from_command_error(&error),
+            FlowError::input_validation(Some("amount"), "value not found in field \"amount\"")
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{idl::Idl, prelude::*};
+
+const NAME: &str = "fetch_and_decode_account";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str =
+        include_str!("../../../node-definitions/solana/fetch_and_decode_account.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    #[serde(with = "value::pubkey")]
+    address: Pubkey,
+    /// IDL as JSON or a JSON string, fetched from the IDL account of the program if not set.
+    #[serde(default)]
+    idl: Option<Value>,
+    /// Program of the IDL account, defaults to the owner of the account.
+    #[serde(default, with = "value::pubkey::opt")]
+    program_id: Option<Pubkey>,
+    /// Name of the account type, found by discriminator if not set.
+    #[serde(default)]
+    account_type: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    account_type: String,
+    data: Value,
+    #[serde(with = "value::pubkey")]
+    owner: Pubkey,
+}
+
+async fn run(ctx: Context, input: Input) -> Result<Output, CommandError> {
+    let account = ctx.solana_client.get_account(&input.address).await?;
+    let idl = match input.idl {
+        None | Some(Value::Null) => {
+            let program_id = input.program_id.unwrap_or(account.owner);
+            crate::idl::fetch_idl(&ctx.solana_client, &program_id).await?
+        }
+        Some(idl) => Idl::from_value(idl)?,
+    };
+    let (account_type, data) = idl.decode_account(input.account_type.as_deref(), &account.data)?;
+    Ok(Output {
+        account_type,
+        data,
+        owner: account.owner,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        build().unwrap();
+    }
+}
@@ -31,6 +31,7 @@ pub struct Input {
     pub url: Url,
     #[serde(default = "default_method")]
     pub method: String,
+    /// Header values can reference secrets with `{{secrets.NAME}}`.
     #[serde(default)]
     pub headers: Vec<(String, String)>,
     pub basic_auth: Option<BasicAuth>,
@@ -166,7 +167,7 @@ impl Ipv6Ext for Ipv6Addr {
     }
 }
 
-async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+async fn run(ctx: Context, input: Input) -> Result<Output, CommandError> {
     match input.url.host() {
         Some(url::Host::Domain(_)) => {}
         Some(url::Host::Ipv4(ip)) => {
@@ -193,7 +194,7 @@ async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
     }
 
     for (k, v) in &input.headers {
-        req = req.header(k, v);
+        req = req.header(k, ctx.expand_secrets(v).await?);
     }
 
     if let Some(basic) = &input.basic_auth {
@@ -213,7 +214,7 @@ async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
         req = req.multipart(multiform);
     }
 
-    let resp = req.send().await?;
+    let resp = ctx.send_http(req).await?;
 
     let status = resp.status();
 
@@ -0,0 +1,1113 @@
+//! Anchor IDLs, decoding of Borsh-encoded accounts and building of instructions with them.
+//!
+//! Both the legacy format and the format of Anchor 0.30 are parsed: `{ "defined": { "name": .. } }`
+//! types, `writable`, `signer` and `optional` accounts, explicit discriminators, account types
+//! defined in `types`, fixed account addresses and PDA seeds without a type. Generic types are
+//! not supported.
+//!
+//! Decoded values:
+//! - `u8` to `u64` are [`Value::U64`], `i8` to `i64` are [`Value::I64`], `u128` and `i128` are
+//!   [`Value::U128`] and [`Value::I128`], floats are [`Value::F64`].
+//! - `publicKey` is [`Value::B32`], `bytes` is [`Value::Bytes`].
+//! - Structs are maps, tuple structs, vectors and arrays are arrays, `None` is [`Value::Null`].
+//! - Enums are maps with the variant name as the only key, and the fields of the variant as a map
+//!   (named fields) or an array (tuple fields) as the value, unit variants have an empty map.
+//!
+//! IDL types are converted to a [`Schema`], which encodes and decodes them. Encoding accepts the
+//! same values, and also integers as decimals or strings, public keys as keypairs or base58
+//! strings, bytes as arrays of integers, unit variants as strings, and fields by their snake_case
+//! names. Recursive types are not supported.
+
+use crate::{prelude::*, utils::anchor_discriminator};
+use serde_json::Value as JsonValue;
+use solana_sdk::{account::Account, instruction::AccountMeta};
+use std::{io::Read, str::FromStr};
+use thiserror::Error as ThisError;
+use value::schema::{Array, Field, Fields, Format, Schema, Variant};
+
+#[derive(ThisError, Debug)]
+pub enum IdlError {
+    #[error("unexpected end of data")]
+    UnexpectedEnd,
+    #[error("type not found in IDL: {0}")]
+    TypeNotFound(String),
+    #[error("account type not found in IDL: {0}")]
+    AccountNotFound(String),
+    #[error("discriminator {} does not match any account of the IDL", hex::encode(.0))]
+    UnknownDiscriminator([u8; 8]),
+    #[error("account is not a {0}")]
+    WrongDiscriminator(String),
+    #[error("invalid {ty}: {message}")]
+    Invalid { ty: &'static str, message: String },
+    #[error("type is not supported: {0}")]
+    Unsupported(&'static str),
+    #[error("invalid IDL: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid IDL account: {0}")]
+    IdlAccount(String),
+    #[error("value is out of range of {0}")]
+    OutOfRange(&'static str),
+    #[error("missing field {0}")]
+    MissingField(String),
+    #[error("instruction not found in IDL: {0}")]
+    InstructionNotFound(String),
+    #[error("missing account {0}")]
+    MissingAccount(String),
+    #[error(transparent)]
+    Schema(#[from] value::schema::Error),
+    #[error("{path}: {error}")]
+    At { path: String, error: Box<IdlError> },
+}
+
+impl IdlError {
+    /// Add the name of a field or an account to the path of the error.
+    pub fn at(self, name: &str) -> Self {
+        match self {
+            IdlError::At { path, error } => IdlError::At {
+                path: format!("{name}.{path}"),
+                error,
+            },
+            error => IdlError::At {
+                path: name.to_owned(),
+                error: Box::new(error),
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct Idl {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub instructions: Vec<IdlInstruction>,
+    #[serde(default)]
+    pub accounts: Vec<IdlAccountDef>,
+    #[serde(default)]
+    pub types: Vec<IdlTypeDef>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct IdlInstruction {
+    pub name: String,
+    /// Defaults to the Anchor discriminator of the instruction name.
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
+    #[serde(default)]
+    pub accounts: Vec<IdlAccountItem>,
+    #[serde(default)]
+    pub args: Vec<IdlField>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum IdlAccountItem {
+    /// A group of accounts, from a nested `Accounts` struct.
+    Accounts(IdlAccounts),
+    Account(IdlAccount),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct IdlAccounts {
+    pub name: String,
+    pub accounts: Vec<IdlAccountItem>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlAccount {
+    pub name: String,
+    #[serde(default, alias = "writable")]
+    pub is_mut: bool,
+    #[serde(default, alias = "signer")]
+    pub is_signer: bool,
+    #[serde(default, alias = "optional")]
+    pub is_optional: bool,
+    /// Fixed address of the account.
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub pda: Option<IdlPda>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlPda {
+    pub seeds: Vec<IdlSeed>,
+    /// Program of the PDA, defaults to the program of the instruction.
+    #[serde(default, alias = "program")]
+    pub program_id: Option<IdlSeed>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum IdlSeed {
+    /// Bytes if there is no type.
+    Const {
+        #[serde(rename = "type", default)]
+        ty: Option<IdlType>,
+        value: JsonValue,
+    },
+    /// An argument of the instruction, or a field of an argument, the type defaults to the type
+    /// of the argument.
+    Arg {
+        #[serde(rename = "type", default)]
+        ty: Option<IdlType>,
+        path: String,
+    },
+    /// The address of another account of the instruction.
+    Account {
+        #[serde(rename = "type", default)]
+        ty: Option<IdlType>,
+        path: String,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct IdlTypeDef {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlTypeDefTy,
+}
+
+/// Account type, Anchor 0.30 IDLs define the type in `types`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct IdlAccountDef {
+    pub name: String,
+    /// Defaults to the Anchor discriminator of the account name.
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
+    #[serde(rename = "type", default)]
+    pub ty: Option<IdlTypeDefTy>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum IdlTypeDefTy {
+    /// Fields are named, or a tuple.
+    Struct {
+        #[serde(default)]
+        fields: Option<IdlEnumFields>,
+    },
+    Enum {
+        variants: Vec<IdlEnumVariant>,
+    },
+    Alias {
+        value: IdlType,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct IdlField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlType,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct IdlEnumVariant {
+    pub name: String,
+    #[serde(default)]
+    pub fields: Option<IdlEnumFields>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum IdlEnumFields {
+    Named(Vec<IdlField>),
+    Tuple(Vec<IdlType>),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    F32,
+    U64,
+    I64,
+    F64,
+    U128,
+    I128,
+    U256,
+    I256,
+    Bytes,
+    String,
+    #[serde(alias = "pubkey")]
+    PublicKey,
+    #[serde(deserialize_with = "defined")]
+    Defined(String),
+    Option(Box<IdlType>),
+    Vec(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+}
+
+/// Name of a defined type, in the legacy format or in the format of Anchor 0.30.
+fn defined<'de, D>(d: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Defined {
+        Name(String),
+        Type {
+            name: String,
+            #[serde(default)]
+            generics: Vec<JsonValue>,
+        },
+    }
+    match Defined::deserialize(d)? {
+        Defined::Name(name) => Ok(name),
+        Defined::Type { name, generics } if generics.is_empty() => Ok(name),
+        Defined::Type { name, .. } => Err(serde::de::Error::custom(format!(
+            "generic type {name} is not supported"
+        ))),
+    }
+}
+
+impl Idl {
+    /// Parse an IDL from a JSON string or a JSON value.
+    pub fn from_value(value: Value) -> Result<Self, IdlError> {
+        Ok(match value {
+            Value::String(s) => serde_json::from_str(&s)?,
+            value => serde_json::from_value(JsonValue::from(value))?,
+        })
+    }
+
+    /// Find a type by name, in `types` or `accounts`.
+    pub fn type_def(&self, name: &str) -> Result<&IdlTypeDefTy, IdlError> {
+        self.types
+            .iter()
+            .map(|t| (&t.name, Some(&t.ty)))
+            .chain(self.accounts.iter().map(|a| (&a.name, a.ty.as_ref())))
+            .find_map(|(n, ty)| ty.filter(|_| n == name))
+            .ok_or_else(|| IdlError::TypeNotFound(name.to_owned()))
+    }
+
+    /// Type of an account, in `types` if it is not in the account.
+    pub fn account_type<'a>(
+        &'a self,
+        account: &'a IdlAccountDef,
+    ) -> Result<&'a IdlTypeDefTy, IdlError> {
+        match &account.ty {
+            Some(ty) => Ok(ty),
+            None => self.type_def(&account.name),
+        }
+    }
+
+    /// [`Schema`] of an IDL type, defined types are inlined.
+    pub fn schema(&self, ty: &IdlType) -> Result<Schema, IdlError> {
+        self.schema_of(ty, &mut Vec::new())
+    }
+
+    /// [`Schema`] of a defined type.
+    pub fn type_def_schema(&self, name: &str, ty: &IdlTypeDefTy) -> Result<Schema, IdlError> {
+        self.type_def_schema_of(ty, &mut vec![name.to_owned()])
+    }
+
+    /// `defined` are the names of the types being inlined, to detect recursive types.
+    fn schema_of(&self, ty: &IdlType, defined: &mut Vec<String>) -> Result<Schema, IdlError> {
+        Ok(match ty {
+            IdlType::Bool => Schema::Bool,
+            IdlType::U8 => Schema::U8,
+            IdlType::I8 => Schema::I8,
+            IdlType::U16 => Schema::U16,
+            IdlType::I16 => Schema::I16,
+            IdlType::U32 => Schema::U32,
+            IdlType::I32 => Schema::I32,
+            IdlType::F32 => Schema::F32,
+            IdlType::U64 => Schema::U64,
+            IdlType::I64 => Schema::I64,
+            IdlType::F64 => Schema::F64,
+            IdlType::U128 => Schema::U128,
+            IdlType::I128 => Schema::I128,
+            IdlType::U256 => return Err(IdlError::Unsupported("u256")),
+            IdlType::I256 => return Err(IdlError::Unsupported("i256")),
+            IdlType::Bytes => Schema::Bytes,
+            IdlType::String => Schema::String,
+            IdlType::PublicKey => Schema::Pubkey,
+            IdlType::Defined(name) => {
+                if defined.contains(name) {
+                    return Err(IdlError::Unsupported("recursive types"));
+                }
+                defined.push(name.clone());
+                let schema = self.type_def_schema_of(self.type_def(name)?, defined)?;
+                defined.pop();
+                schema
+            }
+            IdlType::Option(ty) => Schema::Option(Box::new(self.schema_of(ty, defined)?)),
+            IdlType::Vec(ty) => {
+                Schema::Array(Array::Dynamic(Box::new(self.schema_of(ty, defined)?)))
+            }
+            IdlType::Array(ty, len) => {
+                Schema::Array(Array::Fixed(Box::new(self.schema_of(ty, defined)?), *len))
+            }
+        })
+    }
+
+    fn type_def_schema_of(
+        &self,
+        ty: &IdlTypeDefTy,
+        defined: &mut Vec<String>,
+    ) -> Result<Schema, IdlError> {
+        Ok(match ty {
+            IdlTypeDefTy::Struct { fields } => {
+                match self.fields_schema(fields.as_ref(), defined)? {
+                    Fields::Named(fields) => Schema::Struct(fields),
+                    Fields::Tuple(schemas) => Schema::Tuple(schemas),
+                }
+            }
+            IdlTypeDefTy::Enum { variants } => Schema::Enum(
+                variants
+                    .iter()
+                    .map(|v| {
+                        Ok(Variant {
+                            name: v.name.clone(),
+                            fields: self.fields_schema(v.fields.as_ref(), defined)?,
+                        })
+                    })
+                    .collect::<Result<_, IdlError>>()?,
+            ),
+            IdlTypeDefTy::Alias { value } => self.schema_of(value, defined)?,
+        })
+    }
+
+    fn fields_schema(
+        &self,
+        fields: Option<&IdlEnumFields>,
+        defined: &mut Vec<String>,
+    ) -> Result<Fields, IdlError> {
+        Ok(match fields {
+            None => Fields::default(),
+            Some(IdlEnumFields::Named(fields)) => Fields::Named(
+                fields
+                    .iter()
+                    .map(|f| {
+                        Ok(Field {
+                            name: f.name.clone(),
+                            schema: self.schema_of(&f.ty, defined)?,
+                        })
+                    })
+                    .collect::<Result<_, IdlError>>()?,
+            ),
+            Some(IdlEnumFields::Tuple(types)) => Fields::Tuple(
+                types
+                    .iter()
+                    .map(|ty| self.schema_of(ty, defined))
+                    .collect::<Result<_, _>>()?,
+            ),
+        })
+    }
+
+    /// Type of argument `path` of an instruction, a field of an argument is separated by `.`.
+    fn arg_type<'a>(&'a self, mut fields: &'a [IdlField], path: &str) -> Result<IdlType, IdlError> {
+        let mut segments = path.split('.').peekable();
+        while let Some(name) = segments.next() {
+            let field = fields
+                .iter()
+                .find(|f| f.name == name || snake_case(&f.name) == name)
+                .ok_or_else(|| IdlError::MissingField(path.to_owned()))?;
+            if segments.peek().is_none() {
+                return Ok(field.ty.clone());
+            }
+            fields = match &field.ty {
+                IdlType::Defined(name) => match self.type_def(name)? {
+                    IdlTypeDefTy::Struct {
+                        fields: Some(IdlEnumFields::Named(fields)),
+                    } => fields,
+                    _ => return Err(IdlError::MissingField(path.to_owned())),
+                },
+                _ => return Err(IdlError::MissingField(path.to_owned())),
+            };
+        }
+        Err(IdlError::MissingField(path.to_owned()))
+    }
+
+    pub fn instruction(&self, name: &str) -> Result<&IdlInstruction, IdlError> {
+        self.instructions
+            .iter()
+            .find(|i| i.name == name || snake_case(&i.name) == name)
+            .ok_or_else(|| IdlError::InstructionNotFound(name.to_owned()))
+    }
+
+    /// Build instruction `name` of `program_id`, its data is the discriminator of the instruction
+    /// followed by the Borsh-encoded `args`.
+    ///
+    /// Accounts are read from `accounts` by name, accounts of a nested group can be in a map
+    /// named after the group. Accounts that are not set are derived from the PDA seeds of the
+    /// IDL, or are well-known programs and sysvars. Keypairs of signer accounts are returned as
+    /// signers.
+    pub fn build_instruction(
+        &self,
+        program_id: Pubkey,
+        name: &str,
+        args: &value::Map,
+        accounts: &value::Map,
+    ) -> Result<(Instruction, Vec<Keypair>), IdlError> {
+        let ix = self.instruction(name)?;
+        let discriminator = match &ix.discriminator {
+            Some(discriminator) => discriminator.clone(),
+            None => anchor_discriminator("global", &snake_case(&ix.name)).to_vec(),
+        };
+        let schema = match self.fields_schema(
+            Some(&IdlEnumFields::Named(ix.args.clone())),
+            &mut Vec::new(),
+        )? {
+            Fields::Named(fields) => Schema::Struct(fields),
+            Fields::Tuple(_) => unreachable!(),
+        };
+        let mut data = discriminator;
+        let value = idl_names(&schema, &Value::Map(args.clone()));
+        data.extend(schema.encode(Format::Borsh, &value)?);
+
+        let mut flat = Vec::new();
+        flatten(&ix.accounts, accounts, &mut flat);
+        let mut keys = vec![None; flat.len()];
+        let mut signers = Vec::new();
+        for ((account, value), key) in flat.iter().zip(&mut keys) {
+            *key = match value {
+                Some(value) => {
+                    let (pubkey, keypair) = account_key(value).map_err(|e| e.at(&account.name))?;
+                    if account.is_signer {
+                        signers.extend(keypair);
+                    }
+                    Some(pubkey)
+                }
+                None if account.address.is_some() => {
+                    let address = account.address.as_deref().unwrap_or_default();
+                    Some(Pubkey::from_str(address).map_err(|e| {
+                        IdlError::Invalid {
+                            ty: "public key",
+                            message: e.to_string(),
+                        }
+                        .at(&account.name)
+                    })?)
+                }
+                None if account.pda.is_some() => None,
+                // missing optional accounts are replaced by the program
+                None if account.is_optional => Some(program_id),
+                None => Some(
+                    well_known(&account.name)
+                        .ok_or_else(|| IdlError::MissingAccount(account.name.clone()))?,
+                ),
+            };
+        }
+        // seeds of a PDA can be other PDAs
+        loop {
+            let mut progress = false;
+            let mut error = None;
+            for i in 0..flat.len() {
+                let (account, _) = &flat[i];
+                let (Some(pda), None) = (&account.pda, keys[i]) else {
+                    continue;
+                };
+                match self.find_pda(program_id, pda, &ix.args, args, &flat, &keys) {
+                    Ok(key) => {
+                        keys[i] = Some(key);
+                        progress = true;
+                    }
+                    Err(e) => error = error.or(Some(e.at(&account.name))),
+                }
+            }
+            match error {
+                None => break,
+                Some(error) if !progress => return Err(error),
+                Some(_) => {}
+            }
+        }
+
+        let accounts = flat
+            .iter()
+            .zip(keys)
+            .map(|((account, value), key)| {
+                let pubkey = key.unwrap();
+                if value.is_none() && account.is_optional && account.pda.is_none() {
+                    AccountMeta::new_readonly(pubkey, false)
+                } else if account.is_mut {
+                    AccountMeta::new(pubkey, account.is_signer)
+                } else {
+                    AccountMeta::new_readonly(pubkey, account.is_signer)
+                }
+            })
+            .collect();
+        let instruction = Instruction {
+            program_id,
+            accounts,
+            data,
+        };
+        Ok((instruction, signers))
+    }
+
+    fn find_pda(
+        &self,
+        program_id: Pubkey,
+        pda: &IdlPda,
+        arg_fields: &[IdlField],
+        args: &value::Map,
+        flat: &[(&IdlAccount, Option<&Value>)],
+        keys: &[Option<Pubkey>],
+    ) -> Result<Pubkey, IdlError> {
+        let seed = |seed: &IdlSeed| -> Result<Vec<u8>, IdlError> {
+            let (ty, value) = match seed {
+                IdlSeed::Const { ty, value } => (
+                    ty.clone().unwrap_or(IdlType::Bytes),
+                    Value::from(value.clone()),
+                ),
+                IdlSeed::Arg { ty, path } => {
+                    let mut segments = path.split('.');
+                    let mut value = segments.next().and_then(|name| get(args, name));
+                    for name in segments {
+                        value = match value {
+                            Some(Value::Map(map)) => get(map, name),
+                            _ => None,
+                        };
+                    }
+                    let value = value.ok_or_else(|| IdlError::MissingField(path.clone()))?;
+                    let ty = match ty {
+                        Some(ty) => ty.clone(),
+                        None => self.arg_type(arg_fields, path)?,
+                    };
+                    (ty, value.clone())
+                }
+                IdlSeed::Account { path, .. } if path.contains('.') => {
+                    return Err(IdlError::Unsupported("seeds from account data"))
+                }
+                IdlSeed::Account { path, .. } => {
+                    let key = flat
+                        .iter()
+                        .zip(keys)
+                        .find(|((a, _), _)| a.name == *path)
+                        .and_then(|(_, key)| *key)
+                        .ok_or_else(|| IdlError::MissingAccount(path.clone()))?;
+                    return Ok(key.to_bytes().to_vec());
+                }
+            };
+            let schema = self.schema(&ty)?;
+            let mut data = schema.encode(Format::Borsh, &idl_names(&schema, &value))?;
+            // strings and bytes are used without length
+            if matches!(ty, IdlType::String | IdlType::Bytes) {
+                data.drain(..4);
+            }
+            Ok(data)
+        };
+        let seeds = pda.seeds.iter().map(seed).collect::<Result<Vec<_>, _>>()?;
+        let program_id = match &pda.program_id {
+            None => program_id,
+            Some(program) => {
+                let bytes = seed(program)?;
+                Pubkey::try_from(bytes.as_slice())
+                    .map_err(|_| IdlError::OutOfRange("program ID"))?
+            }
+        };
+        let seeds = seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        Ok(Pubkey::find_program_address(&seeds, &program_id).0)
+    }
+
+    /// Decode account data, with the account type `name` or the one matching the discriminator.
+    /// Returns the name of the account type and the decoded value, bytes after the account are
+    /// ignored.
+    pub fn decode_account(
+        &self,
+        name: Option<&str>,
+        data: &[u8],
+    ) -> Result<(String, Value), IdlError> {
+        let def = match name {
+            Some(name) => {
+                let def = self
+                    .accounts
+                    .iter()
+                    .find(|a| a.name == name)
+                    .ok_or_else(|| IdlError::AccountNotFound(name.to_owned()))?;
+                if !data.starts_with(&account_discriminator(def)) {
+                    return Err(IdlError::WrongDiscriminator(name.to_owned()));
+                }
+                def
+            }
+            None => self
+                .accounts
+                .iter()
+                .find(|a| data.starts_with(&account_discriminator(a)))
+                .ok_or_else(|| match data.get(..8) {
+                    Some(prefix) => IdlError::UnknownDiscriminator(prefix.try_into().unwrap()),
+                    None => IdlError::UnexpectedEnd,
+                })?,
+        };
+        let schema = self.type_def_schema(&def.name, self.account_type(def)?)?;
+        let data = &data[account_discriminator(def).len()..];
+        let (value, _) = schema.decode_prefix(Format::Borsh, data)?;
+        Ok((def.name.clone(), value))
+    }
+}
+
+fn account_discriminator(account: &IdlAccountDef) -> Vec<u8> {
+    match &account.discriminator {
+        Some(discriminator) => discriminator.clone(),
+        None => anchor_discriminator("account", &account.name).to_vec(),
+    }
+}
+
+fn pubkey(value: &Value) -> Result<Pubkey, IdlError> {
+    let bytes = Schema::Pubkey.encode(Format::Borsh, value)?;
+    Ok(Pubkey::try_from(bytes.as_slice()).unwrap())
+}
+
+/// Public key of an account, and its keypair if it is one.
+fn account_key(value: &Value) -> Result<(Pubkey, Option<Keypair>), IdlError> {
+    match value {
+        Value::B64(bytes) => {
+            let keypair = Keypair::from_bytes(bytes).map_err(|e| IdlError::Invalid {
+                ty: "keypair",
+                message: e.to_string(),
+            })?;
+            Ok((keypair.pubkey(), Some(keypair)))
+        }
+        value => Ok((pubkey(value)?, None)),
+    }
+}
+
+/// Rename fields given by their snake_case names to their names in the IDL.
+fn idl_names(schema: &Schema, value: &Value) -> Value {
+    let fields = |fields: &[Field], map: &value::Map| {
+        let map = map
+            .iter()
+            .map(|(key, value)| {
+                match fields
+                    .iter()
+                    .find(|f| f.name == *key || snake_case(&f.name) == *key)
+                {
+                    Some(field) => (field.name.clone(), idl_names(&field.schema, value)),
+                    None => (key.clone(), value.clone()),
+                }
+            })
+            .collect();
+        Value::Map(map)
+    };
+    let tuple = |schemas: &[Schema], values: &[Value]| {
+        let values = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| match schemas.get(i) {
+                Some(schema) => idl_names(schema, value),
+                None => value.clone(),
+            })
+            .collect();
+        Value::Array(values)
+    };
+    match (schema, value) {
+        (Schema::Struct(named), Value::Map(map)) => fields(named, map),
+        (Schema::Tuple(schemas), Value::Array(values)) => tuple(schemas, values),
+        (Schema::Option(schema), value) => idl_names(schema, value),
+        (Schema::Array(Array::Fixed(schema, _) | Array::Dynamic(schema)), Value::Array(values)) => {
+            Value::Array(values.iter().map(|v| idl_names(schema, v)).collect())
+        }
+        (Schema::Enum(variants), Value::Map(map)) if map.len() == 1 => {
+            let (name, value) = map.first().unwrap();
+            let value = match (variants.iter().find(|v| v.name == *name), value) {
+                (Some(v), Value::Map(map)) => match &v.fields {
+                    Fields::Named(named) => fields(named, map),
+                    Fields::Tuple(_) => value.clone(),
+                },
+                (Some(v), Value::Array(values)) => match &v.fields {
+                    Fields::Tuple(schemas) => tuple(schemas, values),
+                    Fields::Named(_) => value.clone(),
+                },
+                _ => value.clone(),
+            };
+            Value::Map([(name.clone(), value)].into_iter().collect())
+        }
+        _ => value.clone(),
+    }
+}
+
+/// Convert a camelCase name of an IDL to snake_case.
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut s = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            if !prev.is_uppercase() || next_lower {
+                s.push('_');
+            }
+        }
+        s.extend(c.to_lowercase());
+    }
+    s
+}
+
+/// Get a field by its name in the IDL, or its snake_case name.
+fn get<'a>(map: &'a value::Map, name: &str) -> Option<&'a Value> {
+    map.get(name).or_else(|| map.get(&snake_case(name)))
+}
+
+/// List the accounts of an instruction with their values.
+fn flatten<'a>(
+    items: &'a [IdlAccountItem],
+    values: &'a value::Map,
+    out: &mut Vec<(&'a IdlAccount, Option<&'a Value>)>,
+) {
+    for item in items {
+        match item {
+            IdlAccountItem::Account(account) => out.push((account, get(values, &account.name))),
+            IdlAccountItem::Accounts(group) => {
+                let values = match get(values, &group.name) {
+                    Some(Value::Map(map)) => map,
+                    _ => values,
+                };
+                flatten(&group.accounts, values, out);
+            }
+        }
+    }
+}
+
+fn well_known(name: &str) -> Option<Pubkey> {
+    Some(match snake_case(name).as_str() {
+        "system_program" => solana_sdk::system_program::ID,
+        "token_program" => spl_token::ID,
+        "associated_token_program" => spl_associated_token_account::ID,
+        "rent" => solana_sdk::sysvar::rent::ID,
+        "clock" => solana_sdk::sysvar::clock::ID,
+        _ => return None,
+    })
+}
+
+/// Address of the account storing the IDL of an Anchor program.
+pub fn idl_address(program_id: &Pubkey) -> Pubkey {
+    let base = Pubkey::find_program_address(&[], program_id).0;
+    Pubkey::create_with_seed(&base, "anchor:idl", program_id).expect("seed is valid")
+}
+
+/// Maximum length of the decompressed JSON of an IDL account.
+const MAX_IDL_JSON_LEN: u64 = 16 * 1024 * 1024;
+
+/// Parse the IDL account of a program: discriminator, authority, length of the data, and
+/// zlib-compressed JSON of at most [`MAX_IDL_JSON_LEN`] bytes.
+pub fn parse_idl_account(data: &[u8]) -> Result<Idl, IdlError> {
+    let data = data.get(40..).ok_or(IdlError::UnexpectedEnd)?;
+    let compressed = match Schema::Bytes.decode_prefix(Format::Borsh, data)? {
+        (Value::Bytes(bytes), _) => bytes,
+        _ => unreachable!(),
+    };
+    let mut json = Vec::new();
+    flate2::read::ZlibDecoder::new(&compressed[..])
+        .take(MAX_IDL_JSON_LEN + 1)
+        .read_to_end(&mut json)
+        .map_err(|e| IdlError::IdlAccount(e.to_string()))?;
+    if json.len() as u64 > MAX_IDL_JSON_LEN {
+        return Err(IdlError::IdlAccount(format!(
+            "JSON is longer than {MAX_IDL_JSON_LEN} bytes"
+        )));
+    }
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// Fetch the IDL of an Anchor program from its IDL account.
+pub async fn fetch_idl(client: &RpcClient, program_id: &Pubkey) -> crate::Result<Idl> {
+    let address = idl_address(program_id);
+    let account: Account = client.get_account(&address).await?;
+    parse_idl_account(&account.data).map_err(crate::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::ZlibEncoder, Compression};
+    use serde_json::json;
+    use std::io::Write;
+
+    fn idl() -> Idl {
+        serde_json::from_value(json!({
+            "version": "0.1.0",
+            "name": "test",
+            "instructions": [],
+            "accounts": [{
+                "name": "Counter",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        { "name": "authority", "type": "publicKey" },
+                        { "name": "count", "type": "u64" },
+                        { "name": "delta", "type": "i16" },
+                        { "name": "kind", "type": { "defined": "Kind" } },
+                        { "name": "items", "type": { "vec": "u16" } },
+                        { "name": "label", "type": { "option": "string" } },
+                        { "name": "pair", "type": { "array": ["bool", 2] } },
+                    ]
+                }
+            }],
+            "types": [{
+                "name": "Kind",
+                "type": {
+                    "kind": "enum",
+                    "variants": [
+                        { "name": "Empty" },
+                        { "name": "Named", "fields": [{ "name": "x", "type": "u8" }] },
+                        { "name": "Tuple", "fields": ["u8", "u128"] },
+                    ]
+                }
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decode_account() {
+        let mut data = anchor_discriminator("account", "Counter").to_vec();
+        data.extend([7; 32]);
+        data.extend(10u64.to_le_bytes());
+        data.extend((-2i16).to_le_bytes());
+        data.extend([2, 1]);
+        data.extend(5u128.to_le_bytes());
+        data.extend(2u32.to_le_bytes());
+        data.extend([1, 0, 2, 0]);
+        data.extend([0]);
+        data.extend([1, 0]);
+        // padding
+        data.extend([0; 16]);
+
+        let idl = idl();
+        let (name, value) = idl.decode_account(None, &data).unwrap();
+        assert_eq!(name, "Counter");
+        assert_eq!(
+            value,
+            value::map! {
+                "authority" => Value::B32([7; 32]),
+                "count" => 10u64,
+                "delta" => -2i64,
+                "kind" => value::map! {
+                    "Tuple" => vec![Value::U64(1), Value::U128(5)],
+                },
+                "items" => vec![Value::U64(1), Value::U64(2)],
+                "label" => Value::Null,
+                "pair" => vec![Value::Bool(true), Value::Bool(false)],
+            }
+            .into()
+        );
+        let ty = idl.account_type(&idl.accounts[0]).unwrap();
+        let schema = idl.type_def_schema("Counter", ty).unwrap();
+        let encoded = schema.encode(Format::Borsh, &value).unwrap();
+        assert_eq!(encoded, data[8..data.len() - 16]);
+
+        assert!(idl.decode_account(Some("Counter"), &data).is_ok());
+        assert!(matches!(
+            idl.decode_account(Some("Other"), &data),
+            Err(IdlError::AccountNotFound(_))
+        ));
+        assert!(matches!(
+            idl.decode_account(None, &data[8..]),
+            Err(IdlError::UnknownDiscriminator(_))
+        ));
+        assert!(matches!(
+            idl.decode_account(None, &data[..20]),
+            Err(IdlError::Schema(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_instruction() {
+        let mut idl = idl();
+        idl.instructions = serde_json::from_value(json!([{
+            "name": "createUser",
+            "accounts": [
+                {
+                    "name": "user",
+                    "isMut": true,
+                    "isSigner": false,
+                    "pda": {
+                        "seeds": [
+                            { "kind": "const", "type": "string", "value": "user" },
+                            { "kind": "account", "type": "publicKey", "path": "authority" },
+                            { "kind": "arg", "type": "u64", "path": "amount" },
+                        ]
+                    }
+                },
+                { "name": "authority", "isMut": true, "isSigner": true },
+                {
+                    "name": "extra",
+                    "accounts": [{ "name": "vault", "isMut": false, "isSigner": false }]
+                },
+                { "name": "referrer", "isMut": false, "isSigner": false, "isOptional": true },
+                { "name": "systemProgram", "isMut": false, "isSigner": false },
+            ],
+            "args": [
+                { "name": "amount", "type": "u64" },
+                { "name": "kind", "type": { "defined": "Kind" } },
+                { "name": "memo", "type": { "option": "string" } },
+            ]
+        }]))
+        .unwrap();
+        let program_id = Pubkey::new_unique();
+        let authority = Keypair::new();
+        let vault = Pubkey::new_unique();
+        let accounts = value::map! {
+            "authority" => authority.clone_keypair(),
+            "extra" => value::map! { "vault" => vault },
+        };
+        let args = value::map! {
+            "amount" => "10",
+            "kind" => value::map! { "Named" => value::map! { "x" => 3u64 } },
+        };
+        let (ix, signers) = idl
+            .build_instruction(program_id, "createUser", &args, &accounts)
+            .unwrap();
+
+        let mut data = anchor_discriminator("global", "create_user").to_vec();
+        data.extend(10u64.to_le_bytes());
+        data.extend([1, 3, 0]);
+        assert_eq!(ix.data, data);
+        let seeds = [
+            &b"user"[..],
+            &authority.pubkey().to_bytes(),
+            &10u64.to_le_bytes(),
+        ];
+        let user = Pubkey::find_program_address(&seeds, &program_id).0;
+        assert_eq!(
+            ix.accounts,
+            [
+                AccountMeta::new(user, false),
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new_readonly(vault, false),
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+            ]
+        );
+        assert_eq!(signers.len(), 1);
+        assert_eq!(signers[0].pubkey(), authority.pubkey());
+
+        let args = value::map! {
+            "amount" => 10u64,
+            "kind" => value::map! { "Tuple" => vec![Value::from(1u64), Value::from("x")] },
+        };
+        let error = idl
+            .build_instruction(program_id, "create_user", &args, &accounts)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "kind.Tuple.1: expected u128, found string"
+        );
+
+        let args = value::map! { "amount" => 10u64 };
+        let error = idl
+            .build_instruction(program_id, "createUser", &args, &ValueSet::new())
+            .unwrap_err();
+        assert_eq!(error.to_string(), "kind: missing field kind");
+    }
+
+    #[test]
+    fn test_anchor_0_30() {
+        let idl: Idl = serde_json::from_value(json!({
+            "address": "11111111111111111111111111111111",
+            "metadata": { "name": "test", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [{
+                "name": "deposit",
+                "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                "accounts": [
+                    { "name": "vault", "writable": true, "pda": { "seeds": [
+                        { "kind": "const", "value": [118, 97, 117, 108, 116] },
+                        { "kind": "arg", "path": "params.id" },
+                    ] } },
+                    { "name": "owner", "writable": true, "signer": true },
+                    { "name": "referrer", "optional": true },
+                    { "name": "token_program", "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" },
+                ],
+                "args": [{ "name": "params", "type": { "defined": { "name": "Params" } } }],
+            }],
+            "accounts": [{ "name": "Vault", "discriminator": [9, 9, 9, 9, 9, 9, 9, 9] }],
+            "types": [
+                {
+                    "name": "Params",
+                    "type": { "kind": "struct", "fields": [{ "name": "id", "type": "u16" }] },
+                },
+                {
+                    "name": "Vault",
+                    "type": { "kind": "struct", "fields": ["pubkey", { "vec": { "defined": { "name": "Empty" } } }] },
+                },
+                { "name": "Empty", "type": { "kind": "struct", "fields": [] } },
+            ],
+        }))
+        .unwrap();
+
+        let program_id = Pubkey::new_unique();
+        let owner = Keypair::new();
+        let accounts = value::map! { "owner" => owner.pubkey() };
+        let args = value::map! { "params" => value::map! { "id" => 7u64 } };
+        let (ix, _) = idl
+            .build_instruction(program_id, "deposit", &args, &accounts)
+            .unwrap();
+        assert_eq!(ix.data, [1, 2, 3, 4, 5, 6, 7, 8, 7, 0]);
+        let seeds = [&b"vault"[..], &7u16.to_le_bytes()];
+        let vault = Pubkey::find_program_address(&seeds, &program_id).0;
+        assert_eq!(
+            ix.accounts,
+            [
+                AccountMeta::new(vault, false),
+                AccountMeta::new(owner.pubkey(), true),
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ]
+        );
+
+        let mut data = vec![9; 8];
+        data.extend([7; 32]);
+        data.extend(2u32.to_le_bytes());
+        // padding
+        data.extend([0; 16]);
+        let (name, value) = idl.decode_account(None, &data).unwrap();
+        assert_eq!(name, "Vault");
+        let empty = Value::Map(value::Map::new());
+        assert_eq!(
+            value,
+            Value::Array(vec![
+                Value::B32([7; 32]),
+                Value::Array(vec![empty.clone(), empty])
+            ])
+        );
+
+        // an array of empty structs can not be longer than the data
+        data[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+        let error = idl.decode_account(None, &data).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "1: invalid array: 4294967295 elements of 0 bytes"
+        );
+
+        let generic = json!({ "name": "x", "type": { "defined": { "name": "T", "generics": [{ "kind": "type", "type": "u8" }] } } });
+        assert!(serde_json::from_value::<IdlField>(generic).is_err());
+    }
+
+    #[test]
+    fn test_idl_account() {
+        let json = serde_json::to_vec(&idl()).unwrap();
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&json).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut data = [0; 40].to_vec();
+        data.extend((compressed.len() as u32).to_le_bytes());
+        data.extend(compressed);
+        assert_eq!(parse_idl_account(&data).unwrap(), idl());
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&vec![b' '; MAX_IDL_JSON_LEN as usize + 1])
+            .unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut data = [0; 40].to_vec();
+        data.extend((compressed.len() as u32).to_le_bytes());
+        data.extend(compressed);
+        assert_eq!(
+            parse_idl_account(&data).unwrap_err().to_string(),
+            "invalid IDL account: JSON is longer than 16777216 bytes"
+        );
+
+        let inline = Value::String(String::from_utf8(json).unwrap());
+        assert_eq!(Idl::from_value(inline).unwrap(), idl());
+    }
+}
@@ -0,0 +1,120 @@
+use crate::prelude::*;
+use flow_lib::{config::client::NodeData, runner::run_interflow, FlowError, FlowId};
+use serde_json::Value as JsonValue;
+
+pub const INTERFLOW: &str = "interflow";
+
+// Input
+pub const FLOW: &str = "flow";
+
+/// Run another flow. Inputs and outputs of the node are inputs and outputs of the flow, named by
+/// labels of its `flow_input` and `flow_output` nodes.
+///
+/// The flow ID is read from the `flow` input, or from `id` of the form data.
+#[derive(Debug)]
+pub struct Interflow {
+    form_data: JsonValue,
+    inputs: Vec<CmdInput>,
+    outputs: Vec<CmdOutput>,
+}
+
+impl Interflow {
+    fn new(nd: &NodeData) -> Self {
+        Self {
+            form_data: nd.targets_form.form_data.clone(),
+            inputs: flow_inputs(nd),
+            outputs: nd
+                .sources
+                .iter()
+                .map(|s| CmdOutput {
+                    name: s.name.clone(),
+                    r#type: s.r#type.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Inputs of the sub-flow and the `flow` input.
+pub(crate) fn flow_inputs(nd: &NodeData) -> Vec<CmdInput> {
+    let flow = CmdInput {
+        name: FLOW.into(),
+        type_bounds: [ValueType::String].to_vec(),
+        required: false,
+        passthrough: false,
+    };
+    std::iter::once(flow)
+        .chain(
+            nd.targets
+                .iter()
+                .filter(|t| t.name != FLOW)
+                .map(|t| CmdInput {
+                    name: t.name.clone(),
+                    type_bounds: t.type_bounds.clone(),
+                    required: false,
+                    passthrough: false,
+                }),
+        )
+        .collect()
+}
+
+/// Remove the `flow` input and parse it, fallback to `id` of the form data.
+pub(crate) fn take_flow_id(
+    form_data: &JsonValue,
+    inputs: &mut ValueSet,
+) -> Result<FlowId, CommandError> {
+    let invalid = || FlowError::input_validation(Some(FLOW), "invalid flow ID");
+    let id = match inputs.remove(FLOW) {
+        Some(Value::String(s)) => s.trim().parse().map_err(|_| invalid())?,
+        Some(Value::U64(id)) => id.try_into().map_err(|_| invalid())?,
+        Some(Value::I64(id)) => id.try_into().map_err(|_| invalid())?,
+        Some(Value::Null) | None => match form_data.get("id").and_then(JsonValue::as_i64) {
+            Some(id) => id.try_into().map_err(|_| invalid())?,
+            None => return Err(FlowError::input_validation(Some(FLOW), "missing flow ID").into()),
+        },
+        Some(_) => return Err(invalid().into()),
+    };
+    Ok(id)
+}
+
+#[async_trait]
+impl CommandTrait for Interflow {
+    fn name(&self) -> Name {
+        INTERFLOW.into()
+    }
+
+    fn inputs(&self) -> Vec<CmdInput> {
+        self.inputs.clone()
+    }
+
+    fn outputs(&self) -> Vec<CmdOutput> {
+        self.outputs.clone()
+    }
+
+    async fn run(&self, ctx: Context, mut inputs: ValueSet) -> Result<ValueSet, CommandError> {
+        let flow_id = take_flow_id(&self.form_data, &mut inputs)?;
+        let result = run_interflow(&ctx, flow_id, inputs, None).await?;
+        Ok(result.output)
+    }
+}
+
+inventory::submit!(CommandDescription::new(INTERFLOW, |nd| {
+    Ok(Box::new(Interflow::new(nd)))
+}));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_flow_id() {
+        let mut inputs = value::map! { FLOW => "12", "a" => 1u64 };
+        assert_eq!(take_flow_id(&json!({}), &mut inputs).unwrap(), 12);
+        assert_eq!(inputs.len(), 1);
+
+        let mut inputs = ValueSet::new();
+        assert_eq!(take_flow_id(&json!({ "id": 3 }), &mut inputs).unwrap(), 3);
+        assert!(take_flow_id(&json!({}), &mut inputs).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{
+    interflow::{flow_inputs, take_flow_id},
+    prelude::*,
+};
+use flow_lib::{config::client::NodeData, context::execute, runner::run_interflow, FlowError};
+use serde_json::Value as JsonValue;
+
+pub const INTERFLOW_INSTRUCTIONS: &str = "interflow_instructions";
+
+// Outputs
+const FEE_PAYER: &str = "fee_payer";
+const SIGNERS: &str = "signers";
+const INSTRUCTIONS: &str = "instructions";
+
+/// Run another flow without submitting its transactions, output the instructions of all
+/// transactions so that they can be submitted in one transaction.
+///
+/// All nodes of the sub-flow must use the same fee payer.
+#[derive(Debug)]
+pub struct InterflowInstructions {
+    form_data: JsonValue,
+    inputs: Vec<CmdInput>,
+}
+
+impl InterflowInstructions {
+    fn new(nd: &NodeData) -> Self {
+        Self {
+            form_data: nd.targets_form.form_data.clone(),
+            inputs: flow_inputs(nd),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandTrait for InterflowInstructions {
+    fn name(&self) -> Name {
+        INTERFLOW_INSTRUCTIONS.into()
+    }
+
+    fn inputs(&self) -> Vec<CmdInput> {
+        self.inputs.clone()
+    }
+
+    fn outputs(&self) -> Vec<CmdOutput> {
+        [
+            CmdOutput {
+                name: FEE_PAYER.into(),
+                r#type: ValueType::Keypair,
+            },
+            CmdOutput {
+                name: SIGNERS.into(),
+                r#type: ValueType::Array,
+            },
+            CmdOutput {
+                name: INSTRUCTIONS.into(),
+                r#type: ValueType::Array,
+            },
+        ]
+        .to_vec()
+    }
+
+    async fn run(&self, ctx: Context, mut inputs: ValueSet) -> Result<ValueSet, CommandError> {
+        let flow_id = take_flow_id(&self.form_data, &mut inputs)?;
+        let (svc, collected) = execute::collect(1);
+        run_interflow(&ctx, flow_id, inputs, Some(svc)).await?;
+
+        let ins = collected
+            .take()
+            .ok_or_else(|| FlowError::other(format!("flow {flow_id} has no instructions")))?;
+        let fee_payer = ins
+            .signers
+            .iter()
+            .find(|k| k.pubkey() == ins.fee_payer)
+            .ok_or_else(|| FlowError::other("fee payer is not a signer"))?
+            .clone_keypair();
+        let signers = ins
+            .signers
+            .iter()
+            .map(|k| Value::from(k.clone_keypair()))
+            .collect::<Vec<_>>();
+
+        Ok(value::map! {
+            FEE_PAYER => fee_payer,
+            SIGNERS => signers,
+            INSTRUCTIONS => value::to_value(&ins.instructions)?,
+        })
+    }
+}
+
+inventory::submit!(CommandDescription::new(INTERFLOW_INSTRUCTIONS, |nd| {
+    Ok(Box::new(InterflowInstructions::new(nd)))
+}));
@@ -1,9 +1,11 @@
+pub mod anchor_instruction;
 pub mod associated_token_account;
 // pub mod clockwork;
 // pub mod compression;
 pub mod create_mint_account;
 pub mod create_token_account;
 pub mod error;
+pub mod fetch_and_decode_account;
 pub mod find_pda;
 pub mod generate_keypair;
 pub mod get_balance;
@@ -12,6 +14,9 @@ pub mod mint_token;
 pub mod nft;
 // pub mod proxy_authority;
 pub mod http_request;
+pub mod idl;
+pub mod interflow;
+pub mod interflow_instructions;
 pub mod request_airdrop;
 pub mod std;
 pub mod transfer_sol;
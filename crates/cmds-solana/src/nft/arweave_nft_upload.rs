@@ -136,19 +136,28 @@ impl CommandTrait for ArweaveNftUpload {
         )?;
 
         if fund_bundlr {
+            ctx.check_canceled()?;
+            // not cancelable, a transfer that is sent must be registered with bundlr
             uploader.lazy_fund_metadata(&metadata, &ctx).await?;
         }
 
-        metadata.image = uploader.upload_file(ctx.clone(), &metadata.image).await?;
+        ctx.check_canceled()?;
+        metadata.image = ctx
+            .cancelable(uploader.upload_file(ctx.clone(), &metadata.image))
+            .await??;
 
         if let Some(properties) = metadata.properties.as_mut() {
             if let Some(files) = properties.files.as_mut() {
                 for file in files.iter_mut() {
-                    file.uri = uploader.upload_file(ctx.clone(), &file.uri).await?;
+                    ctx.check_canceled()?;
+                    file.uri = ctx
+                        .cancelable(uploader.upload_file(ctx.clone(), &file.uri))
+                        .await??;
                 }
             }
         }
 
+        ctx.check_canceled()?;
         let metadata_url = uploader
             .upload(
                 ctx,
@@ -258,6 +258,8 @@ impl From<BotTax> for mpl_candy_guard::guards::BotTax {
 /// Guard that sets a specific date for the mint to stop.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct EndDate {
+    /// Unix seconds, can be given as an RFC 3339 string.
+    #[serde(with = "value::timestamp::unix")]
     pub date: i64,
 }
 
@@ -499,6 +501,8 @@ impl From<SolPayment> for mpl_candy_guard::guards::SolPayment {
 /// Guard that sets a specific start date for the mint.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct StartDate {
+    /// Unix seconds, can be given as an RFC 3339 string.
+    #[serde(with = "value::timestamp::unix")]
     pub date: i64,
 }
 
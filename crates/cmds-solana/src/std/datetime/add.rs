@@ -0,0 +1,81 @@
+use crate::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+
+const NAME: &str = "datetime_add";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str =
+        include_str!("../../../../../node-definitions/std/datetime/datetime_add.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    #[serde(with = "value::timestamp")]
+    timestamp: DateTime<Utc>,
+    /// Negative to subtract.
+    #[serde(with = "value::duration")]
+    duration: Duration,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    #[serde(with = "value::timestamp")]
+    timestamp: DateTime<Utc>,
+    unix: i64,
+}
+
+async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+    let timestamp = input
+        .timestamp
+        .checked_add_signed(input.duration)
+        .ok_or_else(|| anyhow::anyhow!("timestamp out of range"))?;
+    Ok(Output {
+        unix: timestamp.timestamp(),
+        timestamp,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        build().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let output = run(
+            Context::default(),
+            value::from_map(value::map! {
+                "timestamp" => "2024-01-31T12:00:00Z",
+                "duration" => "1d 1h 30m",
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            value::timestamp::format(&output.timestamp),
+            "2024-02-01T13:30:00Z"
+        );
+
+        let output = run(
+            Context::default(),
+            value::from_map(value::map! {
+                "timestamp" => 1_706_702_400u64,
+                "duration" => Value::I64(-60),
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.unix, 1_706_702_340);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+
+const NAME: &str = "datetime_diff";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str =
+        include_str!("../../../../../node-definitions/std/datetime/datetime_diff.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    #[serde(with = "value::timestamp")]
+    start: DateTime<Utc>,
+    #[serde(with = "value::timestamp")]
+    end: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    /// Negative if `end` is before `start`.
+    #[serde(with = "value::duration")]
+    duration: Duration,
+    seconds: i64,
+}
+
+async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+    let duration = input.end - input.start;
+    Ok(Output {
+        seconds: duration.num_seconds(),
+        duration,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        build().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let output = run(
+            Context::default(),
+            value::from_map(value::map! {
+                "start" => "2024-01-31T12:00:00Z",
+                "end" => "2024-01-30T10:59:30Z",
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.seconds, -90_030);
+        assert_eq!(value::duration::format(&output.duration), "-1d 1h 30s");
+    }
+}
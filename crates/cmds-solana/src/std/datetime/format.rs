@@ -0,0 +1,102 @@
+use crate::prelude::*;
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, FixedOffset, Utc,
+};
+use flow_lib::FlowError;
+
+const NAME: &str = "datetime_format";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str =
+        include_str!("../../../../../node-definitions/std/datetime/datetime_format.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+// Inputs
+const FORMAT: &str = "format";
+const UTC_OFFSET: &str = "utc_offset";
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    #[serde(with = "value::timestamp")]
+    timestamp: DateTime<Utc>,
+    /// `strftime` format, RFC 3339 if empty.
+    #[serde(default)]
+    format: String,
+    /// Offset from UTC, such as `+02:00`.
+    #[serde(default)]
+    utc_offset: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    string: String,
+}
+
+async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+    let offset = match input.utc_offset.as_deref().map(str::trim) {
+        None | Some("") | Some("Z") => FixedOffset::east_opt(0).unwrap(),
+        Some(s) => s
+            .parse::<FixedOffset>()
+            .map_err(|e| FlowError::input_validation(Some(UTC_OFFSET), e))?,
+    };
+    let timestamp = input.timestamp.with_timezone(&offset);
+    let string = if input.format.is_empty() {
+        timestamp.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+    } else {
+        // formatting with invalid items panics
+        let items = StrftimeItems::new(&input.format).collect::<Vec<_>>();
+        if items.iter().any(|i| matches!(i, Item::Error)) {
+            return Err(FlowError::input_validation(
+                Some(FORMAT),
+                format!("invalid format {:?}", input.format),
+            )
+            .into());
+        }
+        timestamp.format_with_items(items.into_iter()).to_string()
+    };
+    Ok(Output { string })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        build().unwrap();
+    }
+
+    async fn format(format: &str, utc_offset: Option<&str>) -> Result<String, CommandError> {
+        run(
+            Context::default(),
+            Input {
+                timestamp: value::timestamp::from_unix(1_706_702_400).unwrap(),
+                format: format.to_owned(),
+                utc_offset: utc_offset.map(str::to_owned),
+            },
+        )
+        .await
+        .map(|o| o.string)
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        assert_eq!(format("", None).await.unwrap(), "2024-01-31T12:00:00Z");
+        assert_eq!(
+            format("", Some("+02:00")).await.unwrap(),
+            "2024-01-31T14:00:00+02:00"
+        );
+        assert_eq!(
+            format("%d %b %Y %H:%M", None).await.unwrap(),
+            "31 Jan 2024 12:00"
+        );
+        assert!(format("%Q", None).await.is_err());
+        assert!(format("", Some("CET")).await.is_err());
+    }
+}
@@ -0,0 +1,6 @@
+pub mod add;
+pub mod diff;
+pub mod format;
+pub mod now;
+pub mod parse;
+pub mod to_slot;
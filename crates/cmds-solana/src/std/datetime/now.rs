@@ -0,0 +1,42 @@
+use crate::prelude::*;
+use chrono::{DateTime, Utc};
+
+const NAME: &str = "datetime_now";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str =
+        include_str!("../../../../../node-definitions/std/datetime/datetime_now.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Input {}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    #[serde(with = "value::timestamp")]
+    timestamp: DateTime<Utc>,
+    unix: i64,
+}
+
+async fn run(_: Context, _: Input) -> Result<Output, CommandError> {
+    let timestamp = Utc::now();
+    Ok(Output {
+        unix: timestamp.timestamp(),
+        timestamp,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        build().unwrap();
+    }
+}
@@ -0,0 +1,116 @@
+use crate::prelude::*;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use flow_lib::FlowError;
+
+const NAME: &str = "datetime_parse";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str =
+        include_str!("../../../../../node-definitions/std/datetime/datetime_parse.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+// Inputs
+const VALUE: &str = "value";
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    value: Value,
+    /// `strftime` format, times without an offset are in UTC.
+    #[serde(default)]
+    format: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    #[serde(with = "value::timestamp")]
+    timestamp: DateTime<Utc>,
+    unix: i64,
+}
+
+fn parse_with_format(s: &str, format: &str) -> Option<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_str(s, format) {
+        return Some(t.with_timezone(&Utc));
+    }
+    let naive = NaiveDateTime::parse_from_str(s, format).ok().or_else(|| {
+        NaiveDate::parse_from_str(s, format)
+            .ok()?
+            .and_hms_opt(0, 0, 0)
+    })?;
+    Some(Utc.from_utc_datetime(&naive))
+}
+
+async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+    let timestamp = match input.format.filter(|f| !f.is_empty()) {
+        None => value::timestamp::deserialize(input.value)
+            .map_err(|e| FlowError::input_validation(Some(VALUE), e))?,
+        Some(format) => {
+            let Value::String(s) = input.value else {
+                return Err(FlowError::input_validation(
+                    Some(VALUE),
+                    "expected a string to parse with format",
+                )
+                .into());
+            };
+            parse_with_format(s.trim(), &format).ok_or_else(|| {
+                FlowError::input_validation(Some(VALUE), format!("{s:?} does not match {format:?}"))
+            })?
+        }
+    };
+    Ok(Output {
+        unix: timestamp.timestamp(),
+        timestamp,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        build().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let parse = |value: &str, format: Option<&str>| {
+            run(
+                Context::default(),
+                Input {
+                    value: value.into(),
+                    format: format.map(str::to_owned),
+                },
+            )
+        };
+        let expected = 1_706_702_400;
+        assert_eq!(
+            parse("2024-01-31T12:00:00Z", None).await.unwrap().unix,
+            expected
+        );
+        assert_eq!(parse("1706702400", None).await.unwrap().unix, expected);
+        assert_eq!(
+            parse("31/01/2024 12:00", Some("%d/%m/%Y %H:%M"))
+                .await
+                .unwrap()
+                .unix,
+            expected
+        );
+        assert_eq!(
+            parse("2024-01-31 14:00 +0200", Some("%Y-%m-%d %H:%M %z"))
+                .await
+                .unwrap()
+                .unix,
+            expected
+        );
+        assert_eq!(
+            parse("2024-01-31", Some("%Y-%m-%d")).await.unwrap().unix,
+            expected - 12 * 3600
+        );
+        assert!(parse("31/01/2024", None).await.is_err());
+    }
+}
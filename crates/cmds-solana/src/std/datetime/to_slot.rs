@@ -0,0 +1,84 @@
+use crate::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+use flow_lib::FlowError;
+
+const NAME: &str = "datetime_to_slot";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str =
+        include_str!("../../../../../node-definitions/std/datetime/datetime_to_slot.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+// Inputs
+const SLOT_TIME: &str = "slot_time";
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    #[serde(with = "value::timestamp")]
+    timestamp: DateTime<Utc>,
+    /// Average time between slots.
+    #[serde(default = "default_slot_time", with = "value::duration")]
+    slot_time: Duration,
+}
+
+fn default_slot_time() -> Duration {
+    Duration::milliseconds(400)
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    slot: u64,
+    current_slot: u64,
+}
+
+/// Estimate the slot at `timestamp`, assuming `current_slot` is at `now`.
+fn estimate(
+    timestamp: DateTime<Utc>,
+    now: DateTime<Utc>,
+    current_slot: u64,
+    slot_time: Duration,
+) -> u64 {
+    let slots = (timestamp - now).num_milliseconds() / slot_time.num_milliseconds();
+    current_slot.saturating_add_signed(slots)
+}
+
+async fn run(ctx: Context, input: Input) -> Result<Output, CommandError> {
+    if input.slot_time.num_milliseconds() <= 0 {
+        return Err(
+            FlowError::input_validation(Some(SLOT_TIME), "slot time must be positive").into(),
+        );
+    }
+    let current_slot = ctx.solana_client.get_slot().await?;
+    let slot = estimate(input.timestamp, Utc::now(), current_slot, input.slot_time);
+    Ok(Output { slot, current_slot })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        build().unwrap();
+    }
+
+    #[test]
+    fn test_estimate() {
+        let now = value::timestamp::from_unix(1_706_702_400).unwrap();
+        let hour = Duration::hours(1);
+        assert_eq!(
+            estimate(now + hour, now, 1000, default_slot_time()),
+            1000 + 9000
+        );
+        assert_eq!(estimate(now - hour, now, 1000, default_slot_time()), 0);
+        assert_eq!(
+            estimate(now - Duration::seconds(2), now, 1000, default_slot_time()),
+            995
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use crate::prelude::*;
+use value::schema::Format;
+
+const NAME: &str = "decode";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str = include_str!("../../../../node-definitions/std/decode.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    bytes: bytes::Bytes,
+    /// Schema as JSON, a JSON string, or the name of a primitive type.
+    schema: Value,
+    #[serde(default)]
+    format: Format,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    value: Value,
+}
+
+async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+    let schema = super::encode::parse_schema(input.schema)?;
+    let value = schema.decode(input.format, &input.bytes)?;
+    Ok(Output { value })
+}
@@ -0,0 +1,47 @@
+use crate::prelude::*;
+use value::schema::{Format, Schema};
+
+const NAME: &str = "encode";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str = include_str!("../../../../node-definitions/std/encode.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    value: Value,
+    /// Schema as JSON, a JSON string, or the name of a primitive type.
+    schema: Value,
+    #[serde(default)]
+    format: Format,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    bytes: bytes::Bytes,
+}
+
+/// Parse the `schema` input of `encode` and `decode`.
+pub(crate) fn parse_schema(schema: Value) -> Result<Schema, CommandError> {
+    let schema = match schema {
+        Value::String(s) => match serde_json::from_str(&s) {
+            Ok(schema) => schema,
+            Err(_) => value::from_value(Value::String(s))?,
+        },
+        schema => value::from_value(schema)?,
+    };
+    Ok(schema)
+}
+
+async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+    let schema = parse_schema(input.schema)?;
+    let bytes = schema.encode(input.format, &input.value)?;
+    Ok(Output {
+        bytes: bytes.into(),
+    })
+}
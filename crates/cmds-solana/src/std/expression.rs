@@ -121,6 +121,11 @@ impl CommandTrait for ScriptCommand {
                                 Value::Map(_v) => "maps_not_supported".into(),
                                 Value::B32(v) => bs58::encode(&v).into_string(),
                                 Value::B64(v) => bs58::encode(&v).into_string(),
+                                Value::U256(v) => v.to_string(),
+                                Value::I256(v) => v.to_string(),
+                                Value::EvmAddress(v) => value::evm_address::to_checksum(v),
+                                Value::Timestamp(v) => value::timestamp::format(v),
+                                Value::Duration(v) => value::duration::format(v),
                                 other => serde_json::to_string_pretty(&other).unwrap(),
                             };
                             value
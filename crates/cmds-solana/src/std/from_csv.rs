@@ -0,0 +1,69 @@
+use crate::prelude::*;
+use value::table::Table;
+
+const NAME: &str = "from_csv";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str = include_str!("../../../../node-definitions/std/from_csv.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+fn default_nested() -> bool {
+    true
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    csv: String,
+    #[serde(default = "default_nested")]
+    nested: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    data: Value,
+    columns: Vec<String>,
+}
+
+async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+    let table = Table::read_csv(input.csv.as_bytes())?;
+    let columns = table.columns.clone();
+    Ok(Output {
+        data: table.into_value(input.nested)?,
+        columns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        build().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let output = run(
+            Context::default(),
+            Input {
+                csv: "owner,account.amount\nabc,10\n".to_owned(),
+                nested: true,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            output.data,
+            Value::Array(vec![Value::Map(value::map! {
+                "owner" => "abc",
+                "account" => value::map! { "amount" => "10" },
+            })])
+        );
+    }
+}
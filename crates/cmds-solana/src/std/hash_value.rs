@@ -0,0 +1,70 @@
+use crate::prelude::*;
+use value::canonical::Algorithm;
+
+const NAME: &str = "hash_value";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str = include_str!("../../../../node-definitions/std/hash_value.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    value: Value,
+    #[serde(default)]
+    algorithm: Algorithm,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    hash: bytes::Bytes,
+    hex: String,
+}
+
+async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+    let hash = value::canonical::hash(input.algorithm, &input.value);
+    Ok(Output {
+        hash: hash.to_vec().into(),
+        hex: hex::encode(hash),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        build().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let hash = |value: Value| async move {
+            run(
+                Context::default(),
+                Input {
+                    value,
+                    algorithm: Algorithm::Blake3,
+                },
+            )
+            .await
+            .unwrap()
+            .hex
+        };
+        assert_eq!(
+            hash(Value::Map(
+                value::map! { "a" => 1u64, "b" => Decimal::new(20, 1) }
+            ))
+            .await,
+            hash(Value::Map(
+                value::map! { "b" => 2u64, "a" => Value::I64(1) }
+            ))
+            .await,
+        );
+    }
+}
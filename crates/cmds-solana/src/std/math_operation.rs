@@ -1,193 +1,221 @@
-use std::collections::BTreeMap;
-use thiserror::Error as ThisError;
-
 use crate::prelude::*;
+use flow_lib::FlowError;
+use rust_decimal::prelude::ToPrimitive;
+use value::num::Op;
+
+const NAME: &str = "math_operation";
 
-#[derive(Debug, Clone)]
-pub struct MathOperation;
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str = include_str!("../../../../node-definitions/std/math_operation.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
 
-#[derive(Serialize, Deserialize, Debug)]
+// Inputs
+const NUMBER_1: &str = "number_1";
+const NUMBER_2: &str = "number_2";
+const OPERATOR: &str = "operator";
+
+#[derive(Deserialize, Debug)]
 pub struct Input {
+    number_1: Value,
+    number_2: Value,
+    #[serde(default = "default_operator")]
     operator: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+fn default_operator() -> String {
+    "+".to_owned()
+}
+
+#[derive(Serialize, Debug)]
 pub struct Output {
+    /// Exact result.
+    result: Value,
     result_f64: f64,
+    /// 0 if the result is not an integer in range, fractions are not truncated.
     result_u64: u64,
+    /// 0 if the result is not an integer in range, fractions are not truncated.
     result_i64: i64,
     result_string: String,
 }
 
-// Name
-const MATH_OPERATION: &str = "math_operation";
+fn operator(s: &str) -> Option<Op> {
+    Some(match s.trim() {
+        "+" | "Addition" => Op::Add,
+        "-" | "Subtraction" => Op::Sub,
+        "*" | "Multiplication" => Op::Mul,
+        "/" | "Division" => Op::Div,
+        "%" | "Modulo" => Op::Rem,
+        "^" | "Exponentiation" => Op::Pow,
+        _ => return None,
+    })
+}
 
-// Inputs
-const NUMBER_1: &str = "number_1";
-const NUMBER_2: &str = "number_2";
-const OPERATOR: &str = "operator";
+/// Numbers can also be given as strings, to pass amounts that don't fit in JSON numbers.
+fn number(name: &str, value: Value) -> Result<Value, FlowError> {
+    match value {
+        Value::String(s) => {
+            value::num::parse(&s).map_err(|e| FlowError::input_validation(Some(name), e))
+        }
+        value => Ok(value),
+    }
+}
 
-// Outputs
-const RESULT_F64: &str = "result_u64";
-const RESULT_U64: &str = "result_f64";
-const RESULT_I64: &str = "result_i64";
-const RESULT_STRING: &str = "result_string";
-
-#[derive(Debug, ThisError)]
-enum MathError {
-    #[error("second argument can not be deserialized an object of values")]
-    Decode(serde_json::Error),
-    #[error(transparent)]
-    Compute(#[from] fasteval::Error),
+fn to_f64(value: &Value) -> f64 {
+    match value {
+        Value::U64(n) => *n as f64,
+        Value::I64(n) => *n as f64,
+        Value::U128(n) => *n as f64,
+        Value::I128(n) => *n as f64,
+        Value::F64(n) => *n,
+        Value::Decimal(d) => d.to_f64().unwrap_or_default(),
+        _ => 0.0,
+    }
 }
 
-#[async_trait]
-impl CommandTrait for MathOperation {
-    fn name(&self) -> Name {
-        MATH_OPERATION.into()
+/// The result if it is an integer.
+fn to_i128(value: &Value) -> Option<i128> {
+    match value {
+        Value::U64(n) => Some((*n).into()),
+        Value::I64(n) => Some((*n).into()),
+        Value::U128(n) => i128::try_from(*n).ok(),
+        Value::I128(n) => Some(*n),
+        Value::F64(n) if n.fract() == 0.0 => n.to_i128(),
+        Value::Decimal(d) if d.fract().is_zero() => d.to_i128(),
+        _ => None,
     }
+}
 
-    fn inputs(&self) -> Vec<CmdInput> {
-        [
-            CmdInput {
-                name: NUMBER_1.into(),
-                type_bounds: [ValueType::Free].to_vec(),
-                required: true,
-                passthrough: false,
-            },
-            CmdInput {
-                name: NUMBER_2.into(),
-                type_bounds: [ValueType::Free].to_vec(),
-                required: true,
-                passthrough: false,
-            },
-            CmdInput {
-                name: OPERATOR.into(),
-                type_bounds: [ValueType::String].to_vec(),
-                required: false,
-                passthrough: false,
-            },
-        ]
-        .to_vec()
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::U64(n) => n.to_string(),
+        Value::I64(n) => n.to_string(),
+        Value::U128(n) => n.to_string(),
+        Value::I128(n) => n.to_string(),
+        Value::F64(n) => n.to_string(),
+        Value::Decimal(d) => d.normalize().to_string(),
+        _ => String::new(),
     }
+}
+
+async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+    let op = operator(&input.operator).ok_or_else(|| {
+        FlowError::input_validation(
+            Some(OPERATOR),
+            format!("unknown operator {:?}", input.operator),
+        )
+    })?;
+    let x = number(NUMBER_1, input.number_1)?;
+    let y = number(NUMBER_2, input.number_2)?;
+    let result = x.checked_op(op, &y)?;
+
+    let integer = to_i128(&result);
+    Ok(Output {
+        result_f64: to_f64(&result),
+        result_u64: integer.and_then(|n| u64::try_from(n).ok()).unwrap_or(0),
+        result_i64: integer.and_then(|n| i64::try_from(n).ok()).unwrap_or(0),
+        result_string: to_string(&result),
+        result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn outputs(&self) -> Vec<CmdOutput> {
-        [
-            CmdOutput {
-                name: RESULT_F64.into(),
-                r#type: ValueType::F64,
-            },
-            CmdOutput {
-                name: RESULT_U64.into(),
-                r#type: ValueType::U64,
-            },
-            CmdOutput {
-                name: RESULT_I64.into(),
-                r#type: ValueType::I64,
-            },
-            CmdOutput {
-                name: RESULT_STRING.into(),
-                r#type: ValueType::String,
-            },
-        ]
-        .to_vec()
+    #[test]
+    fn test_build() {
+        build().unwrap();
     }
 
-    async fn run(&self, _ctx: Context, mut inputs: ValueSet) -> Result<ValueSet, CommandError> {
-        let Input { operator } = value::from_map(inputs.clone())?;
-
-        let number_1 = inputs.remove(NUMBER_1).unwrap_or(value::Value::U64(0));
-        let number_1 = match number_1 {
-            Value::Decimal(v) => v.to_string(),
-            Value::U64(v) => v.to_string(),
-            Value::I64(v) => v.to_string(),
-            Value::U128(v) => v.to_string(),
-            Value::I128(v) => v.to_string(),
-            Value::F64(v) => v.to_string(),
-            Value::String(s) => s,
-            other => serde_json::to_string_pretty(&other).unwrap(),
-        };
-
-        let number_2 = inputs.remove(NUMBER_2).unwrap_or(value::Value::U64(0));
-        let number_2 = match number_2 {
-            Value::Decimal(v) => v.to_string(),
-            Value::U64(v) => v.to_string(),
-            Value::I64(v) => v.to_string(),
-            Value::U128(v) => v.to_string(),
-            Value::I128(v) => v.to_string(),
-            Value::F64(v) => v.to_string(),
-            Value::String(s) => s,
-            other => serde_json::to_string_pretty(&other).unwrap(),
-        };
-
-        // Variable Map
-        let mut map: BTreeMap<String, f64> = BTreeMap::new();
-        map.insert(
-            "x".to_string(),
-            serde_json::from_str(&number_1).map_err(MathError::Decode)?,
-        );
-        map.insert(
-            "y".to_string(),
-            serde_json::from_str(&number_2).map_err(MathError::Decode)?,
-        );
-
-        let operator = match &*operator {
-            "^" | "Exponentiation" => "^",
-            "%" | "Modulo" => "%",
-            "/" | "Division" => "/",
-            "*" | "Multiplication" => "*",
-            "-" | "Subtraction" => "-",
-            "+" | "Addition" => "+",
-            &_ => "+",
-        };
-
-        let expression = ["x", operator, "y"].join("");
-
-        // Calculation
-        let result_f64 = match fasteval::ez_eval(&expression, &mut map) {
-            Ok(value) => value,
-            Err(_error) => 0.0,
-        };
-
-        // Get other types if matching
-        let result_u64 = match result_f64.fract() == 0.0 {
-            true => result_f64 as u64,
-            false => 0,
-        };
-
-        let result_i64 = match result_f64.is_sign_negative() && result_f64.fract() == 0.0 {
-            true => result_f64 as i64,
-            false => 0,
-        };
-
-        let result_string = result_f64.to_string();
-
-        Ok(value::to_map(&Output {
-            result_f64,
-            result_u64,
-            result_i64,
-            result_string,
-        })?)
+    #[tokio::test]
+    async fn test_run() {
+        let output = run(
+            Context::default(),
+            value::from_map(value::map! {
+                NUMBER_1 => "1234567891234567891",
+                NUMBER_2 => 1_000_000_000u64,
+                OPERATOR => "Division",
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.result_string, "1234567891.234567891");
+        assert_eq!(output.result_u64, 0);
+        assert_eq!(output.result_i64, 0);
+
+        let output = run(
+            Context::default(),
+            value::from_map(value::map! {
+                NUMBER_1 => 3u64,
+                NUMBER_2 => 5u64,
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.result, Value::U64(8));
+        assert_eq!(output.result_u64, 8);
+        assert_eq!(output.result_i64, 8);
+
+        let output = run(
+            Context::default(),
+            value::from_map(value::map! {
+                NUMBER_1 => "4.5",
+                NUMBER_2 => 2u64,
+                OPERATOR => "*",
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.result_u64, 9);
+        assert_eq!(output.result_i64, 9);
+
+        let output = run(
+            Context::default(),
+            value::from_map(value::map! {
+                NUMBER_1 => 3u64,
+                NUMBER_2 => 5u64,
+                OPERATOR => "-",
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.result, Value::I64(-2));
+        assert_eq!(output.result_i64, -2);
+        assert_eq!(output.result_f64, -2.0);
+
+        // fractions are not truncated
+        let output = run(
+            Context::default(),
+            value::from_map(value::map! {
+                NUMBER_1 => 7u64,
+                NUMBER_2 => 2u64,
+                OPERATOR => "/",
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.result_string, "3.5");
+        assert_eq!(output.result_u64, 0);
+        assert_eq!(output.result_i64, 0);
+
+        // unknown operators are not treated as `+`
+        let unknown = value::from_map(value::map! {
+            NUMBER_1 => 1u64,
+            NUMBER_2 => 1u64,
+            OPERATOR => "max",
+        })
+        .unwrap();
+        let error = run(Context::default(), unknown).await.unwrap_err();
+        assert!(error.to_string().contains("unknown operator \"max\""));
     }
 }
-
-inventory::submit!(CommandDescription::new(MATH_OPERATION, |_| Ok(Box::new(
-    MathOperation {}
-))));
-
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-
-//     #[tokio::test]
-//     async fn test_valid() {
-//         let input = value::to_map(&Input {
-//             number_1: 100,
-//             number_2: 20,
-//         })
-//         .unwrap();
-//         let output = Addition.run(Context::default(), input).await.unwrap();
-//         let result = value::from_map::<Output>(output).unwrap().result;
-//         dbg!(result);
-//     }
-// }
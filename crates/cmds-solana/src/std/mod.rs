@@ -1,7 +1,13 @@
+pub mod datetime;
+pub mod decode;
+pub mod encode;
 pub mod expression;
+pub mod from_csv;
+pub mod hash_value;
 pub mod json_get_field;
 pub mod math_operation;
 pub mod range;
 pub mod to_bytes;
+pub mod to_csv;
 pub mod to_string;
 pub mod to_vec;
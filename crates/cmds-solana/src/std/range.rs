@@ -6,8 +6,11 @@ const NAME: &str = "range";
 const DEFINITION: &str = include_str!("../../../../node-definitions/std/range.json");
 
 fn build() -> BuildResult {
-    static CACHE: BuilderCache =
-        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    static CACHE: BuilderCache = BuilderCache::new(|| {
+        CmdBuilder::new(DEFINITION)?
+            .check_name(NAME)?
+            .stream_output("element")
+    });
     Ok(CACHE.clone()?.build(run))
 }
 
@@ -21,18 +24,22 @@ pub struct Input {
     pub end: Decimal,
     #[serde(default, with = "value::decimal::opt")]
     pub step_by: Option<Decimal>,
+    #[serde(default)]
+    pub stream: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Output {
-    pub result: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Vec<Value>>,
 }
 
-async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+async fn run(ctx: Context, input: Input) -> Result<Output, CommandError> {
     const MAX_LENGTH: usize = 10_000_000;
     let mut start = input.start;
     let end = input.end;
     let step = input.step_by.unwrap_or(Decimal::ONE);
+
     let length: usize = ((end - start).abs() / step).floor().try_into()?;
     if length > MAX_LENGTH {
         return Err(anyhow::anyhow!(
@@ -40,12 +47,24 @@ async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
             MAX_LENGTH,
         ));
     }
+
+    if input.stream {
+        for _ in 0..length {
+            ctx.check_canceled()?;
+            ctx.emit(value::map! { "element" => start }).await?;
+            start += step;
+        }
+        return Ok(Output { result: None });
+    }
+
     let mut result = Vec::with_capacity(length);
     for _ in 0..length {
         result.push(Value::Decimal(start));
         start += step;
     }
-    Ok(Output { result })
+    Ok(Output {
+        result: Some(result),
+    })
 }
 
 #[cfg(test)]
@@ -55,4 +74,40 @@ mod tests {
     fn test_build() {
         build().unwrap();
     }
+
+    #[tokio::test]
+    async fn test_stream() {
+        use futures::StreamExt;
+
+        let mut ctx = Context::default();
+        let (svc, rx) = flow_lib::context::stream::channel(16);
+        ctx.command.as_mut().unwrap().stream = svc;
+        let input = value::from_map(value::map! {
+            "start" => 0,
+            "end" => 3,
+            "stream" => true,
+        })
+        .unwrap();
+        let output = run(ctx, input).await.unwrap();
+        assert!(output.result.is_none());
+        let elements = rx.collect::<Vec<_>>().await;
+        assert_eq!(
+            elements,
+            [0, 1, 2]
+                .map(|i| value::map! { "element" => Decimal::from(i) })
+                .to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_limit() {
+        let input = value::from_map(value::map! {
+            "start" => 0,
+            "end" => 20_000_000,
+            "stream" => true,
+        })
+        .unwrap();
+        let error = run(Context::default(), input).await.unwrap_err();
+        assert_eq!(error.to_string(), "too large, maximum length is 10000000");
+    }
 }
@@ -0,0 +1,44 @@
+use crate::prelude::*;
+use value::table::{Options, Table};
+
+const NAME: &str = "to_csv";
+
+inventory::submit!(CommandDescription::new(NAME, |_| build()));
+
+fn build() -> BuildResult {
+    const DEFINITION: &str = include_str!("../../../../node-definitions/std/to_csv.json");
+    static CACHE: BuilderCache =
+        BuilderCache::new(|| CmdBuilder::new(DEFINITION)?.check_name(NAME));
+    Ok(CACHE.clone()?.build(run))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Input {
+    data: Value,
+    #[serde(default)]
+    depth: Option<usize>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    csv: String,
+    columns: Vec<String>,
+}
+
+async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+    let table = Table::from_value(input.data, &Options { depth: input.depth });
+    Ok(Output {
+        csv: table.to_csv()?,
+        columns: table.columns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        build().unwrap();
+    }
+}
@@ -49,9 +49,14 @@ impl CommandTrait for ToString {
             Value::I64(v) => v.to_string(),
             Value::U128(v) => v.to_string(),
             Value::I128(v) => v.to_string(),
+            Value::U256(v) => v.to_string(),
+            Value::I256(v) => v.to_string(),
             Value::F64(v) => v.to_string(),
             Value::B32(v) => bs58::encode(&v).into_string(),
             Value::B64(v) => bs58::encode(&v).into_string(),
+            Value::EvmAddress(v) => value::evm_address::to_checksum(&v),
+            Value::Timestamp(v) => value::timestamp::format(&v),
+            Value::Duration(v) => value::duration::format(&v),
             Value::String(s) => s,
             other => serde_json::to_string_pretty(&other).unwrap(),
         };
@@ -114,9 +114,14 @@ pub async fn try_sign_wallet(
     Ok(())
 }
 
-//
+/// Discriminator of Anchor instructions.
 pub fn anchor_sighash(name: &str) -> [u8; 8] {
-    let namespace = "global";
+    anchor_discriminator("global", name)
+}
+
+/// First 8 bytes of the hash of `{namespace}:{name}`, Anchor uses the `global` namespace for
+/// instructions and `account` for accounts.
+pub fn anchor_discriminator(namespace: &str, name: &str) -> [u8; 8] {
     let preimage = format!("{}:{}", namespace, name);
     let mut sighash = [0u8; 8];
     sighash.copy_from_slice(
@@ -139,6 +139,7 @@ mod tests {
                 extra: Extra::default(),
                 wasm_bytes: None,
             },
+            policy: None,
         };
         assert_eq!(Wallet::new(&nd).form.unwrap().pubkey, PUBKEY);
     }
@@ -161,6 +162,7 @@ mod tests {
                 extra: Extra::default(),
                 wasm_bytes: None,
             },
+            policy: None,
         };
         let wallet = Wallet::new(&nd).form.unwrap();
         assert_eq!(wallet.keypair.to_base58_string(), KEYPAIR);
@@ -45,29 +45,25 @@ async fn run(ctx: Context, input: Input) -> Result<Output, CommandError> {
         wormhole_endpoint, wormhole_path, input.chain_id, input.emitter, input.sequence
     );
 
-    async fn send_wormhole_request(
-        client: &reqwest::Client,
-        wormhole_url: &str,
-        timeout: Duration,
-    ) -> Result<reqwest::Response, reqwest::Error> {
-        let response = client.get(wormhole_url).timeout(timeout).send().await?;
-        Ok(response)
-    }
-
     let timeout = Duration::from_secs(60);
 
-    let mut response = send_wormhole_request(&ctx.http, &wormhole_url, timeout).await?;
+    let mut response = ctx
+        .send_http(ctx.http.get(&wormhole_url).timeout(timeout))
+        .await?;
 
     while response.status() != 200 {
+        ctx.check_canceled()?;
         // Solana
         if input.chain_id == "1" {
-            sleep(Duration::from_secs(5)).await;
+            ctx.cancelable(sleep(Duration::from_secs(5))).await?;
         }
         // Eth Sepolia about 20m
         if input.chain_id == "10002" {
-            sleep(Duration::from_secs(45)).await;
+            ctx.cancelable(sleep(Duration::from_secs(45))).await?;
         }
-        response = send_wormhole_request(&ctx.http, &wormhole_url, timeout).await?;
+        response = ctx
+            .send_http(ctx.http.get(&wormhole_url).timeout(timeout))
+            .await?;
     }
 
     let response_text = response.text().await?;
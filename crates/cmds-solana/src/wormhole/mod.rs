@@ -58,6 +58,58 @@ pub mod post_message;
 pub mod post_vaa;
 pub mod verify_signatures;
 
+/// Serde helper for the target address of a transfer: a 32-byte Wormhole address as bytes or
+/// hex, or a 20-byte EVM address, see [`value::evm_address`], which is left-padded with zeros.
+/// Shorter hex strings are also left-padded.
+pub mod target_address {
+    use super::ForeignAddress;
+    use serde::de::Error;
+    use value::Value;
+
+    pub fn serialize<S>(address: &ForeignAddress, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.serialize_bytes(address)
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<ForeignAddress, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = <Value as serde::Deserialize>::deserialize(d)?;
+        wormhole_sdk::Address::try_from(value)
+            .map(|address| address.0)
+            .map_err(D::Error::custom)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use value::evm_address;
+
+        #[test]
+        fn test_deserialize() {
+            let evm = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+            let mut universal = [0u8; 32];
+            universal[12..].copy_from_slice(&evm_address::parse(evm).unwrap());
+            assert_eq!(deserialize(Value::from(evm)).unwrap(), universal);
+            assert_eq!(
+                deserialize(Value::EvmAddress(evm_address::parse(evm).unwrap())).unwrap(),
+                universal
+            );
+            let hex = format!("0x{}", hex::encode(universal));
+            assert_eq!(deserialize(Value::from(hex)).unwrap(), universal);
+            assert_eq!(deserialize(Value::B32(universal)).unwrap(), universal);
+            let bytes = Value::Bytes(universal[12..].to_vec().into());
+            assert_eq!(deserialize(bytes).unwrap(), universal);
+            assert!(
+                deserialize(Value::from("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD")).is_err()
+            );
+        }
+    }
+}
+
 #[repr(u8)]
 #[derive(BorshSerialize, BorshDeserialize)]
 pub enum WormholeInstructions {
@@ -1,6 +1,6 @@
 use crate::{
     prelude::*,
-    wormhole::token_bridge::{eth::hex_to_address, get_sequence_number, SequenceTracker},
+    wormhole::token_bridge::{get_sequence_number, Address, SequenceTracker},
 };
 
 use borsh::BorshSerialize;
@@ -33,7 +33,8 @@ inventory::submit!(CommandDescription::new(NAME, |_| { build() }));
 pub struct Input {
     #[serde(with = "value::keypair")]
     pub payer: Keypair,
-    pub target_address: String,
+    #[serde(with = "crate::wormhole::target_address")]
+    pub target_address: crate::wormhole::ForeignAddress,
     pub target_chain: u16,
     #[serde(with = "value::keypair")]
     pub message: Keypair,
@@ -93,7 +94,7 @@ async fn run(mut ctx: Context, input: Input) -> Result<Output, CommandError> {
     // TODO: use a real nonce
     let nonce = rand::thread_rng().gen();
 
-    let address = hex_to_address(&input.target_address).map_err(anyhow::Error::msg)?;
+    let address = Address(input.target_address);
 
     let data = TransferNativeData {
         nonce,
@@ -1,14 +1,11 @@
 use crate::wormhole::ForeignAddress;
-use std::str::FromStr;
 
 use crate::prelude::*;
 
 use borsh::BorshSerialize;
-use primitive_types::U256;
 use rand::Rng;
 use solana_program::instruction::AccountMeta;
 use solana_sdk::pubkey::Pubkey;
-use wormhole_sdk::Address;
 
 use super::{NFTBridgeInstructions, TransferWrappedData};
 
@@ -36,10 +33,15 @@ pub struct Input {
     pub payer: Keypair,
     pub token_chain: u16,
     pub token_address: ForeignAddress,
-    pub token_id: String,
+    #[serde(
+        serialize_with = "value::u256::serialize",
+        deserialize_with = "token_id"
+    )]
+    pub token_id: value::u256::U256,
     pub amount: u64,
     pub fee: u64,
-    pub target_address: Address,
+    #[serde(with = "crate::wormhole::target_address")]
+    pub target_address: ForeignAddress,
     pub target_chain: u16,
     #[serde(with = "value::keypair")]
     pub message: Keypair,
@@ -51,6 +53,21 @@ pub struct Input {
     submit: bool,
 }
 
+/// Token IDs given as strings are hex, with or without `0x`.
+fn token_id<'de, D>(d: D) -> Result<value::u256::U256, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    match Value::deserialize(d)? {
+        Value::String(s) => {
+            let hex = s.trim().strip_prefix("0x").unwrap_or(s.trim());
+            value::u256::U256::from_str_radix(hex, 16).map_err(D::Error::custom)
+        }
+        value => value::u256::deserialize(value).map_err(D::Error::custom),
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Output {
     #[serde(default, with = "value::signature::opt")]
@@ -66,11 +83,7 @@ async fn run(mut ctx: Context, input: Input) -> Result<Output, CommandError> {
 
     let config_key = Pubkey::find_program_address(&[b"config"], &nft_bridge_program_id).0;
 
-    // Convert token id
-    let token_id_input =
-        U256::from_str(&input.token_id).map_err(|_| anyhow::anyhow!("Invalid token id"))?;
-    let mut token_id = vec![0u8; 32];
-    token_id_input.to_big_endian(&mut token_id);
+    let token_id = input.token_id.to_be_bytes();
 
     let wrapped_mint_key = Pubkey::find_program_address(
         &[
@@ -118,7 +131,7 @@ async fn run(mut ctx: Context, input: Input) -> Result<Output, CommandError> {
 
     let wrapped_data = TransferWrappedData {
         nonce,
-        target_address: super::Address(input.target_address.0),
+        target_address: super::Address(input.target_address),
         target_chain: input.target_chain,
     };
 
@@ -35,6 +35,8 @@ pub struct Output {
     vaa_hash: bytes::Bytes,
     vaa_secp256k_hash: bytes::Bytes,
     guardian_set_index: u32,
+    #[serde(with = "value::timestamp")]
+    timestamp: chrono::DateTime<chrono::Utc>,
     payload: serde_json::Value,
     nft_token_id: Option<String>,
 }
@@ -149,6 +151,7 @@ async fn run(_ctx: Context, input: Input) -> Result<Output, CommandError> {
         vaa_hash: bytes::Bytes::copy_from_slice(&vaa_hash),
         vaa_secp256k_hash: bytes::Bytes::copy_from_slice(&vaa_secp256k_hash),
         guardian_set_index: parsed_vaa.guardian_set_index,
+        timestamp: value::timestamp::from_unix(parsed_vaa.timestamp.into())?,
         payload: output_payload.clone(),
         nft_token_id,
     })
@@ -121,17 +121,9 @@ pub fn decode_hex(s: &str) -> Result<Vec<u8>, ParseIntError> {
         .collect()
 }
 
-// Function to Convert Hex String to Address
+// Function to Convert EVM Hex Address to Address
 pub fn hex_to_address(hex: &str) -> Result<Address, anyhow::Error> {
-    if !hex.starts_with("0x") {
-        return Err(anyhow::anyhow!("invalid address {}", hex));
-    };
-
-    let stripped_address = hex.split_at(2).1;
-
-    let bytes = decode_hex(stripped_address).unwrap();
-    let mut array = [0u8; 32];
-    array[32 - bytes.len()..].copy_from_slice(&bytes);
-    let address: Address = Address(array);
-    Ok(address)
+    let address = value::evm_address::parse(hex)
+        .map_err(|error| anyhow::anyhow!("invalid address {}: {}", hex, error))?;
+    Ok(wormhole_sdk::Address::try_from(value::Value::EvmAddress(address))?.into())
 }
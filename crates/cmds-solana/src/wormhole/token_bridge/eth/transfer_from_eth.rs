@@ -20,7 +20,8 @@ inventory::submit!(CommandDescription::new(NAME, |_| { build() }));
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Input {
     pub keypair: String,
-    pub token: String,
+    #[serde(with = "value::evm_address")]
+    pub token: value::evm_address::Target,
     pub network_name: String,
     pub recipient: String,
     pub amount: String,
@@ -46,7 +47,7 @@ async fn run(ctx: Context, input: Input) -> Result<Output, CommandError> {
 
     let payload = Payload {
         network_name: input.network_name,
-        token: input.token,
+        token: value::evm_address::to_checksum(&input.token),
         keypair: input.keypair,
         recipient: input.recipient,
         amount: input.amount,
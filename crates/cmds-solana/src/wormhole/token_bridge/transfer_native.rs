@@ -6,8 +6,7 @@ use solana_program::instruction::AccountMeta;
 use solana_sdk::pubkey::Pubkey;
 
 use super::{
-    eth::hex_to_address, get_sequence_number, SequenceTracker, TokenBridgeInstructions,
-    TransferNativeData,
+    get_sequence_number, Address, SequenceTracker, TokenBridgeInstructions, TransferNativeData,
 };
 
 // Command Name
@@ -41,7 +40,8 @@ pub struct Input {
     // 1 = 1,000,000,000
     pub amount: u64,
     pub fee: u64,
-    pub target_address: String,
+    #[serde(with = "crate::wormhole::target_address")]
+    pub target_address: crate::wormhole::ForeignAddress,
     pub target_chain: u16,
     #[serde(default = "value::default::bool_true")]
     submit: bool,
@@ -85,7 +85,7 @@ async fn run(mut ctx: Context, input: Input) -> Result<Output, CommandError> {
     // TODO: use a real nonce
     let nonce = rand::thread_rng().gen();
 
-    let address = hex_to_address(&input.target_address).map_err(anyhow::Error::msg)?;
+    let address = Address(input.target_address);
 
     let wrapped_data = TransferNativeData {
         nonce,
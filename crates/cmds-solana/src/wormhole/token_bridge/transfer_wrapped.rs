@@ -1,4 +1,4 @@
-use crate::wormhole::token_bridge::eth::hex_to_address;
+use crate::wormhole::token_bridge::Address;
 
 use crate::prelude::*;
 
@@ -35,7 +35,8 @@ pub struct Input {
     pub token_address: Pubkey,
     pub amount: u64,
     pub fee: u64,
-    pub target_address: String,
+    #[serde(with = "crate::wormhole::target_address")]
+    pub target_address: crate::wormhole::ForeignAddress,
     pub target_chain: u16,
     #[serde(with = "value::keypair")]
     pub message: Keypair,
@@ -62,7 +63,7 @@ async fn run(mut ctx: Context, input: Input) -> Result<Output, CommandError> {
 
     let config_key = Pubkey::find_program_address(&[b"config"], &token_bridge_program_id).0;
 
-    let target_address = hex_to_address(&input.target_address).map_err(anyhow::Error::msg)?;
+    let target_address = Address(input.target_address);
 
     let wrapped_mint_key = Pubkey::find_program_address(
         &[
@@ -0,0 +1,526 @@
+//! Macros for implementing commands, re-exported and documented in `flow_lib::command::derive`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse::ParseStream, parse_macro_input, Data, DeriveInput, Fields, FnArg, GenericArgument,
+    Ident, ItemFn, LitStr, PathArguments, ReturnType, Token, Type,
+};
+
+/// Register an `async fn run(ctx: Context, input: Input) -> Result<Output, CommandError>` as a
+/// command.
+#[proc_macro_attribute]
+pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut args = CommandArgs::default();
+    let parser = syn::meta::parser(|meta| args.parse(meta));
+    parse_macro_input!(attr with parser);
+    let item = parse_macro_input!(item as ItemFn);
+    expand_command(args, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implement `CommandInputs` for a struct.
+#[proc_macro_derive(CommandInputs, attributes(input))]
+pub fn derive_inputs(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_io(input, Io::Input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implement `CommandOutputs` for a struct.
+#[proc_macro_derive(CommandOutputs, attributes(output))]
+pub fn derive_outputs(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_io(input, Io::Output)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct CommandArgs {
+    name: Option<LitStr>,
+    signature: Option<LitStr>,
+    user_tokens: bool,
+}
+
+impl CommandArgs {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("signature") {
+            self.signature = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("user_tokens") {
+            self.user_tokens = true;
+        } else {
+            return Err(meta.error("expected `name`, `signature` or `user_tokens`"));
+        }
+        Ok(())
+    }
+}
+
+fn expand_command(args: CommandArgs, item: ItemFn) -> syn::Result<TokenStream2> {
+    let name = args
+        .name
+        .ok_or_else(|| syn::Error::new(Span::call_site(), "missing `name = \"...\"`"))?;
+    if item.sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            &item.sig,
+            "command must be an `async fn`",
+        ));
+    }
+
+    let input = match item.sig.inputs.iter().nth(1) {
+        Some(FnArg::Typed(arg)) if item.sig.inputs.len() == 2 => arg.ty.clone(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &item.sig.inputs,
+                "expected `(ctx: Context, input: Input)`",
+            ))
+        }
+    };
+    let output = match &item.sig.output {
+        ReturnType::Type(_, ty) => generic_arg(ty, "Result").cloned(),
+        ReturnType::Default => None,
+    }
+    .ok_or_else(|| {
+        syn::Error::new_spanned(&item.sig.output, "expected `Result<Output, CommandError>`")
+    })?;
+
+    let fn_name = &item.sig.ident;
+    let ty = format_ident!("{}Command", camel_case(&name.value()));
+    let user_tokens = args.user_tokens;
+    let signature = args
+        .signature
+        .map(|s| quote!(let builder = builder.simple_instruction_info(#s)?;));
+
+    Ok(quote! {
+        #item
+
+        /// Generated by `#[command]`.
+        pub struct #ty;
+
+        impl #ty {
+            pub const NAME: &'static str = #name;
+
+            pub fn definition() -> ::flow_lib::config::node::Definition {
+                ::flow_lib::command::derive::definition::<#input, #output>(
+                    Self::NAME,
+                    ::flow_lib::config::node::Permissions {
+                        user_tokens: #user_tokens,
+                    },
+                )
+            }
+
+            pub fn definition_json() -> ::std::string::String {
+                ::flow_lib::command::derive::to_json(&Self::definition())
+            }
+
+            pub fn build() -> ::flow_lib::command::builder::BuildResult {
+                let builder = ::flow_lib::command::builder::CmdBuilder::from_definition(
+                    Self::definition(),
+                );
+                #signature
+                ::std::result::Result::Ok(builder.build(#fn_name))
+            }
+        }
+
+        ::flow_lib::command::derive::__private::inventory::submit!(
+            ::flow_lib::command::CommandDescription::new(#name, |_| #ty::build())
+        );
+    })
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Io {
+    Input,
+    Output,
+}
+
+#[derive(Default)]
+struct FieldInfo {
+    name: Option<String>,
+    skip: bool,
+    optional: bool,
+    value_types: Vec<Ident>,
+    /// Types set with `#[input(value_type = "...")]`, replacing `value_types`.
+    explicit_types: Vec<Ident>,
+    passthrough: bool,
+}
+
+impl FieldInfo {
+    fn parse_serde(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("rename") {
+            self.name = Some(meta.value()?.parse::<LitStr>()?.value());
+        } else if meta.path.is_ident("with") || meta.path.is_ident("deserialize_with") {
+            let path = meta.value()?.parse::<LitStr>()?.value();
+            let mut parts = path.split("::").collect::<Vec<_>>();
+            if parts.last() == Some(&"opt") {
+                self.optional = true;
+                parts.pop();
+            }
+            if parts.first() == Some(&"serde_bytes") {
+                self.value_types = vec![value_type_ident("bytes").unwrap()];
+            } else if let Some(ty) = parts.last().and_then(|s| value_type_ident(s)) {
+                if parts.len() >= 2 && parts[parts.len() - 2] == "value" {
+                    self.value_types = vec![ty];
+                }
+            }
+        } else if meta.path.is_ident("default") {
+            self.optional = true;
+            skip_value(meta.input)?;
+        } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+            self.skip = true;
+        } else if meta.path.is_ident("flatten") {
+            return Err(meta.error("ports of `#[serde(flatten)]` fields can not be derived"));
+        } else {
+            skip_value(meta.input)?;
+        }
+        Ok(())
+    }
+
+    fn parse_flow(&mut self, meta: syn::meta::ParseNestedMeta, io: Io) -> syn::Result<()> {
+        if meta.path.is_ident("value_type") {
+            let s = meta.value()?.parse::<LitStr>()?;
+            let ty = value_type_ident(&s.value())
+                .ok_or_else(|| syn::Error::new_spanned(&s, "unknown value type"))?;
+            if io == Io::Output {
+                self.explicit_types.clear();
+            }
+            self.explicit_types.push(ty);
+        } else if meta.path.is_ident("passthrough") && io == Io::Input {
+            self.passthrough = true;
+        } else if meta.path.is_ident("required") && io == Io::Input {
+            self.optional = !meta.value()?.parse::<syn::LitBool>()?.value;
+        } else {
+            return Err(meta.error("unknown attribute"));
+        }
+        Ok(())
+    }
+}
+
+/// Field names of a `#[serde(rename_all = "...")]` convention.
+#[derive(Clone, Copy, Default)]
+enum RenameAll {
+    #[default]
+    None,
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameAll {
+    fn parse_serde(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("rename_all") {
+            if !meta.input.peek(Token![=]) {
+                return Err(meta.error("expected `rename_all = \"...\"`"));
+            }
+            let s = meta.value()?.parse::<LitStr>()?;
+            *self = match s.value().as_str() {
+                "snake_case" => Self::None,
+                "lowercase" => Self::Lower,
+                "UPPERCASE" => Self::Upper,
+                "PascalCase" => Self::Pascal,
+                "camelCase" => Self::Camel,
+                "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+                "kebab-case" => Self::Kebab,
+                "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+                _ => return Err(syn::Error::new_spanned(&s, "unknown rename rule")),
+            };
+        } else {
+            skip_value(meta.input)?;
+        }
+        Ok(())
+    }
+
+    /// Rename a snake_case field, like serde does.
+    fn apply(self, field: &str) -> String {
+        match self {
+            Self::None => field.to_owned(),
+            Self::Lower => field.to_ascii_lowercase(),
+            Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+            Self::Pascal => camel_case(field),
+            Self::Camel => {
+                let pascal = camel_case(field);
+                let mut chars = pascal.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_lowercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            Self::Kebab => field.replace('_', "-"),
+            Self::ScreamingKebab => field.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+}
+
+fn skip_value(input: ParseStream) -> syn::Result<()> {
+    if input.peek(Token![=]) {
+        input.parse::<Token![=]>()?;
+        input.parse::<syn::Expr>()?;
+    } else if input.peek(syn::token::Paren) {
+        let content;
+        syn::parenthesized!(content in input);
+        content.parse::<TokenStream2>()?;
+    }
+    Ok(())
+}
+
+fn expand_io(input: DeriveInput, io: Io) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => f.named.iter().collect::<Vec<_>>(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(_) => {
+                return Err(syn::Error::new_spanned(&input, "expected named fields"))
+            }
+        },
+        _ => return Err(syn::Error::new_spanned(&input, "expected a struct")),
+    };
+
+    let attr_name = match io {
+        Io::Input => "input",
+        Io::Output => "output",
+    };
+    let mut rename_all = RenameAll::default();
+    for attr in &input.attrs {
+        if attr.path().is_ident("serde") {
+            attr.parse_nested_meta(|meta| rename_all.parse_serde(meta))?;
+        }
+    }
+    let mut items = Vec::new();
+    for field in fields {
+        let mut info = FieldInfo::default();
+        let (ty, is_option) = match generic_arg(&field.ty, "Option") {
+            Some(inner) => (inner, true),
+            None => (&field.ty, false),
+        };
+        info.optional = is_option;
+        info.value_types = vec![rust_value_type(ty)];
+        for attr in &field.attrs {
+            if attr.path().is_ident("serde") {
+                attr.parse_nested_meta(|meta| info.parse_serde(meta))?;
+            }
+        }
+        for attr in &field.attrs {
+            if attr.path().is_ident(attr_name) {
+                attr.parse_nested_meta(|meta| info.parse_flow(meta, io))?;
+            }
+        }
+        if info.skip {
+            continue;
+        }
+        if !info.explicit_types.is_empty() {
+            info.value_types = std::mem::take(&mut info.explicit_types);
+        }
+        let name = info.name.clone().unwrap_or_else(|| {
+            let ident = field.ident.as_ref().unwrap().to_string();
+            rename_all.apply(ident.trim_start_matches("r#"))
+        });
+        let types = &info.value_types;
+        items.push(match io {
+            Io::Input => {
+                let required = !info.optional;
+                let passthrough = info.passthrough;
+                quote! {
+                    ::flow_lib::CmdInputDescription {
+                        name: #name.to_owned(),
+                        type_bounds: ::std::vec![#(::flow_lib::ValueType::#types),*],
+                        required: #required,
+                        passthrough: #passthrough,
+                    }
+                }
+            }
+            Io::Output => {
+                let ty = &types[0];
+                quote! {
+                    ::flow_lib::CmdOutputDescription {
+                        name: #name.to_owned(),
+                        r#type: ::flow_lib::ValueType::#ty,
+                    }
+                }
+            }
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(match io {
+        Io::Input => quote! {
+            impl #impl_generics ::flow_lib::command::derive::CommandInputs for #ident #ty_generics #where_clause {
+                fn inputs() -> ::std::vec::Vec<::flow_lib::CmdInputDescription> {
+                    ::std::vec![#(#items),*]
+                }
+            }
+        },
+        Io::Output => quote! {
+            impl #impl_generics ::flow_lib::command::derive::CommandOutputs for #ident #ty_generics #where_clause {
+                fn outputs() -> ::std::vec::Vec<::flow_lib::CmdOutputDescription> {
+                    ::std::vec![#(#items),*]
+                }
+            }
+        },
+    })
+}
+
+/// Get `T` of `Wrapper<T>`.
+fn generic_arg<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// Map names used in node-definition files to `ValueType` variants.
+fn value_type_ident(name: &str) -> Option<Ident> {
+    let variant = match name {
+        "bool" => "Bool",
+        "u8" => "U8",
+        "u16" => "U16",
+        "u32" => "U32",
+        "u64" => "U64",
+        "u128" => "U128",
+        "i8" => "I8",
+        "i16" => "I16",
+        "i32" => "I32",
+        "i64" => "I64",
+        "i128" => "I128",
+        "f32" => "F32",
+        "f64" => "F64",
+        "decimal" | "number" => "Decimal",
+        "pubkey" => "Pubkey",
+        "address" => "Address",
+        "keypair" => "Keypair",
+        "signature" => "Signature",
+        "string" => "String",
+        "bytes" => "Bytes",
+        "array" => "Array",
+        "object" => "Map",
+        "json" => "Json",
+        "free" => "Free",
+        _ => return None,
+    };
+    Some(Ident::new(variant, Span::call_site()))
+}
+
+fn rust_value_type(ty: &Type) -> Ident {
+    let name = match ty {
+        Type::Path(path) => {
+            let segment = path.path.segments.last().unwrap();
+            match segment.ident.to_string().as_str() {
+                "bool" => "bool",
+                n @ ("u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64"
+                | "i128" | "f32" | "f64") => return value_type_ident(n).unwrap(),
+                "String" => "string",
+                "Pubkey" => "pubkey",
+                "Keypair" => "keypair",
+                "Signature" => "signature",
+                "Decimal" => "decimal",
+                "Bytes" => "bytes",
+                // `Vec<u8>` is serialized as an array, unless `#[serde(with = "serde_bytes")]`
+                "Vec" => "array",
+                "HashMap" | "BTreeMap" | "IndexMap" | "Map" => "object",
+                "JsonValue" => "json",
+                "Value"
+                    if path.path.segments.len() > 1
+                        && path.path.segments[0].ident == "serde_json" =>
+                {
+                    "json"
+                }
+                _ => "free",
+            }
+        }
+        Type::Reference(r) if matches!(&*r.elem, Type::Path(p) if p.path.is_ident("str")) => {
+            "string"
+        }
+        _ => "free",
+    };
+    value_type_ident(name).unwrap()
+}
+
+/// `transfer_sol` -> `TransferSol`
+fn camel_case(s: &str) -> String {
+    s.split(['_', '-', ' '])
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(input: DeriveInput) -> String {
+        expand_io(input, Io::Input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_unsupported_serde() {
+        assert_eq!(
+            error(syn::parse_quote! {
+                struct Input {
+                    #[serde(flatten)]
+                    inner: Inner,
+                }
+            }),
+            "ports of `#[serde(flatten)]` fields can not be derived"
+        );
+        assert_eq!(
+            error(syn::parse_quote! {
+                #[serde(rename_all(deserialize = "camelCase"))]
+                struct Input {
+                    a: u64,
+                }
+            }),
+            "expected `rename_all = \"...\"`"
+        );
+        assert_eq!(
+            error(syn::parse_quote! {
+                #[serde(rename_all = "Title Case")]
+                struct Input {
+                    a: u64,
+                }
+            }),
+            "unknown rename rule"
+        );
+    }
+
+    #[test]
+    fn test_rename_all() {
+        let cases = [
+            (RenameAll::None, "fee_payer"),
+            (RenameAll::Lower, "fee_payer"),
+            (RenameAll::Upper, "FEE_PAYER"),
+            (RenameAll::Pascal, "FeePayer"),
+            (RenameAll::Camel, "feePayer"),
+            (RenameAll::ScreamingSnake, "FEE_PAYER"),
+            (RenameAll::Kebab, "fee-payer"),
+            (RenameAll::ScreamingKebab, "FEE-PAYER"),
+        ];
+        for (rule, name) in cases {
+            assert_eq!(rule.apply("fee_payer"), name);
+        }
+    }
+}
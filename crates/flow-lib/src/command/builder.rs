@@ -67,12 +67,14 @@ use super::{CommandError, CommandTrait};
 use crate::{
     command::InstructionInfo,
     config::node::{Definition, Permissions},
+    policy::Policy,
     utils::BoxFuture,
     Context, Name,
 };
 use serde::{de::DeserializeOwned, Serialize};
 use std::future::Future;
 use thiserror::Error as ThisError;
+use tracing::Instrument;
 
 /// `fn build() -> BuildResult`.
 pub type BuildResult = Result<Box<dyn CommandTrait>, CommandError>;
@@ -85,6 +87,7 @@ pub type BuilderCache = once_cell::sync::Lazy<Result<CmdBuilder, BuilderError>>;
 pub struct CmdBuilder {
     def: Definition,
     signature_name: Option<String>,
+    stream_outputs: Vec<Name>,
 }
 
 #[derive(ThisError, Debug, Clone)]
@@ -111,9 +114,20 @@ impl CmdBuilder {
         Ok(Self {
             def,
             signature_name: None,
+            stream_outputs: Vec::new(),
         })
     }
 
+    /// Start building command with a [`Definition`] created in code, see
+    /// [`derive`][crate::command::derive].
+    pub fn from_definition(def: Definition) -> Self {
+        Self {
+            def,
+            signature_name: None,
+            stream_outputs: Vec::new(),
+        }
+    }
+
     /// Check that the command name in node-definition is equal to this name, to prevent accidentally
     /// using the wrong node-definition.
     pub fn check_name(self, name: &str) -> Result<Self, BuilderError> {
@@ -140,6 +154,16 @@ impl CmdBuilder {
         }
     }
 
+    /// Mark an output as streamed, see [`CommandTrait::stream_outputs`].
+    pub fn stream_output(mut self, name: &str) -> Result<Self, BuilderError> {
+        if self.def.sources.iter().any(|x| x.name == name) {
+            self.stream_outputs.push(name.to_owned());
+            Ok(self)
+        } else {
+            Err(BuilderError::OutputNotFound(name.to_owned()))
+        }
+    }
+
     /// Build the command, `f` will be used as this command's [`fn run()`][CommandTrait::run].
     ///
     /// - `f` must be an `async fn(Context, Input) -> Result<Output, CommandError>`.
@@ -157,7 +181,9 @@ impl CmdBuilder {
             inputs: Vec<crate::CmdInputDescription>,
             outputs: Vec<crate::CmdOutputDescription>,
             instruction_info: Option<InstructionInfo>,
+            stream_outputs: Vec<Name>,
             permissions: Permissions,
+            policy: Policy,
             run: Box<dyn Fn(Context, T) -> Fut + Send + Sync + 'static>,
         }
 
@@ -175,6 +201,10 @@ impl CmdBuilder {
                 self.instruction_info.clone()
             }
 
+            fn stream_outputs(&self) -> Vec<Name> {
+                self.stream_outputs.clone()
+            }
+
             fn inputs(&self) -> Vec<crate::CmdInputDescription> {
                 self.inputs.clone()
             }
@@ -188,18 +218,27 @@ impl CmdBuilder {
                 ctx: Context,
                 params: crate::ValueSet,
             ) -> BoxFuture<'b, Result<crate::ValueSet, CommandError>> {
-                match value::from_map(params) {
+                // inputs are coerced here too, for commands that are not run by the runner
+                let input = super::coerce_inputs(&self.inputs, params)
+                    .map_err(CommandError::from)
+                    .and_then(|params| Ok(value::from_map(params)?));
+                match input {
                     Ok(input) => {
+                        let span = ctx.node_span();
                         let fut = (self.run)(ctx, input);
-                        Box::pin(async move { Ok(value::to_map(&fut.await?)?) })
+                        Box::pin(async move { Ok(value::to_map(&fut.await?)?) }.instrument(span))
                     }
-                    Err(error) => Box::pin(async move { Err(error.into()) }),
+                    Err(error) => Box::pin(async move { Err(error) }),
                 }
             }
 
             fn permissions(&self) -> Permissions {
                 self.permissions.clone()
             }
+
+            fn policy(&self) -> Policy {
+                self.policy.clone()
+            }
         }
 
         let mut cmd = Command {
@@ -225,8 +264,10 @@ impl CmdBuilder {
                     r#type: x.r#type,
                 })
                 .collect(),
-            instruction_info: None,
+            instruction_info: self.def.instruction_info,
+            stream_outputs: self.stream_outputs,
             permissions: self.def.permissions,
+            policy: self.def.policy,
         };
 
         if let Some(name) = self.signature_name {
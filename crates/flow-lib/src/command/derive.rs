@@ -0,0 +1,222 @@
+//! Implement commands from Rust types, without writing node-definition files.
+//!
+//! - `#[derive(CommandInputs)]` and `#[derive(CommandOutputs)]` describe inputs and outputs from
+//!   struct fields.
+//! - `#[command(name = "...")]` on an `async fn(Context, Input) -> Result<Output, CommandError>`
+//!   generates a `{Name}Command` type with `NAME`, `definition()`, `definition_json()` and
+//!   `build()`, and registers the command with [`inventory`].
+//!
+//! Value types are inferred from field types, `Option<T>` and `#[serde(default)]` fields are not
+//! required, and `#[serde(with = "value::pubkey")]` (also `keypair`, `signature`, `decimal` and
+//! their `opt` variants) map to the matching [`ValueType`][crate::ValueType]. `Vec<u8>` is an
+//! array, or bytes with `#[serde(with = "serde_bytes")]`. Names follow `#[serde(rename)]` and
+//! `#[serde(rename_all)]`, `#[serde(flatten)]` fields are not supported.
+//! Use `#[input(value_type = "...")]` (can be repeated), `#[input(passthrough)]`,
+//! `#[input(required = false)]` and `#[output(value_type = "...")]` to override.
+//!
+//! Attribute options of `#[command]`:
+//! - `name = "..."`: command name.
+//! - `signature = "..."`: use [`InstructionInfo::simple`][super::InstructionInfo::simple] with
+//!   this output as the signature.
+//! - `user_tokens`: request [`Permissions::user_tokens`].
+//!
+//! # Example
+//!
+//! ```
+//! use flow_lib::{command::{derive::*, CommandError}, Context};
+//! use serde::{Deserialize, Serialize};
+//! use solana_sdk::pubkey::Pubkey;
+//!
+//! #[derive(Deserialize, CommandInputs)]
+//! struct Input {
+//!     #[serde(with = "value::pubkey")]
+//!     owner: Pubkey,
+//!     #[serde(default)]
+//!     memo: String,
+//! }
+//!
+//! #[derive(Serialize, CommandOutputs)]
+//! struct Output {
+//!     #[serde(with = "value::pubkey")]
+//!     owner: Pubkey,
+//! }
+//!
+//! #[command(name = "echo_owner")]
+//! async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+//!     Ok(Output { owner: input.owner })
+//! }
+//!
+//! let def = EchoOwnerCommand::definition();
+//! assert_eq!(def.targets[0].type_bounds, [flow_lib::ValueType::Pubkey]);
+//! assert!(!def.targets[1].required);
+//! ```
+
+use crate::{
+    config::node::{Data, Definition, Permissions, Source, Target},
+    CmdInputDescription, CmdOutputDescription, CommandType,
+};
+
+pub use flow_lib_macros::{command, CommandInputs, CommandOutputs};
+
+/// Describe inputs of a command, use `#[derive(CommandInputs)]` to implement.
+pub trait CommandInputs {
+    fn inputs() -> Vec<CmdInputDescription>;
+}
+
+/// Describe outputs of a command, use `#[derive(CommandOutputs)]` to implement.
+pub trait CommandOutputs {
+    fn outputs() -> Vec<CmdOutputDescription>;
+}
+
+/// Build a node-definition from input and output types.
+pub fn definition<I: CommandInputs, O: CommandOutputs>(
+    name: &str,
+    permissions: Permissions,
+) -> Definition {
+    Definition {
+        r#type: CommandType::Native,
+        data: Data {
+            node_id: name.to_owned(),
+        },
+        sources: O::outputs()
+            .into_iter()
+            .map(|o| Source {
+                name: o.name,
+                r#type: o.r#type,
+            })
+            .collect(),
+        targets: I::inputs()
+            .into_iter()
+            .map(|i| Target {
+                name: i.name,
+                type_bounds: i.type_bounds,
+                required: i.required,
+                passthrough: i.passthrough,
+            })
+            .collect(),
+        permissions,
+        policy: <_>::default(),
+        instruction_info: None,
+    }
+}
+
+/// Serialize a node-definition as pretty-printed JSON.
+pub fn to_json(def: &Definition) -> String {
+    serde_json::to_string_pretty(def).expect("Definition is always serializable")
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use inventory;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{command::CommandError, Context, ValueType};
+    use serde::{Deserialize, Serialize};
+    use solana_sdk::{pubkey::Pubkey, signature::Signature};
+    use value::Decimal;
+
+    #[derive(Deserialize, CommandInputs)]
+    struct Input {
+        #[serde(with = "value::keypair")]
+        #[allow(dead_code)]
+        payer: solana_sdk::signature::Keypair,
+        #[serde(with = "value::pubkey::opt", default)]
+        #[input(passthrough)]
+        recipient: Option<Pubkey>,
+        #[serde(with = "value::decimal")]
+        amount: Decimal,
+        #[serde(rename = "memo_text")]
+        #[input(value_type = "string", value_type = "json")]
+        memo: Option<serde_json::Value>,
+        #[serde(skip)]
+        #[allow(dead_code)]
+        ignored: u8,
+    }
+
+    #[derive(Serialize, CommandOutputs)]
+    #[serde(rename_all = "camelCase")]
+    struct Output {
+        #[serde(with = "value::signature")]
+        signature: Signature,
+        amount: u64,
+        #[serde(with = "serde_bytes")]
+        bytes: Vec<u8>,
+        byte_array: Vec<u8>,
+    }
+
+    #[command(name = "test_derive", signature = "signature", user_tokens)]
+    async fn run(_: Context, input: Input) -> Result<Output, CommandError> {
+        let _ = (input.recipient, input.amount, input.memo);
+        Ok(Output {
+            signature: Signature::default(),
+            amount: 0,
+            bytes: Vec::new(),
+            byte_array: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_derive() {
+        let inputs = Input::inputs();
+        assert_eq!(
+            inputs
+                .iter()
+                .map(|i| (
+                    i.name.as_str(),
+                    i.type_bounds.clone(),
+                    i.required,
+                    i.passthrough
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("payer", vec![ValueType::Keypair], true, false),
+                ("recipient", vec![ValueType::Pubkey], false, true),
+                ("amount", vec![ValueType::Decimal], true, false),
+                (
+                    "memo_text",
+                    vec![ValueType::String, ValueType::Json],
+                    false,
+                    false
+                ),
+            ]
+        );
+        let outputs = Output::outputs();
+        assert_eq!(
+            outputs
+                .iter()
+                .map(|o| (o.name.as_str(), o.r#type.clone()))
+                .collect::<Vec<_>>(),
+            [
+                ("signature", ValueType::Signature),
+                ("amount", ValueType::U64),
+                ("bytes", ValueType::Bytes),
+                ("byteArray", ValueType::Array),
+            ]
+        );
+        // sequences of `u8` are only bytes if they are not empty, `serde_bytes` always is
+        let output = value::to_map(&Output {
+            signature: Signature::default(),
+            amount: 0,
+            bytes: Vec::new(),
+            byte_array: Vec::new(),
+        })
+        .unwrap();
+        assert!(matches!(output["bytes"], value::Value::Bytes(_)));
+        assert!(matches!(output["byteArray"], value::Value::Array(_)));
+
+        let def = TestDeriveCommand::definition();
+        assert!(def.permissions.user_tokens);
+        let json: serde_json::Value =
+            serde_json::from_str(&TestDeriveCommand::definition_json()).unwrap();
+        assert_eq!(json["data"]["node_id"], "test_derive");
+
+        let cmd = TestDeriveCommand::build().unwrap();
+        assert_eq!(cmd.name(), "test_derive");
+        assert_eq!(cmd.instruction_info().unwrap().signature, "signature");
+        assert!(inventory::iter::<crate::command::CommandDescription>()
+            .any(|d| d.name == "test_derive"));
+    }
+}
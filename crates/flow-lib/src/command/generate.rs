@@ -0,0 +1,334 @@
+//! Generate node-definition files from registered commands.
+//!
+//! [`generate`] builds every [`CommandDescription`] registered with [`inventory`] and creates or
+//! updates its node-definition: `data.node_id`, `sources`, `targets`, `permissions` and
+//! `instruction_info` are taken from the command, other fields such as `usage`, `design`,
+//! `tooltip` and `defaultValue` are kept as they are in the file.
+//!
+//! Files are matched by `data.node_id`, new commands are written to `{root}/generated/{name}.json`.
+
+use super::{CommandDescription, CommandError, CommandTrait};
+use crate::{
+    config::client::{Extra, NodeData, TargetsForm},
+    CommandType, Name,
+};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+pub struct Generated {
+    pub name: Name,
+    pub path: PathBuf,
+    pub json: JsonValue,
+    /// `false` if the file exists and is already up to date.
+    pub changed: bool,
+}
+
+impl Generated {
+    /// Pretty-printed JSON to write to [`path`][Generated::path].
+    pub fn content(&self) -> String {
+        serde_json::to_string_pretty(&self.json).unwrap() + "\n"
+    }
+}
+
+/// Find node-definition files in `root` and its sub-directories, by command name.
+pub fn find_definitions(root: &Path) -> std::io::Result<BTreeMap<Name, PathBuf>> {
+    fn walk(dir: &Path, result: &mut BTreeMap<Name, PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(&path, result)?;
+            } else if path.extension().is_some_and(|e| e == "json") {
+                let Ok(json) = serde_json::from_slice::<JsonValue>(&std::fs::read(&path)?) else {
+                    continue;
+                };
+                if let Some(name) = json["data"]["node_id"].as_str() {
+                    result.insert(name.to_owned(), path);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    let mut result = BTreeMap::new();
+    walk(root, &mut result)?;
+    Ok(result)
+}
+
+/// Generate node-definitions of all registered commands, commands that fail to build are
+/// returned as errors.
+pub fn generate(root: &Path) -> std::io::Result<Vec<Result<Generated, (Name, CommandError)>>> {
+    let files = find_definitions(root)?;
+    let mut descriptions = inventory::iter::<CommandDescription>().collect::<Vec<_>>();
+    descriptions.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut result = Vec::new();
+    for d in descriptions {
+        let name = d.name.to_string();
+        let cmd = match (d.fn_new)(&placeholder_node_data(&name)) {
+            Ok(cmd) => cmd,
+            Err(error) => {
+                result.push(Err((name, error)));
+                continue;
+            }
+        };
+        let (path, existing) = match files.get(&name) {
+            Some(path) => {
+                let existing = serde_json::from_slice::<JsonValue>(&std::fs::read(path)?)?;
+                (path.clone(), Some(existing))
+            }
+            None => (root.join("generated").join(format!("{name}.json")), None),
+        };
+        let json = update_definition(&*cmd, existing.clone());
+        result.push(Ok(Generated {
+            name,
+            path,
+            changed: existing.as_ref() != Some(&json),
+            json,
+        }));
+    }
+    Ok(result)
+}
+
+/// [`NodeData`] used to build commands, commands that need form data will fail to build.
+fn placeholder_node_data(name: &str) -> NodeData {
+    NodeData {
+        r#type: CommandType::Native,
+        node_id: name.to_owned(),
+        sources: Vec::new(),
+        targets: Vec::new(),
+        targets_form: TargetsForm {
+            form_data: json!({}),
+            extra: Extra::default(),
+            wasm_bytes: None,
+        },
+        policy: None,
+    }
+}
+
+/// Create or update the node-definition of `cmd`.
+pub fn update_definition(cmd: &dyn CommandTrait, existing: Option<JsonValue>) -> JsonValue {
+    let name = cmd.name();
+    let mut json = match existing {
+        Some(JsonValue::Object(map)) => map,
+        _ => template(&name),
+    };
+
+    let data = json
+        .entry("data")
+        .or_insert_with(|| json!({}))
+        .as_object_mut();
+    if let Some(data) = data {
+        data.insert("node_id".to_owned(), name.into());
+    }
+
+    let sources = cmd
+        .outputs()
+        .into_iter()
+        .map(|o| {
+            let mut port = JsonMap::new();
+            port.insert("name".to_owned(), o.name.into());
+            port.insert("type".to_owned(), json!(o.r#type));
+            port.insert("defaultValue".to_owned(), JsonValue::Null);
+            port.insert("tooltip".to_owned(), "".into());
+            port
+        })
+        .collect();
+    let sources = merge_ports(json.get("sources"), sources, &["name", "type"]);
+    json.insert("sources".to_owned(), sources);
+
+    let targets = cmd
+        .inputs()
+        .into_iter()
+        .map(|i| {
+            let mut port = JsonMap::new();
+            port.insert("name".to_owned(), i.name.into());
+            port.insert("type_bounds".to_owned(), json!(i.type_bounds));
+            port.insert("required".to_owned(), i.required.into());
+            port.insert("defaultValue".to_owned(), JsonValue::Null);
+            port.insert("tooltip".to_owned(), "".into());
+            port.insert("passthrough".to_owned(), i.passthrough.into());
+            port
+        })
+        .collect();
+    let targets = merge_ports(
+        json.get("targets"),
+        targets,
+        &["name", "type_bounds", "required", "passthrough"],
+    );
+    json.insert("targets".to_owned(), targets);
+
+    let permissions = cmd.permissions();
+    if json.contains_key("permissions") || permissions.user_tokens {
+        json.insert("permissions".to_owned(), json!(permissions));
+    }
+
+    match cmd.instruction_info() {
+        Some(info) => {
+            json.insert("instruction_info".to_owned(), json!(info));
+        }
+        None => {
+            json.remove("instruction_info");
+        }
+    }
+
+    JsonValue::Object(json)
+}
+
+/// Use `new` ports in order, keeping other fields of existing ports with the same name.
+fn merge_ports(
+    existing: Option<&JsonValue>,
+    new: Vec<JsonMap<String, JsonValue>>,
+    generated_keys: &[&str],
+) -> JsonValue {
+    let existing = existing
+        .and_then(JsonValue::as_array)
+        .map(|a| a.as_slice())
+        .unwrap_or_default();
+    new.into_iter()
+        .map(|port| {
+            let old = existing
+                .iter()
+                .filter_map(JsonValue::as_object)
+                .find(|p| p.get("name") == port.get("name"));
+            match old {
+                Some(old) => {
+                    let mut old = old.clone();
+                    for key in generated_keys {
+                        if let Some(value) = port.get(*key) {
+                            old.insert((*key).to_owned(), value.clone());
+                        }
+                    }
+                    JsonValue::Object(old)
+                }
+                None => JsonValue::Object(port),
+            }
+        })
+        .collect()
+}
+
+fn template(name: &str) -> JsonMap<String, JsonValue> {
+    let display_name = name
+        .split('_')
+        .map(|w| {
+            let mut chars = w.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let json = json!({
+        "type": "native",
+        "data": {
+            "node_definition_version": "0.1",
+            "unique_id": "",
+            "node_id": name,
+            "version": "0.1",
+            "display_name": display_name,
+            "description": "",
+            "tags": [],
+            "related_to": [],
+            "resources": {
+                "source_code_url": "",
+                "documentation_url": ""
+            },
+            "usage": {
+                "license": "Apache-2.0",
+                "license_url": "",
+                "pricing": {
+                    "currency": "USDC",
+                    "purchase_price": 0,
+                    "price_per_run": 0,
+                    "custom": {
+                        "unit": "monthly",
+                        "value": "0"
+                    }
+                }
+            },
+            "authors": [
+                {
+                    "name": "Space Operator",
+                    "contact": ""
+                }
+            ],
+            "design": {
+                "width": 0,
+                "height": 0,
+                "icon_url": "",
+                "backgroundColorDark": "#000000",
+                "backgroundColor": "#fff"
+            },
+            "options": {}
+        },
+        "sources": [],
+        "targets": [],
+        "targets_form.json_schema": {},
+        "targets_form.ui_schema": {}
+    });
+    match json {
+        JsonValue::Object(map) => map,
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{command::builder::CmdBuilder, config::node::Definition, Context};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Empty {}
+
+    #[test]
+    fn test_update_definition() {
+        let existing = json!({
+            "type": "native",
+            "data": { "node_id": "transfer", "display_name": "Transfer" },
+            "sources": [
+                { "name": "signature", "type": "signature", "tooltip": "tx signature" },
+                { "name": "removed", "type": "u64" }
+            ],
+            "targets": [
+                { "name": "amount", "type_bounds": ["u64"], "required": true, "passthrough": false, "tooltip": "in lamports" }
+            ],
+            "targets_form.ui_schema": {}
+        });
+        let def = json!({
+            "type": "native",
+            "data": { "node_id": "transfer" },
+            "sources": [{ "name": "signature", "type": "signature" }],
+            "targets": [
+                { "name": "amount", "type_bounds": ["f64"], "required": true, "passthrough": false },
+                { "name": "memo", "type_bounds": ["string"], "required": false, "passthrough": false }
+            ]
+        });
+        let cmd = CmdBuilder::new(&def.to_string())
+            .unwrap()
+            .simple_instruction_info("signature")
+            .unwrap()
+            .build(|_: Context, _: Empty| async { Ok(Empty {}) });
+
+        let json = update_definition(&*cmd, Some(existing));
+        assert_eq!(json["data"]["display_name"], "Transfer");
+        assert_eq!(json["sources"].as_array().unwrap().len(), 1);
+        assert_eq!(json["sources"][0]["tooltip"], "tx signature");
+        assert_eq!(json["targets"][0]["tooltip"], "in lamports");
+        assert_eq!(json["targets"][0]["type_bounds"], json!(["f64"]));
+        assert_eq!(json["targets"][1]["name"], "memo");
+        assert_eq!(json["instruction_info"]["signature"], "signature");
+        assert!(json.get("targets_form.ui_schema").is_some());
+
+        let parsed = serde_json::from_value::<Definition>(json.clone()).unwrap();
+        assert_eq!(parsed.instruction_info, cmd.instruction_info());
+        assert_eq!(update_definition(&*cmd, Some(json.clone())), json);
+
+        let new = update_definition(&*cmd, None);
+        assert_eq!(new["data"]["display_name"], "Transfer");
+        assert_eq!(new["targets"][1]["defaultValue"], JsonValue::Null);
+    }
+}
@@ -1,9 +1,10 @@
 //! [`CommandTrait`] and command [`builder`].
 //!
 //! To make a new [`native`][crate::config::CommandType::Native] command:
-//! 1. Implement [`CommandTrait`], 2 ways;
+//! 1. Implement [`CommandTrait`], 3 ways;
 //!     - Manually implement it to your types.
 //!     - Use [`builder`] helper.
+//!     - Use [`derive`] macros.
 //! 2. Use [`inventory::submit`] with a [`CommandDescription`] to register the command at compile-time.
 
 use crate::{
@@ -12,12 +13,16 @@ use crate::{
         ValueSet,
     },
     context::Context,
-    ValueType,
+    policy::Policy,
+    FlowError, ValueType,
 };
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use value::Value;
 
 pub mod builder;
+pub mod derive;
+pub mod generate;
 
 /// Error type when of commmands.
 pub type CommandError = anyhow::Error;
@@ -90,18 +95,47 @@ pub trait CommandTrait: Send + Sync + 'static {
         None
     }
 
+    /// List of outputs that are emitted incrementally with [`Context::emit`], nodes downstream
+    /// of them run once for each emitted value without waiting for [`run`][CommandTrait::run] to
+    /// return. See [`runner`][crate::runner#streams].
+    fn stream_outputs(&self) -> Vec<Name> {
+        Vec::new()
+    }
+
+    /// Default retry, timeout and circuit-breaker policy, can be overridden with
+    /// [`NodeConfig::policy`][crate::NodeConfig::policy]. See [`crate::policy`].
+    fn policy(&self) -> Policy {
+        Policy::default()
+    }
+
     /// Specify requested permissions of this command.
     fn permissions(&self) -> Permissions {
         Permissions::default()
     }
 }
 
+/// Convert `values` to the `type_bounds` of `inputs` with [`value::coerce_any`], before they
+/// are passed to [`CommandTrait::run`]. The runner does this for all commands, and commands made
+/// with [`builder`] also do it in their `run`.
+pub fn coerce_inputs(
+    inputs: &[CmdInputDescription],
+    mut values: ValueSet,
+) -> Result<ValueSet, FlowError> {
+    for input in inputs {
+        if let Some(value) = values.get_mut(&input.name) {
+            *value = value::coerce_any(std::mem::take(value), &input.type_bounds)
+                .map_err(|error| FlowError::input_validation(Some(&input.name), error))?;
+        }
+    }
+    Ok(values)
+}
+
 /// Specify the order with which a command will return its output:
 /// - [`before`][InstructionInfo::before]: list of output names returned before instructions are
-/// sent.
+///   sent.
 /// - [`signature`][InstructionInfo::signature]: name of the signature output.
 /// - [`after`][InstructionInfo::after]: list of output names returned after instructions are sent.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct InstructionInfo {
     pub before: Vec<Name>,
     pub signature: Name,
@@ -155,3 +189,84 @@ impl CommandDescription {
 }
 
 inventory::collect!(CommandDescription);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coerce_inputs() {
+        let input = |name: &str, ty: ValueType| CmdInputDescription {
+            name: name.to_owned(),
+            type_bounds: [ty].to_vec(),
+            required: true,
+            passthrough: false,
+        };
+        let inputs = [
+            input("amount", ValueType::Decimal),
+            input("n", ValueType::U8),
+        ];
+
+        let values = coerce_inputs(&inputs, value::map! { "amount" => "1.5", "n" => "3" }).unwrap();
+        assert_eq!(values["amount"], Value::Decimal(value::Decimal::new(15, 1)));
+        assert_eq!(values["n"], Value::U64(3));
+
+        let error = coerce_inputs(&inputs, value::map! { "n" => 300u64 }).unwrap_err();
+        assert_eq!(
+            error,
+            FlowError::input_validation(Some("n"), "300 is out of range of u8")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_builder_coerces_inputs() {
+        #[derive(Deserialize)]
+        struct Input {
+            n: u8,
+            label: String,
+        }
+
+        #[derive(Serialize)]
+        struct Output {
+            label: String,
+        }
+
+        let cmd = builder::CmdBuilder::new(
+            r#"{
+                "type": "native",
+                "data": { "node_id": "test_builder_coerces_inputs" },
+                "sources": [{ "name": "label", "type": "string" }],
+                "targets": [
+                    { "name": "n", "type_bounds": ["u8"], "required": true, "passthrough": false },
+                    { "name": "label", "type_bounds": ["string"], "required": true, "passthrough": false }
+                ]
+            }"#,
+        )
+        .unwrap()
+        .build(|_, input: Input| async move {
+            Ok(Output {
+                label: format!("{}{}", input.label, input.n),
+            })
+        });
+
+        let output = cmd
+            .run(
+                Context::default(),
+                value::map! { "n" => "3", "label" => 12u64 },
+            )
+            .await
+            .unwrap();
+        assert_eq!(output["label"], Value::String("123".into()));
+        let error = cmd
+            .run(
+                Context::default(),
+                value::map! { "n" => 300u64, "label" => "a" },
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            FlowError::from_command_error(&error),
+            FlowError::input_validation(Some("n"), "300 is out of range of u8")
+        );
+    }
+}
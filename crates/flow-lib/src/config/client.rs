@@ -1,6 +1,6 @@
 //! Parse JS front-end flow config into back-end flow config
 
-use crate::{CommandType, FlowId, FlowRunId, NodeId, SolanaNet, ValueType};
+use crate::{policy::Policy, CommandType, FlowId, FlowRunId, NodeId, SolanaNet, ValueType};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use serde_with::serde_as;
@@ -25,6 +25,8 @@ pub struct ClientConfig {
     pub partial_config: Option<PartialConfig>,
     #[serde(default)]
     pub collect_instructions: bool,
+    /// Number of `interflow` nodes above this flow, limited by
+    /// [`MAX_CALL_DEPTH`][crate::runner::MAX_CALL_DEPTH].
     #[serde(default)]
     pub call_depth: u32,
     #[serde(default = "default_origin")]
@@ -45,15 +47,26 @@ pub enum FlowRunOrigin {
     },
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Where to load outputs of nodes that are not run by a partial run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ValuesConfig {
+    /// Previous run of each node.
     #[serde(default)]
     pub nodes: HashMap<NodeId, FlowRunId>,
+    /// Previous run of nodes not in [`nodes`][Self::nodes].
     pub default_run_id: Option<FlowRunId>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+impl ValuesConfig {
+    pub fn flow_run_id(&self, node_id: &NodeId) -> Option<FlowRunId> {
+        self.nodes.get(node_id).copied().or(self.default_run_id)
+    }
+}
+
+/// Run a subset of nodes, using outputs of previous runs for the other nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PartialConfig {
+    /// Nodes to run.
     pub only_nodes: Vec<NodeId>,
     pub values_config: ValuesConfig,
 }
@@ -93,6 +106,9 @@ pub struct NodeData {
     pub sources: Vec<Source>,
     pub targets: Vec<Target>,
     pub targets_form: TargetsForm,
+    /// Override the policy from the node definition.
+    #[serde(default)]
+    pub policy: Option<Policy>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -102,6 +118,8 @@ pub struct NodeDataSkipWasm {
     pub sources: Vec<Source>,
     pub targets: Vec<Target>,
     pub targets_form: TargetsFormSkipWasm,
+    #[serde(default)]
+    pub policy: Option<Policy>,
 }
 
 impl From<NodeData> for NodeDataSkipWasm {
@@ -112,6 +130,7 @@ impl From<NodeData> for NodeDataSkipWasm {
             sources,
             targets,
             targets_form,
+            policy,
         }: NodeData,
     ) -> Self {
         let TargetsForm {
@@ -123,6 +142,7 @@ impl From<NodeData> for NodeDataSkipWasm {
             sources,
             targets,
             targets_form: TargetsFormSkipWasm { form_data, extra },
+            policy,
         }
     }
 }
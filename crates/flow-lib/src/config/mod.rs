@@ -1,68 +1,13 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::{collections::HashMap, num::NonZeroU64, str::FromStr};
+use std::{collections::HashMap, num::NonZeroU64, path::PathBuf, str::FromStr};
 use thiserror::Error as ThisError;
 use uuid::Uuid;
 
 pub mod client;
 pub mod node;
 
-/// Use to describe input types and output types of nodes.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum ValueType {
-    #[serde(rename = "bool")]
-    Bool,
-    #[serde(rename = "u8")]
-    U8,
-    #[serde(rename = "u16")]
-    U16,
-    #[serde(rename = "u32")]
-    U32,
-    #[serde(rename = "u64")]
-    U64,
-    #[serde(rename = "u128")]
-    U128,
-    #[serde(rename = "i8")]
-    I8,
-    #[serde(rename = "i16")]
-    I16,
-    #[serde(rename = "i32")]
-    I32,
-    #[serde(rename = "i64")]
-    I64,
-    #[serde(rename = "i128")]
-    I128,
-    #[serde(rename = "f32")]
-    F32,
-    #[serde(rename = "f64")]
-    F64,
-    #[serde(alias = "number")]
-    #[serde(rename = "decimal")]
-    Decimal,
-    #[serde(rename = "pubkey")]
-    Pubkey,
-    // Wormhole address
-    #[serde(rename = "address")]
-    Address,
-    #[serde(rename = "keypair")]
-    Keypair,
-    #[serde(rename = "signature")]
-    Signature,
-    #[serde(rename = "string")]
-    String,
-    #[serde(rename = "bytes")]
-    Bytes,
-    #[serde(rename = "array")]
-    Array,
-    #[serde(rename = "object")]
-    Map,
-    #[serde(rename = "json")]
-    Json,
-    #[serde(rename = "free")]
-    Free,
-    #[serde(other)]
-    Other,
-}
+pub use value::ValueType;
 
 pub type FlowId = i32;
 pub type NodeId = Uuid;
@@ -109,6 +54,12 @@ pub struct FlowConfig {
     pub edges: Vec<(Gate, Gate)>,
     #[serde(default)]
     pub instructions_bundling: client::BundlingMode,
+    /// See [`ClientConfig::call_depth`][client::ClientConfig::call_depth].
+    #[serde(default)]
+    pub call_depth: u32,
+    /// Run only some nodes, see [`FlowRunner`][crate::runner::FlowRunner].
+    #[serde(default)]
+    pub partial_config: Option<client::PartialConfig>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -117,6 +68,9 @@ pub struct NodeConfig {
     pub command_name: Name,
     pub form_data: JsonValue,
     pub client_node_data: client::NodeData,
+    /// Override [`CommandTrait::policy`][crate::command::CommandTrait::policy] for this node.
+    #[serde(default)]
+    pub policy: Option<crate::policy::Policy>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -161,6 +115,57 @@ impl Default for ContextConfig {
     }
 }
 
+/// Where to read secrets from and which nodes can read them.
+///
+/// This is configuration of the server that runs flows, it is not part of [`FlowConfig`] so that
+/// flows cannot choose a backend or grant themselves access. Create a service with
+/// [`secret_store::from_config`][crate::secret_store::from_config] and pass it to
+/// [`FlowRunner::secrets`][crate::runner::FlowRunner::secrets].
+///
+/// Only names are stored here, secret values are never part of the config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct SecretsConfig {
+    #[serde(default)]
+    pub backend: SecretsBackend,
+    /// Names of secrets that each node is allowed to read.
+    #[serde(default)]
+    pub grants: HashMap<NodeId, Vec<String>>,
+}
+
+impl SecretsConfig {
+    /// Check if node `node_id` is allowed to read secret `name`.
+    pub fn is_granted(&self, node_id: Option<NodeId>, name: &str) -> bool {
+        node_id
+            .and_then(|id| self.grants.get(&id))
+            .map(|names| names.iter().any(|n| n == name))
+            .unwrap_or(false)
+    }
+}
+
+/// Backends implemented in [`secret_store`][crate::secret_store].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SecretsBackend {
+    /// No secrets available.
+    #[default]
+    None,
+    /// Read from environment variables of the current process, `NAME` is read from
+    /// `{prefix}NAME`.
+    Env { prefix: String },
+    /// Read from a local file encrypted with AES-256-GCM-SIV,
+    /// the base64-encoded key is read from environment variable `key_env`.
+    File { path: PathBuf, key_env: String },
+    /// Read from a Vault-style KV version 2 HTTP API,
+    /// secret names are keys of the secret at `{url}/v1/{mount}/data/{path}`.
+    /// The access token is read from environment variable `token_env`.
+    Vault {
+        url: String,
+        mount: String,
+        path: String,
+        token_env: String,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HttpClientConfig {
     pub timeout_in_secs: NonZeroU64,
@@ -260,6 +265,7 @@ impl FlowConfig {
                 id: n.id,
                 command_name: n.data.node_id.clone(),
                 form_data: n.data.targets_form.form_data.clone(),
+                policy: n.data.policy.clone(),
                 client_node_data: n.data,
             })
             .collect();
@@ -281,6 +287,8 @@ impl FlowConfig {
             nodes,
             edges,
             instructions_bundling: config.instructions_bundling,
+            call_depth: config.call_depth,
+            partial_config: config.partial_config,
         }
     }
 
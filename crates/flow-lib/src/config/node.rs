@@ -9,6 +9,10 @@ pub struct Definition {
     pub targets: Vec<Target>,
     #[serde(default)]
     pub permissions: Permissions,
+    #[serde(default)]
+    pub policy: crate::policy::Policy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction_info: Option<crate::command::InstructionInfo>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
@@ -7,17 +7,29 @@
 //! - [`get_jwt`]
 //! - [`execute`]
 //! - [`signer`]
+//! - [`secrets`]
+//! - [`stream`]
+//! - [`get_flow`]
+//! - [`run_store`]
 
 use crate::{
-    config::{client::FlowRunOrigin, Endpoints},
+    config::{
+        client::{ClientConfig, FlowRunOrigin},
+        Endpoints,
+    },
+    events::{self, EventContent, LogLevel},
+    record,
     solana::Instructions,
     utils::Extensions,
-    ContextConfig, FlowRunId, NodeId, UserId,
+    ContextConfig, FlowId, FlowRunId, NodeId, UserId,
 };
 use bytes::Bytes;
 use solana_client::nonblocking::rpc_client::RpcClient as SolanaClient;
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
-use std::{any::Any, collections::HashMap, sync::Arc, time::Duration};
+use std::{any::Any, collections::HashMap, future::Future, sync::Arc, time::Duration};
+use thiserror::Error as ThisError;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
 use tower::{Service, ServiceExt};
 
 /// Get user's JWT, require
@@ -86,8 +98,12 @@ pub mod get_jwt {
 /// Request Solana signature from external wallets.
 pub mod signer {
     use crate::{utils::TowerClient, BoxError, UserId};
-    use solana_sdk::{pubkey::Pubkey, signature::Signature};
-    use std::time::Duration;
+    use solana_sdk::{
+        pubkey::Pubkey,
+        signature::{Keypair, Signature},
+        signer::Signer,
+    };
+    use std::{collections::HashMap, sync::Arc, time::Duration};
     use thiserror::Error as ThisError;
 
     #[derive(ThisError, Debug)]
@@ -128,6 +144,291 @@ pub mod signer {
     pub fn unimplemented_svc() -> Svc {
         Svc::unimplemented(|| BoxError::from("unimplemented").into(), Error::Worker)
     }
+
+    /// Sign with local keypairs instead of asking a wallet, requests for other pubkeys fail
+    /// with [`Error::Pubkey`].
+    pub fn keypair_svc(keypairs: Vec<Keypair>, size: usize) -> Svc {
+        let keypairs = Arc::new(
+            keypairs
+                .into_iter()
+                .map(|k| (k.pubkey(), k))
+                .collect::<HashMap<_, _>>(),
+        );
+        let handle = move |req: SignatureRequest| {
+            let result = match keypairs.get(&req.pubkey) {
+                Some(k) => Ok(SignatureResponse {
+                    signature: k.sign_message(&req.message),
+                }),
+                None => Err(Error::Pubkey),
+            };
+            std::future::ready(result)
+        };
+        Svc::from_service(tower::service_fn(handle), Error::Worker, size)
+    }
+}
+
+/// Read secrets such as API keys, require a grant in
+/// [`SecretsConfig::grants`][crate::config::SecretsConfig::grants].
+///
+/// Backends are implemented in [`secret_store`][crate::secret_store].
+pub mod secrets {
+    use crate::{utils::TowerClient, BoxError, NodeId, UserId};
+    use std::sync::Arc;
+    use thiserror::Error as ThisError;
+
+    pub struct Request {
+        pub user_id: UserId,
+        /// Node that is reading the secret, used to check grants.
+        pub node_id: Option<NodeId>,
+        pub name: String,
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct Response {
+        pub value: Secret,
+    }
+
+    /// A secret value, its [`Debug`][std::fmt::Debug] implementation will not print the value.
+    #[derive(Clone, PartialEq, Eq)]
+    pub struct Secret(String);
+
+    impl Secret {
+        pub fn new(value: String) -> Self {
+            Self(value)
+        }
+
+        /// Get the secret value, be careful to not log it.
+        pub fn expose(&self) -> &str {
+            &self.0
+        }
+    }
+
+    impl std::fmt::Debug for Secret {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("Secret(***)")
+        }
+    }
+
+    #[derive(ThisError, Debug, Clone)]
+    pub enum Error {
+        #[error("not allowed to read secret: {0}")]
+        NotAllowed(String),
+        #[error("secret not found: {0}")]
+        NotFound(String),
+        #[error("invalid secret reference: {0}")]
+        InvalidReference(String),
+        #[error(transparent)]
+        Worker(Arc<BoxError>),
+        #[error(transparent)]
+        Other(#[from] Arc<BoxError>),
+    }
+
+    impl Error {
+        pub fn worker(e: BoxError) -> Self {
+            Error::Worker(Arc::new(e))
+        }
+
+        pub fn other<E: Into<BoxError>>(e: E) -> Self {
+            Error::Other(Arc::new(e.into()))
+        }
+    }
+
+    pub type Svc = TowerClient<Request, Response, Error>;
+
+    pub fn unimplemented_svc() -> Svc {
+        Svc::unimplemented(|| Error::other("unimplemented"), Error::worker)
+    }
+
+    const PREFIX: &str = "{{secrets.";
+    const SUFFIX: &str = "}}";
+
+    /// Find all secret names referenced in `text` with the `{{secrets.NAME}}` syntax.
+    pub fn references(text: &str) -> Result<Vec<&str>, Error> {
+        let mut names = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find(PREFIX) {
+            let after = &rest[start + PREFIX.len()..];
+            let end = after
+                .find(SUFFIX)
+                .ok_or_else(|| Error::InvalidReference(rest[start..].to_owned()))?;
+            let name = after[..end].trim();
+            if name.is_empty() {
+                return Err(Error::InvalidReference(rest[start..].to_owned()));
+            }
+            names.push(name);
+            rest = &after[end + SUFFIX.len()..];
+        }
+        Ok(names)
+    }
+
+    /// Replace `{{secrets.NAME}}` references in `text` with values returned by `get`.
+    pub fn expand<F>(text: &str, mut get: F) -> Result<String, Error>
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(PREFIX) {
+            result.push_str(&rest[..start]);
+            let after = &rest[start + PREFIX.len()..];
+            let end = after
+                .find(SUFFIX)
+                .ok_or_else(|| Error::InvalidReference(rest[start..].to_owned()))?;
+            let name = after[..end].trim();
+            let value = get(name).ok_or_else(|| Error::NotFound(name.to_owned()))?;
+            result.push_str(&value);
+            rest = &after[end + SUFFIX.len()..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+}
+
+/// Load flow configs, for nodes that run other flows such as `interflow`.
+pub mod get_flow {
+    use crate::{config::client::ClientConfig, utils::TowerClient, BoxError, FlowId, UserId};
+    use std::{path::PathBuf, sync::Arc};
+    use thiserror::Error as ThisError;
+
+    pub struct Request {
+        pub user_id: UserId,
+        pub flow_id: FlowId,
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct Response {
+        pub config: ClientConfig,
+    }
+
+    #[derive(ThisError, Debug, Clone)]
+    pub enum Error {
+        #[error("flow not found: {0}")]
+        NotFound(FlowId),
+        #[error("not allowed to run flow {0}")]
+        NotAllowed(FlowId),
+        #[error(transparent)]
+        Worker(Arc<BoxError>),
+        #[error(transparent)]
+        Other(#[from] Arc<BoxError>),
+    }
+
+    impl Error {
+        pub fn worker(e: BoxError) -> Self {
+            Error::Worker(Arc::new(e))
+        }
+
+        pub fn other<E: Into<BoxError>>(e: E) -> Self {
+            Error::Other(Arc::new(e.into()))
+        }
+    }
+
+    pub type Svc = TowerClient<Request, Response, Error>;
+
+    pub fn unimplemented_svc() -> Svc {
+        Svc::unimplemented(|| Error::other("unimplemented"), Error::worker)
+    }
+
+    /// Read flow `{flow_id}` from `{dir}/{flow_id}.json`, in the format of [`ClientConfig`].
+    pub fn from_dir(dir: PathBuf, size: usize) -> Svc {
+        let handle = move |req: Request| {
+            let path = dir.join(format!("{}.json", req.flow_id));
+            let result = match std::fs::read(&path) {
+                Ok(json) => serde_json::from_slice(&json)
+                    .map(|config| Response { config })
+                    .map_err(Error::other),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    Err(Error::NotFound(req.flow_id))
+                }
+                Err(e) => Err(Error::other(e)),
+            };
+            std::future::ready(result)
+        };
+        Svc::from_service(tower::service_fn(handle), Error::worker, size)
+    }
+}
+
+/// Load node outputs recorded by previous runs, for partial runs, see
+/// [`PartialConfig`][crate::config::client::PartialConfig].
+pub mod run_store {
+    use crate::{utils::TowerClient, BoxError, FlowRunId, NodeId, UserId, ValueSet};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+    use thiserror::Error as ThisError;
+
+    pub struct Request {
+        pub user_id: UserId,
+        pub flow_run_id: FlowRunId,
+        pub node_id: NodeId,
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct Response {
+        pub output: ValueSet,
+    }
+
+    #[derive(ThisError, Debug, Clone)]
+    pub enum Error {
+        #[error("output of node {node_id} not found in flow run {flow_run_id}")]
+        NotFound {
+            flow_run_id: FlowRunId,
+            node_id: NodeId,
+        },
+        #[error("not allowed to read flow run {0}")]
+        NotAllowed(FlowRunId),
+        #[error(transparent)]
+        Worker(Arc<BoxError>),
+        #[error(transparent)]
+        Other(#[from] Arc<BoxError>),
+    }
+
+    impl Error {
+        pub fn worker(e: BoxError) -> Self {
+            Error::Worker(Arc::new(e))
+        }
+
+        pub fn other<E: Into<BoxError>>(e: E) -> Self {
+            Error::Other(Arc::new(e.into()))
+        }
+    }
+
+    pub type Svc = TowerClient<Request, Response, Error>;
+
+    pub fn unimplemented_svc() -> Svc {
+        Svc::unimplemented(|| Error::other("unimplemented"), Error::worker)
+    }
+
+    /// Keep node outputs of runs in memory.
+    #[derive(Clone, Default)]
+    pub struct MemoryStore(Arc<Mutex<HashMap<FlowRunId, HashMap<NodeId, ValueSet>>>>);
+
+    impl MemoryStore {
+        /// Record outputs of a run, replacing outputs recorded with the same `flow_run_id`.
+        pub fn insert(&self, flow_run_id: FlowRunId, node_outputs: HashMap<NodeId, ValueSet>) {
+            self.0.lock().unwrap().insert(flow_run_id, node_outputs);
+        }
+
+        pub fn svc(&self, size: usize) -> Svc {
+            let runs = self.0.clone();
+            let handle = move |req: Request| {
+                let output = runs
+                    .lock()
+                    .unwrap()
+                    .get(&req.flow_run_id)
+                    .and_then(|run| run.get(&req.node_id))
+                    .cloned();
+                std::future::ready(match output {
+                    Some(output) => Ok(Response { output }),
+                    None => Err(Error::NotFound {
+                        flow_run_id: req.flow_run_id,
+                        node_id: req.node_id,
+                    }),
+                })
+            };
+            Svc::from_service(tower::service_fn(handle), Error::worker, size)
+        }
+    }
 }
 
 /// Output values and Solana instructions to be executed.
@@ -136,7 +437,7 @@ pub mod execute {
     use futures::channel::oneshot::Canceled;
     use solana_client::client_error::ClientError;
     use solana_sdk::{signature::Signature, signer::SignerError};
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
     use thiserror::Error as ThisError;
 
     pub type Svc = TowerClient<Request, Response, Error>;
@@ -161,6 +462,8 @@ pub mod execute {
         TxIncomplete,
         #[error("time out")]
         Timeout,
+        #[error("deadline exceeded")]
+        DeadlineExceeded,
         #[error("insufficient solana balance, needed={needed}; have={balance};")]
         InsufficientSolanaBalance { needed: u64, balance: u64 },
         #[error("transaction simulation failed")]
@@ -191,6 +494,15 @@ pub mod execute {
         }
     }
 
+    impl From<super::Interrupted> for Error {
+        fn from(value: super::Interrupted) -> Self {
+            match value {
+                super::Interrupted::Canceled => Error::Canceled,
+                super::Interrupted::DeadlineExceeded => Error::DeadlineExceeded,
+            }
+        }
+    }
+
     impl From<BoxError> for Error {
         fn from(value: BoxError) -> Self {
             Error::Other(Arc::new(value))
@@ -217,29 +529,205 @@ pub mod execute {
         Svc::unimplemented(|| Error::other("unimplemented"), Error::worker)
     }
 
+    /// Execute instructions of each request in a transaction.
+    ///
+    /// Cancellation is only checked before submitting, a submitted transaction is always
+    /// awaited until it is confirmed or fails.
     pub fn simple(ctx: &super::Context, size: usize) -> Svc {
         let rpc = ctx.solana_client.clone();
         let signer = ctx.signer.clone();
         let user_id = ctx.user.id;
+        let cancel = ctx.cancel.clone();
         let handle = move |req: Request| {
             let rpc = rpc.clone();
             let signer = signer.clone();
+            let cancel = cancel.clone();
             async move {
+                cancel.check()?;
+                let signature = req.instructions.execute(&rpc, signer, user_id).await?;
                 Ok(Response {
-                    signature: Some(req.instructions.execute(&rpc, signer, user_id).await?),
+                    signature: Some(signature),
                 })
             }
         };
         Svc::from_service(tower::service_fn(handle), Error::worker, size)
     }
+
+    /// Instructions collected by [`collect`].
+    #[derive(Clone, Default)]
+    pub struct Collected(Arc<Mutex<Option<Instructions>>>);
+
+    impl Collected {
+        pub fn take(&self) -> Option<Instructions> {
+            self.0.lock().unwrap().take()
+        }
+    }
+
+    /// Combine instructions of all requests instead of executing them, responses have no
+    /// signature. Requests with a different fee payer fail with [`Error::TxIncomplete`].
+    pub fn collect(size: usize) -> (Svc, Collected) {
+        let collected = Collected::default();
+        let state = collected.0.clone();
+        let handle = move |req: Request| {
+            let mut state = state.lock().unwrap();
+            let result = match state.as_mut() {
+                None => {
+                    *state = Some(req.instructions);
+                    Ok(Response { signature: None })
+                }
+                Some(ins) => ins
+                    .combine(req.instructions)
+                    .map(|_| Response { signature: None })
+                    .map_err(|_| Error::TxIncomplete),
+            };
+            std::future::ready(result)
+        };
+        let svc = Svc::from_service(tower::service_fn(handle), Error::worker, size);
+        (svc, collected)
+    }
+}
+
+/// Reason why a [`Cancellation`] stopped a task.
+#[derive(ThisError, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupted {
+    #[error("canceled")]
+    Canceled,
+    #[error("deadline exceeded")]
+    DeadlineExceeded,
+}
+
+/// Cancellation signal and optional deadline of a flow run, cheap to clone.
+///
+/// Cancel with [`token.cancel()`][CancellationToken::cancel], all clones will observe it.
+#[derive(Clone, Debug, Default)]
+pub struct Cancellation {
+    pub token: CancellationToken,
+    pub deadline: Option<Instant>,
+}
+
+impl Cancellation {
+    pub fn new(token: CancellationToken, deadline: Option<Instant>) -> Self {
+        Self { token, deadline }
+    }
+
+    /// Return an error if canceled or the deadline has passed.
+    pub fn check(&self) -> Result<(), Interrupted> {
+        if self.token.is_cancelled() {
+            Err(Interrupted::Canceled)
+        } else if self.deadline.is_some_and(|d| d <= Instant::now()) {
+            Err(Interrupted::DeadlineExceeded)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Time left before the deadline.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|d| d.saturating_duration_since(Instant::now()))
+    }
+
+    /// Wait until canceled or the deadline has passed.
+    pub async fn wait(&self) -> Interrupted {
+        let deadline = async {
+            match self.deadline {
+                Some(d) => tokio::time::sleep_until(d).await,
+                None => futures::future::pending().await,
+            }
+        };
+        tokio::select! {
+            biased;
+            _ = self.token.cancelled() => Interrupted::Canceled,
+            _ = deadline => Interrupted::DeadlineExceeded,
+        }
+    }
+
+    /// Run `fut` until it completes, or stop it when canceled or the deadline has passed.
+    pub async fn run<F: Future>(&self, fut: F) -> Result<F::Output, Interrupted> {
+        self.check()?;
+        tokio::select! {
+            biased;
+            reason = self.wait() => Err(reason),
+            output = fut => Ok(output),
+        }
+    }
+}
+
+/// Emit outputs incrementally, before [`run`][crate::command::CommandTrait::run] returns.
+/// Nodes downstream of the emitted outputs run once for each emitted value, see
+/// [`runner`][crate::runner#streams].
+///
+/// Commands declare which outputs are streamed with
+/// [`CommandTrait::stream_outputs`][crate::command::CommandTrait::stream_outputs].
+pub mod stream {
+    use crate::{utils::TowerClient, BoxError};
+    use futures::{channel::mpsc, SinkExt};
+    use std::sync::Arc;
+    use thiserror::Error as ThisError;
+
+    pub struct Request {
+        pub output: value::Map,
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    pub struct Response;
+
+    #[derive(ThisError, Debug, Clone)]
+    pub enum Error {
+        #[error("not available on this Context")]
+        NotAvailable,
+        #[error("receiver closed")]
+        Closed,
+        #[error(transparent)]
+        Interrupted(#[from] super::Interrupted),
+        #[error(transparent)]
+        Worker(Arc<BoxError>),
+        #[error(transparent)]
+        Other(#[from] Arc<BoxError>),
+    }
+
+    impl Error {
+        pub fn worker(e: BoxError) -> Self {
+            Error::Worker(Arc::new(e))
+        }
+
+        pub fn other<E: Into<BoxError>>(e: E) -> Self {
+            Error::Other(Arc::new(e.into()))
+        }
+    }
+
+    pub type Svc = TowerClient<Request, Response, Error>;
+
+    pub fn unimplemented_svc() -> Svc {
+        Svc::unimplemented(|| Error::NotAvailable, Error::worker)
+    }
+
+    /// Send emitted outputs to a channel. Emitting will wait when the channel is full, so a
+    /// slow receiver will slow down the command instead of buffering everything in memory.
+    pub fn channel(size: usize) -> (Svc, mpsc::Receiver<value::Map>) {
+        let (tx, rx) = mpsc::channel(size);
+        let handle = move |req: Request| {
+            let mut tx = tx.clone();
+            async move {
+                tx.send(req.output).await.map_err(|_| Error::Closed)?;
+                Ok(Response)
+            }
+        };
+        let svc = Svc::from_service(tower::service_fn(handle), Error::worker, size.max(1));
+        (svc, rx)
+    }
 }
 
 #[derive(Clone)]
 pub struct CommandContext {
     pub svc: execute::Svc,
+    pub stream: stream::Svc,
     pub flow_run_id: FlowRunId,
     pub node_id: NodeId,
     pub times: u32,
+    /// Number of `interflow` nodes above this flow, see
+    /// [`ClientConfig::call_depth`][crate::config::client::ClientConfig::call_depth].
+    pub call_depth: u32,
 }
 
 #[derive(Clone)]
@@ -254,6 +742,12 @@ pub struct Context {
     pub command: Option<CommandContext>,
     pub signer: signer::Svc,
     pub get_jwt: get_jwt::Svc,
+    pub secrets: secrets::Svc,
+    pub get_flow: get_flow::Svc,
+    pub cancel: Cancellation,
+    pub events: events::Sender,
+    /// Record or replay requests, see [`record::Mode::install`].
+    pub record: record::Mode,
 }
 
 impl Default for Context {
@@ -267,9 +761,11 @@ impl Default for Context {
         );
         ctx.command = Some(CommandContext {
             svc: execute::simple(&ctx, 1),
+            stream: stream::unimplemented_svc(),
             flow_run_id: uuid::Uuid::nil(),
             node_id: uuid::Uuid::nil(),
             times: 0,
+            call_depth: 0,
         });
         ctx
     }
@@ -316,6 +812,11 @@ impl Context {
             command: None,
             signer: sig_svc,
             get_jwt: token_svc,
+            secrets: secrets::unimplemented_svc(),
+            get_flow: get_flow::unimplemented_svc(),
+            cancel: Cancellation::default(),
+            events: events::Sender::default(),
+            record: record::Mode::Off,
         }
     }
 
@@ -333,6 +834,43 @@ impl Context {
                 .access_token)
     }
 
+    /// Call [`secrets`] service, the service checks if this node was granted access to `name`.
+    pub async fn get_secret(&self, name: &str) -> Result<secrets::Secret, secrets::Error> {
+        let secrets::Response { value } = self
+            .secrets
+            .call_ref(secrets::Request {
+                user_id: self.user.id,
+                node_id: self.command.as_ref().map(|c| c.node_id),
+                name: name.to_owned(),
+            })
+            .await?;
+        Ok(value)
+    }
+
+    /// Replace `{{secrets.NAME}}` references in `text` with values from [`secrets`] service.
+    pub async fn expand_secrets(&self, text: &str) -> Result<String, secrets::Error> {
+        let mut values = HashMap::new();
+        for name in secrets::references(text)? {
+            if !values.contains_key(name) {
+                let value = self.get_secret(name).await?;
+                values.insert(name.to_owned(), value);
+            }
+        }
+        secrets::expand(text, |name| values.get(name).map(|s| s.expose().to_owned()))
+    }
+
+    /// Call [`get_flow`] service.
+    pub async fn get_flow(&self, flow_id: FlowId) -> Result<ClientConfig, get_flow::Error> {
+        let get_flow::Response { config } = self
+            .get_flow
+            .call_ref(get_flow::Request {
+                user_id: self.user.id,
+                flow_id,
+            })
+            .await?;
+        Ok(config)
+    }
+
     pub fn new_interflow_origin(&self) -> Option<FlowRunOrigin> {
         let c = self.command.as_ref()?;
         Some(FlowRunOrigin::Interflow {
@@ -343,23 +881,42 @@ impl Context {
     }
 
     /// Call [`execute`] service.
+    ///
+    /// Return [`execute::Error::Canceled`] or [`execute::Error::DeadlineExceeded`] if the flow
+    /// is canceled or its deadline has passed before calling the service. Once called, the
+    /// service is awaited even if the flow is canceled, because dropping it could abandon a
+    /// transaction that was already submitted.
     pub async fn execute(
         &mut self,
         instructions: Instructions,
         output: value::Map,
     ) -> Result<execute::Response, execute::Error> {
-        if let Some(ctx) = &mut self.command {
-            ctx.svc
-                .ready()
-                .await?
-                .call(execute::Request {
-                    instructions,
-                    output,
-                })
-                .await
-        } else {
-            Err(execute::Error::NotAvailable)
-        }
+        self.check_canceled()?;
+        let mut svc = match &self.command {
+            Some(ctx) => ctx.svc.clone(),
+            None => return Err(execute::Error::NotAvailable),
+        };
+        svc.ready()
+            .await?
+            .call(execute::Request {
+                instructions,
+                output,
+            })
+            .await
+    }
+
+    /// Call [`stream`] service, send `output` to downstream nodes without waiting for
+    /// [`run`][crate::command::CommandTrait::run] to return. Waits while downstream nodes are
+    /// busy.
+    pub async fn emit(&self, output: value::Map) -> Result<(), stream::Error> {
+        let svc = match &self.command {
+            Some(ctx) => ctx.stream.clone(),
+            None => return Err(stream::Error::NotAvailable),
+        };
+        self.cancel
+            .run(svc.call_ref(stream::Request { output }))
+            .await??;
+        Ok(())
     }
 
     /// Call [`signer`] service.
@@ -372,19 +929,91 @@ impl Context {
         let mut s = self.signer.clone();
         let user_id = self.user.id;
 
-        let signer::SignatureResponse { signature } = s
-            .ready()
-            .await?
-            .call(signer::SignatureRequest {
-                user_id,
-                pubkey,
-                message,
-                timeout,
-            })
-            .await?;
+        let fut = async move {
+            s.ready()
+                .await?
+                .call(signer::SignatureRequest {
+                    user_id,
+                    pubkey,
+                    message,
+                    timeout,
+                })
+                .await
+        };
+        let signer::SignatureResponse { signature } = self.cancel.run(fut).await??;
         Ok(signature)
     }
 
+    /// Send an HTTP request, stop waiting for the response when the flow is canceled or its
+    /// deadline has passed. The request is recorded or replayed according to [`Context::record`].
+    pub async fn send_http(
+        &self,
+        req: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, anyhow::Error> {
+        self.cancel.run(self.record.send_http(req)).await?
+    }
+
+    /// Return an error if the flow is canceled or its deadline has passed.
+    /// This is cheap, call it between long-running steps and in loops.
+    pub fn check_canceled(&self) -> Result<(), Interrupted> {
+        self.cancel.check()
+    }
+
+    /// Run `fut` until it completes, or stop it when the flow is canceled or its deadline has
+    /// passed.
+    pub async fn cancelable<F: Future>(&self, fut: F) -> Result<F::Output, Interrupted> {
+        self.cancel.run(fut).await
+    }
+
+    fn node_sink(&self) -> Option<events::NodeSink> {
+        let c = self.command.as_ref()?;
+        Some(events::NodeSink {
+            sender: self.events.clone(),
+            flow_run_id: c.flow_run_id,
+            node_id: c.node_id,
+            times: c.times,
+        })
+    }
+
+    /// Send a log line to [`events`], it is not sent to [`tracing`].
+    pub fn log(&self, level: LogLevel, message: impl Into<String>) {
+        if let Some(sink) = self.node_sink() {
+            sink.send(EventContent::Log {
+                level,
+                message: message.into(),
+            });
+        }
+    }
+
+    /// Report progress of this node to [`events`], `percent` is clamped to `0..=100`.
+    pub fn progress(&self, percent: f64, message: Option<String>) {
+        if let Some(sink) = self.node_sink() {
+            sink.send(EventContent::Progress {
+                percent: percent.clamp(0.0, 100.0),
+                message,
+            });
+        }
+    }
+
+    /// Send a custom event to [`events`].
+    pub fn custom_event(&self, name: impl Into<String>, data: serde_json::Value) {
+        if let Some(sink) = self.node_sink() {
+            sink.send(EventContent::Custom {
+                name: name.into(),
+                data,
+            });
+        }
+    }
+
+    /// A span that forwards [`tracing`] events to [`events`] when
+    /// [`TracingLayer`][events::TracingLayer] is installed.
+    pub fn node_span(&self) -> tracing::Span {
+        match self.node_sink() {
+            Some(sink) => events::node_span(sink),
+            None => tracing::Span::none(),
+        }
+    }
+
     /// Get an extension by type.
     pub fn get<T: Any + Send + Sync + 'static>(&self) -> Option<&T> {
         self.extensions.get::<T>()
@@ -398,3 +1027,100 @@ impl Context {
         f::<Self>();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_keypair_signer() {
+        use solana_sdk::{signature::Keypair, signer::Signer};
+
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        let svc = signer::keypair_svc(vec![keypair], 1);
+        let message = Bytes::from_static(b"hello");
+        let req = |pubkey| signer::SignatureRequest {
+            user_id: uuid::Uuid::nil(),
+            pubkey,
+            message: message.clone(),
+            timeout: Duration::from_secs(1),
+        };
+
+        let res = svc.call_ref(req(pubkey)).await.unwrap();
+        assert!(res.signature.verify(pubkey.as_ref(), &message));
+        let res = svc.call_ref(req(Pubkey::new_unique())).await;
+        assert!(matches!(res, Err(signer::Error::Pubkey)));
+    }
+
+    #[tokio::test]
+    async fn test_cancel() {
+        let ctx = Context::default();
+        assert_eq!(ctx.check_canceled(), Ok(()));
+        assert_eq!(ctx.cancelable(async { 1 }).await, Ok(1));
+
+        let token = ctx.cancel.token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            token.cancel();
+        });
+        let result = ctx.cancelable(futures::future::pending::<()>()).await;
+        assert_eq!(result, Err(Interrupted::Canceled));
+        assert_eq!(ctx.check_canceled(), Err(Interrupted::Canceled));
+
+        let mut ctx = ctx;
+        let error = ctx
+            .execute(Instructions::default(), <_>::default())
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(error, execute::Error::Canceled));
+    }
+
+    #[tokio::test]
+    async fn test_simple_execute_canceled() {
+        let mut ctx = Context::default();
+        ctx.cancel.deadline = Some(Instant::now());
+        let svc = execute::simple(&ctx, 1);
+        let error = svc
+            .call_ref(execute::Request {
+                instructions: Instructions::default(),
+                output: <_>::default(),
+            })
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(error, execute::Error::DeadlineExceeded));
+    }
+
+    #[tokio::test]
+    async fn test_emit() {
+        use futures::StreamExt;
+
+        let mut ctx = Context::default();
+        let (svc, rx) = stream::channel(1);
+        ctx.command.as_mut().unwrap().stream = svc;
+        tokio::spawn(async move {
+            for i in 0..3u64 {
+                ctx.emit(value::map! { "element" => i }).await.unwrap();
+            }
+        });
+        let received = rx.collect::<Vec<_>>().await;
+        assert_eq!(
+            received,
+            (0..3u64)
+                .map(|i| value::map! { "element" => i })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deadline() {
+        let mut ctx = Context::default();
+        ctx.cancel.deadline = Some(Instant::now() + Duration::from_millis(10));
+        assert!(ctx.cancel.remaining().unwrap() <= Duration::from_millis(10));
+        let result = ctx.cancelable(futures::future::pending::<()>()).await;
+        assert_eq!(result, Err(Interrupted::DeadlineExceeded));
+        assert_eq!(ctx.check_canceled(), Err(Interrupted::DeadlineExceeded));
+    }
+}
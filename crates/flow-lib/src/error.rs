@@ -0,0 +1,410 @@
+//! Structured errors with stable machine-readable codes.
+//!
+//! [`CommandError`] is an [`anyhow::Error`], use [`FlowError::from_command_error`] to classify it.
+//! Crates with their own error types register an [`ErrorClassifier`] to classify them.
+//! [`FlowError`] serializes to JSON with a `"code"` field, for flow UIs to react to specific
+//! failures:
+//!
+//! ```json
+//! { "code": "program-error", "message": "...", "instruction_index": 1, "custom_code": 6000 }
+//! ```
+
+use crate::{
+    command::CommandError,
+    context::{execute, Interrupted},
+    policy::PolicyError,
+    solana::program_errors,
+};
+use serde::{Deserialize, Serialize};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_request::RpcError,
+};
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+use thiserror::Error as ThisError;
+
+/// Stable error codes, the string values will not change.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorCode {
+    InputValidation,
+    InsufficientFunds,
+    Rpc,
+    SignatureTimeout,
+    ProgramError,
+    Http,
+    Canceled,
+    Timeout,
+    Other,
+}
+
+impl ErrorCode {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::InputValidation => "input-validation",
+            ErrorCode::InsufficientFunds => "insufficient-funds",
+            ErrorCode::Rpc => "rpc",
+            ErrorCode::SignatureTimeout => "signature-timeout",
+            ErrorCode::ProgramError => "program-error",
+            ErrorCode::Http => "http",
+            ErrorCode::Canceled => "canceled",
+            ErrorCode::Timeout => "timeout",
+            ErrorCode::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(ThisError, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "code", rename_all = "kebab-case")]
+pub enum FlowError {
+    #[error("{message}")]
+    InputValidation {
+        message: String,
+        /// Name of the invalid input, if known.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input: Option<String>,
+    },
+    #[error("insufficient funds: {message}")]
+    InsufficientFunds {
+        message: String,
+        /// In lamports.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        needed: Option<u64>,
+        /// In lamports.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        balance: Option<u64>,
+    },
+    #[error("{message}")]
+    Rpc {
+        message: String,
+        /// JSON-RPC error code.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rpc_code: Option<i64>,
+    },
+    #[error("time-out waiting for signature")]
+    SignatureTimeout,
+    #[error("{message}")]
+    ProgramError {
+        message: String,
+        /// Index of the failing instruction in the transaction.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        instruction_index: Option<u8>,
+        /// Value of [`InstructionError::Custom`].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        custom_code: Option<u32>,
+        /// Program that returned `custom_code`, if found in simulation logs.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        program_id: Option<String>,
+        /// Name of `custom_code` in the program's error enum, see
+        /// [`program_errors`][crate::solana::program_errors].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error_name: Option<String>,
+    },
+    #[error("{message}")]
+    Http {
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<u16>,
+    },
+    #[error("canceled")]
+    Canceled,
+    #[error("{message}")]
+    Timeout { message: String },
+    #[error("{message}")]
+    Other { message: String },
+}
+
+impl FlowError {
+    pub fn input_validation(input: Option<&str>, message: impl ToString) -> Self {
+        FlowError::InputValidation {
+            message: message.to_string(),
+            input: input.map(str::to_owned),
+        }
+    }
+
+    pub fn other(message: impl ToString) -> Self {
+        FlowError::Other {
+            message: message.to_string(),
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            FlowError::InputValidation { .. } => ErrorCode::InputValidation,
+            FlowError::InsufficientFunds { .. } => ErrorCode::InsufficientFunds,
+            FlowError::Rpc { .. } => ErrorCode::Rpc,
+            FlowError::SignatureTimeout => ErrorCode::SignatureTimeout,
+            FlowError::ProgramError { .. } => ErrorCode::ProgramError,
+            FlowError::Http { .. } => ErrorCode::Http,
+            FlowError::Canceled => ErrorCode::Canceled,
+            FlowError::Timeout { .. } => ErrorCode::Timeout,
+            FlowError::Other { .. } => ErrorCode::Other,
+        }
+    }
+
+    /// Classify an error returned by [`CommandTrait::run`][crate::command::CommandTrait::run].
+    ///
+    /// The error chain is searched for known error types and types of registered
+    /// [`ErrorClassifier`]s, the first match wins. Errors that are already a [`FlowError`] are
+    /// returned as-is.
+    pub fn from_command_error(error: &CommandError) -> Self {
+        for e in error.chain() {
+            if let Some(e) = e.downcast_ref::<FlowError>() {
+                return e.clone();
+            }
+            if let Some(e) = e.downcast_ref::<execute::Error>() {
+                if let Some(e) = Self::from_execute_error(e) {
+                    return e;
+                }
+            }
+            if let Some(e) = e.downcast_ref::<ClientError>() {
+                return e.into();
+            }
+            if let Some(e) = e.downcast_ref::<reqwest::Error>() {
+                return e.into();
+            }
+            if let Some(e) = e.downcast_ref::<value::Error>() {
+                return Self::input_validation(None, e);
+            }
+            if let Some(e) = e.downcast_ref::<Interrupted>() {
+                return (*e).into();
+            }
+            if let Some(e) = e.downcast_ref::<PolicyError>() {
+                return (*e).into();
+            }
+            if let Some(e) = inventory::iter::<ErrorClassifier>().find_map(|c| (c.0)(e)) {
+                return e;
+            }
+        }
+        Self::other(format!("{:#}", error))
+    }
+
+    /// Returns `None` for wrapper variants, so that the chain can be searched further.
+    fn from_execute_error(e: &execute::Error) -> Option<Self> {
+        Some(match e {
+            execute::Error::Canceled => FlowError::Canceled,
+            execute::Error::Timeout => FlowError::SignatureTimeout,
+            execute::Error::DeadlineExceeded => FlowError::Timeout {
+                message: e.to_string(),
+            },
+            execute::Error::InsufficientSolanaBalance { needed, balance } => {
+                FlowError::InsufficientFunds {
+                    message: e.to_string(),
+                    needed: Some(*needed),
+                    balance: Some(*balance),
+                }
+            }
+            execute::Error::Solana(e) => e.as_ref().into(),
+            execute::Error::Signer(_) => Self::input_validation(None, e),
+            execute::Error::Worker(_) | execute::Error::Other(_) => return None,
+            _ => Self::other(e),
+        })
+    }
+}
+
+/// Classify an error type of another crate, return `None` if `error` is not of that type.
+///
+/// Use [`inventory::submit`] to register it for [`FlowError::from_command_error`]:
+///
+/// ```
+/// use flow_lib::error::{ErrorClassifier, FlowError};
+///
+/// #[derive(Debug, thiserror::Error)]
+/// #[error("out of stock")]
+/// struct OutOfStock;
+///
+/// inventory::submit!(ErrorClassifier::new(|e| {
+///     e.downcast_ref::<OutOfStock>()
+///         .map(|e| FlowError::input_validation(None, e))
+/// }));
+///
+/// let error = FlowError::from_command_error(&OutOfStock.into());
+/// assert_eq!(error, FlowError::input_validation(None, "out of stock"));
+/// ```
+pub struct ErrorClassifier(pub fn(&(dyn std::error::Error + 'static)) -> Option<FlowError>);
+
+impl ErrorClassifier {
+    pub const fn new(f: fn(&(dyn std::error::Error + 'static)) -> Option<FlowError>) -> Self {
+        Self(f)
+    }
+}
+
+inventory::collect!(ErrorClassifier);
+
+impl From<&execute::Error> for FlowError {
+    fn from(e: &execute::Error) -> Self {
+        Self::from_execute_error(e).unwrap_or_else(|| Self::other(e))
+    }
+}
+
+impl From<&TransactionError> for FlowError {
+    fn from(e: &TransactionError) -> Self {
+        match e {
+            TransactionError::InsufficientFundsForFee
+            | TransactionError::InsufficientFundsForRent { .. } => FlowError::InsufficientFunds {
+                message: e.to_string(),
+                needed: None,
+                balance: None,
+            },
+            TransactionError::InstructionError(index, error) => FlowError::ProgramError {
+                message: e.to_string(),
+                instruction_index: Some(*index),
+                custom_code: match error {
+                    InstructionError::Custom(code) => Some(*code),
+                    _ => None,
+                },
+                program_id: None,
+                error_name: None,
+            },
+            _ => FlowError::ProgramError {
+                message: e.to_string(),
+                instruction_index: None,
+                custom_code: None,
+                program_id: None,
+                error_name: None,
+            },
+        }
+    }
+}
+
+impl From<&ClientError> for FlowError {
+    fn from(e: &ClientError) -> Self {
+        let message = crate::solana::verbose_solana_error(e);
+        if let Some(tx_error) = e.get_transaction_error() {
+            let mut error = FlowError::from(&tx_error);
+            match &mut error {
+                FlowError::ProgramError {
+                    message: m,
+                    custom_code,
+                    program_id,
+                    error_name,
+                    ..
+                } => {
+                    *m = message;
+                    if let Some(decoded) =
+                        program_errors::decode(e).filter(|d| Some(d.code) == *custom_code)
+                    {
+                        *program_id = Some(decoded.program_id.to_string());
+                        *error_name = decoded.info.map(|i| i.name.to_owned());
+                    }
+                }
+                FlowError::InsufficientFunds { message: m, .. } => *m = message,
+                _ => {}
+            }
+            return error;
+        }
+        match &e.kind {
+            ClientErrorKind::Reqwest(e) => e.into(),
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => FlowError::Rpc {
+                message,
+                rpc_code: Some(*code),
+            },
+            ClientErrorKind::SigningError(_) => Self::input_validation(None, message),
+            _ => FlowError::Rpc {
+                message,
+                rpc_code: None,
+            },
+        }
+    }
+}
+
+impl From<&reqwest::Error> for FlowError {
+    fn from(e: &reqwest::Error) -> Self {
+        if e.is_timeout() {
+            FlowError::Timeout {
+                message: e.to_string(),
+            }
+        } else {
+            FlowError::Http {
+                message: e.to_string(),
+                status: e.status().map(|s| s.as_u16()),
+            }
+        }
+    }
+}
+
+impl From<Interrupted> for FlowError {
+    fn from(e: Interrupted) -> Self {
+        match e {
+            Interrupted::Canceled => FlowError::Canceled,
+            Interrupted::DeadlineExceeded => FlowError::Timeout {
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+impl From<PolicyError> for FlowError {
+    fn from(e: PolicyError) -> Self {
+        match e {
+            PolicyError::TimedOut(_) => FlowError::Timeout {
+                message: e.to_string(),
+            },
+            PolicyError::CircuitOpen => Self::other(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_program_error() {
+        let tx_error = TransactionError::InstructionError(1, InstructionError::Custom(6000));
+        let client_error = ClientError::from(ClientErrorKind::TransactionError(tx_error));
+        let error = anyhow::Error::new(execute::Error::Solana(Arc::new(client_error)))
+            .context("send transaction");
+        let error = FlowError::from_command_error(&error);
+        assert_eq!(error.code(), ErrorCode::ProgramError);
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "program-error");
+        assert_eq!(json["instruction_index"], 1);
+        assert_eq!(json["custom_code"], 6000);
+        assert_eq!(serde_json::from_value::<FlowError>(json).unwrap(), error);
+    }
+
+    #[test]
+    fn test_classify() {
+        let classify = |e: anyhow::Error| FlowError::from_command_error(&e).code();
+        assert_eq!(
+            classify(
+                execute::Error::InsufficientSolanaBalance {
+                    needed: 10,
+                    balance: 1
+                }
+                .into()
+            ),
+            ErrorCode::InsufficientFunds
+        );
+        assert_eq!(
+            classify(execute::Error::Timeout.into()),
+            ErrorCode::SignatureTimeout
+        );
+        assert_eq!(
+            classify(execute::Error::DeadlineExceeded.into()),
+            ErrorCode::Timeout
+        );
+        assert_eq!(
+            classify(value::Error::Custom("missing field `amount`".to_owned()).into()),
+            ErrorCode::InputValidation
+        );
+        assert_eq!(classify(Interrupted::Canceled.into()), ErrorCode::Canceled);
+        assert_eq!(
+            classify(FlowError::input_validation(Some("amount"), "must be positive").into()),
+            ErrorCode::InputValidation
+        );
+        assert_eq!(classify(anyhow::anyhow!("unknown")), ErrorCode::Other);
+        assert_eq!(
+            serde_json::to_value(ErrorCode::SignatureTimeout).unwrap(),
+            ErrorCode::SignatureTimeout.as_str()
+        );
+    }
+}
@@ -0,0 +1,398 @@
+//! Per-node logs, progress and custom events, for flow UIs to display.
+//!
+//! Nodes send events with [`Context::log`], [`Context::progress`] and
+//! [`Context::custom_event`]. Events are tagged with `flow_run_id` and `node_id` of the node, and
+//! sent to the [`Sender`] in [`Context::events`].
+//!
+//! [`TracingLayer`] forwards [`tracing`] events emitted inside a [`Context::node_span`] span to
+//! the node's [`Sender`], so existing `tracing::info!` calls also show up as node logs.
+//! Commands built with [`CmdBuilder`][crate::command::builder::CmdBuilder] run inside this span
+//! automatically.
+//!
+//! [`Context`]: crate::Context
+//! [`Context::log`]: crate::Context::log
+//! [`Context::progress`]: crate::Context::progress
+//! [`Context::custom_event`]: crate::Context::custom_event
+//! [`Context::events`]: crate::Context::events
+//! [`Context::node_span`]: crate::Context::node_span
+
+use crate::{FlowRunId, NodeId};
+use futures::channel::mpsc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::SystemTime,
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Subscriber,
+};
+use tracing_subscriber::{layer, registry::LookupSpan, Layer};
+
+/// Name of the span created by [`Context::node_span`][crate::Context::node_span].
+pub const NODE_SPAN: &str = "flow_node";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub flow_run_id: FlowRunId,
+    pub node_id: NodeId,
+    pub times: u32,
+    pub time: SystemTime,
+    #[serde(flatten)]
+    pub content: EventContent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventContent {
+    Log {
+        level: LogLevel,
+        message: String,
+    },
+    Progress {
+        /// From 0 to 100.
+        percent: f64,
+        message: Option<String>,
+    },
+    Custom {
+        name: String,
+        data: serde_json::Value,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl From<&tracing::Level> for LogLevel {
+    fn from(level: &tracing::Level) -> Self {
+        match *level {
+            tracing::Level::TRACE => LogLevel::Trace,
+            tracing::Level::DEBUG => LogLevel::Debug,
+            tracing::Level::INFO => LogLevel::Info,
+            tracing::Level::WARN => LogLevel::Warn,
+            tracing::Level::ERROR => LogLevel::Error,
+        }
+    }
+}
+
+/// Destination of events.
+pub trait Sink: Send + Sync + 'static {
+    /// Must not block, events are sent from synchronous code such as [`TracingLayer`].
+    fn send(&self, event: Event);
+}
+
+impl Sink for mpsc::UnboundedSender<Event> {
+    fn send(&self, event: Event) {
+        // receiver is gone, nobody is interested in this event
+        self.unbounded_send(event).ok();
+    }
+}
+
+/// Cheaply cloneable handle to a [`Sink`], the default value discards all events.
+#[derive(Clone)]
+pub struct Sender(Option<Arc<dyn Sink>>);
+
+impl Sender {
+    pub fn new<S: Sink>(sink: S) -> Self {
+        Self(Some(Arc::new(sink)))
+    }
+
+    pub fn discard() -> Self {
+        Self(None)
+    }
+
+    pub fn send(&self, event: Event) {
+        if let Some(sink) = &self.0 {
+            sink.send(event);
+        }
+    }
+}
+
+impl Default for Sender {
+    fn default() -> Self {
+        Self::discard()
+    }
+}
+
+/// Send events to an unbounded channel.
+pub fn channel() -> (Sender, mpsc::UnboundedReceiver<Event>) {
+    let (tx, rx) = mpsc::unbounded();
+    (Sender::new(tx), rx)
+}
+
+/// Collect events in memory, useful for local runners and tests.
+#[derive(Clone, Default)]
+pub struct Collector {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+impl Collector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sender(&self) -> Sender {
+        Sender::new(self.clone())
+    }
+
+    /// Get a copy of collected events.
+    pub fn events(&self) -> Vec<Event> {
+        self.events.lock().unwrap().clone()
+    }
+
+    /// Remove and return collected events.
+    pub fn take(&self) -> Vec<Event> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+impl Sink for Collector {
+    fn send(&self, event: Event) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+/// Sender and node information attached to a [`NODE_SPAN`] span.
+#[derive(Clone)]
+pub(crate) struct NodeSink {
+    pub sender: Sender,
+    pub flow_run_id: FlowRunId,
+    pub node_id: NodeId,
+    pub times: u32,
+}
+
+impl NodeSink {
+    pub fn send(&self, content: EventContent) {
+        self.sender.send(Event {
+            flow_run_id: self.flow_run_id,
+            node_id: self.node_id,
+            times: self.times,
+            time: SystemTime::now(),
+            content,
+        });
+    }
+}
+
+// Span fields can only be primitive values, so we pass the sink to the layer through this map,
+// using a field with the key as its value.
+static NEXT_KEY: AtomicU64 = AtomicU64::new(0);
+static PENDING: Lazy<Mutex<HashMap<u64, NodeSink>>> = Lazy::new(Default::default);
+
+pub(crate) fn node_span(sink: NodeSink) -> tracing::Span {
+    let key = NEXT_KEY.fetch_add(1, Ordering::Relaxed);
+    let (flow_run_id, node_id, times) = (sink.flow_run_id, sink.node_id, sink.times);
+    PENDING.lock().unwrap().insert(key, sink);
+    let span = tracing::info_span!(
+        NODE_SPAN,
+        %flow_run_id,
+        %node_id,
+        times,
+        events_key = key
+    );
+    // the layer has already taken what it needs in `on_new_span`
+    PENDING.lock().unwrap().remove(&key);
+    span
+}
+
+/// A [`tracing_subscriber::Layer`] that forwards events emitted inside a [`NODE_SPAN`] span to
+/// the node's [`Sender`].
+///
+/// ```
+/// use tracing_subscriber::prelude::*;
+///
+/// tracing_subscriber::registry()
+///     .with(flow_lib::events::TracingLayer)
+///     .init();
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TracingLayer;
+
+struct KeyVisitor(Option<u64>);
+
+impl Visit for KeyVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "events_key" {
+            self.0 = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            write!(self.fields, " {}={}", field.name(), value).ok();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            write!(self.message, "{:?}", value).ok();
+        } else {
+            write!(self.fields, " {}={:?}", field.name(), value).ok();
+        }
+    }
+}
+
+impl<S> Layer<S> for TracingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: layer::Context<'_, S>) {
+        if attrs.metadata().name() != NODE_SPAN {
+            return;
+        }
+        let mut visitor = KeyVisitor(None);
+        attrs.record(&mut visitor);
+        let sink = visitor
+            .0
+            .and_then(|key| PENDING.lock().unwrap().get(&key).cloned());
+        if let (Some(sink), Some(span)) = (sink, ctx.span(id)) {
+            span.extensions_mut().insert(sink);
+        }
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: layer::Context<'_, S>) {
+        let Some(scope) = ctx.event_scope(event) else {
+            return;
+        };
+        for span in scope {
+            if let Some(sink) = span.extensions().get::<NodeSink>() {
+                let mut visitor = MessageVisitor::default();
+                event.record(&mut visitor);
+                sink.send(EventContent::Log {
+                    level: event.metadata().level().into(),
+                    message: visitor.message + &visitor.fields,
+                });
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Context;
+    use tracing::Instrument;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_json() {
+        let event = Event {
+            flow_run_id: <_>::default(),
+            node_id: <_>::default(),
+            times: 0,
+            time: SystemTime::UNIX_EPOCH,
+            content: EventContent::Progress {
+                percent: 50.0,
+                message: None,
+            },
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "progress");
+        assert_eq!(serde_json::from_value::<Event>(json).unwrap(), event);
+    }
+
+    #[tokio::test]
+    async fn test_tracing_bridge() {
+        let collector = Collector::new();
+        let ctx = Context {
+            events: collector.sender(),
+            ..Context::default()
+        };
+
+        let subscriber = tracing_subscriber::registry().with(TracingLayer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        tracing::info!("outside of node");
+        ctx.log(LogLevel::Warn, "direct");
+        ctx.progress(10.0, None);
+        async {
+            tracing::info!(amount = 1, "inside node");
+        }
+        .instrument(ctx.node_span())
+        .await;
+
+        let events = collector.take();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[0].content,
+            EventContent::Log {
+                level: LogLevel::Warn,
+                message: "direct".to_owned()
+            }
+        );
+        assert!(matches!(events[1].content, EventContent::Progress { .. }));
+        assert_eq!(
+            events[2].content,
+            EventContent::Log {
+                level: LogLevel::Info,
+                message: "inside node amount=1".to_owned()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_builder_span() {
+        use crate::command::builder::CmdBuilder;
+
+        const DEF: &str = r#"{
+            "type": "native",
+            "data": { "node_id": "log_something" },
+            "sources": [],
+            "targets": []
+        }"#;
+
+        #[derive(Serialize, Deserialize)]
+        struct Empty {}
+
+        async fn run(_: Context, _: Empty) -> Result<Empty, crate::command::CommandError> {
+            tracing::debug!("from run");
+            Ok(Empty {})
+        }
+
+        let cmd = CmdBuilder::new(DEF).unwrap().build(run);
+        let collector = Collector::new();
+        let ctx = Context {
+            events: collector.sender(),
+            ..Context::default()
+        };
+
+        let subscriber = tracing_subscriber::registry().with(TracingLayer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+        cmd.run(ctx, <_>::default()).await.unwrap();
+
+        let events = collector.take();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].content,
+            EventContent::Log {
+                level: LogLevel::Debug,
+                message: "from run".to_owned()
+            }
+        );
+    }
+}
@@ -4,12 +4,25 @@
 //! - [`command`]: implementing a new command.
 //! - [`config`]: types definition
 //! - [`context`]: providing services and information for nodes to use.
+//! - [`error`]: structured errors with stable codes.
+//! - [`secret_store`]: backends for reading secrets.
+//! - [`events`]: per-node logs, progress and custom events.
+//! - [`policy`]: per-node retry, timeout and circuit-breaker policies.
 //! - [`solana`]: utilities for working with Solana.
 //! - [`utils`]: other utilities.
 
+// allow macros from `flow-lib-macros` to refer to `::flow_lib` inside this crate
+extern crate self as flow_lib;
+
 pub mod command;
 pub mod config;
 pub mod context;
+pub mod error;
+pub mod events;
+pub mod policy;
+pub mod record;
+pub mod runner;
+pub mod secret_store;
 pub mod solana;
 pub mod utils;
 
@@ -19,8 +32,9 @@ pub type UserId = uuid::Uuid;
 
 pub use config::{
     CmdInputDescription, CmdOutputDescription, CommandType, ContextConfig, FlowConfig, FlowId,
-    FlowRunId, Gate, HttpClientConfig, Name, NodeConfig, NodeId, SolanaClientConfig, SolanaNet,
-    ValueSet, ValueType,
+    FlowRunId, Gate, HttpClientConfig, Name, NodeConfig, NodeId, SecretsConfig, SolanaClientConfig,
+    SolanaNet, ValueSet, ValueType,
 };
 pub use context::{Context, User};
+pub use error::{ErrorCode, FlowError};
 pub use value::{self, Error as ValueError, Value};
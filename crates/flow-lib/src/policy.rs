@@ -0,0 +1,798 @@
+//! Per-node retry, timeout and circuit-breaker policies.
+//!
+//! A [`Policy`] is declared in node-definition files (the `"policy"` field of
+//! [`Definition`][crate::config::node::Definition]) and can be overridden for each node with
+//! [`NodeConfig::policy`][crate::NodeConfig::policy].
+//!
+//! Policies are implemented as [`tower`] layers around a [`CommandService`], use [`service`] to
+//! build the stack:
+//! - [`CircuitBreakerLayer`]: fail fast after too many consecutive transient failures.
+//! - [`RetryLayer`]: retry [transient][is_transient] failures with exponential backoff, but never
+//!   after the command has called the [`execute`] service, because the transaction could have
+//!   been sent even if the call failed or timed out, or after it has emitted an output with
+//!   [`Context::emit`], because downstream nodes could already have run on it.
+//! - [`TimeoutLayer`]: limit the duration of each attempt.
+//!
+//! The circuit-breaker state is owned by [`CircuitBreakerLayer`] and shared by all services it
+//! builds. Runners keep one layer per node in [`Breakers`], so that the circuit stays open
+//! across runs and loop iterations of the node.
+
+use crate::{
+    command::{CommandError, CommandTrait},
+    context::{execute, stream},
+    utils::BoxFuture,
+    Context, NodeId, ValueSet,
+};
+use serde::{Deserialize, Serialize};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_request::RpcError,
+};
+use std::{
+    collections::HashMap,
+    num::NonZeroU64,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::Poll,
+    time::Duration,
+};
+use thiserror::Error as ThisError;
+use tokio::time::Instant;
+use tower::{util::BoxCloneService, Layer, Service};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Policy {
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
+    /// Maximum duration of each attempt.
+    #[serde(default)]
+    pub timeout_in_secs: Option<NonZeroU64>,
+    #[serde(default)]
+    pub circuit_breaker: Option<CircuitBreakerPolicy>,
+}
+
+impl Policy {
+    /// Fields that are set in `other` take precedence.
+    pub fn merge(&self, other: Option<&Policy>) -> Policy {
+        match other {
+            None => self.clone(),
+            Some(other) => Policy {
+                retry: other.retry.clone().or_else(|| self.retry.clone()),
+                timeout_in_secs: other.timeout_in_secs.or(self.timeout_in_secs),
+                circuit_breaker: other
+                    .circuit_breaker
+                    .clone()
+                    .or_else(|| self.circuit_breaker.clone()),
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    #[serde(default = "RetryPolicy::default_max_attempts")]
+    pub max_attempts: u32,
+    #[serde(default = "RetryPolicy::default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "RetryPolicy::default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    #[serde(default = "RetryPolicy::default_backoff_multiplier")]
+    pub backoff_multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: Self::default_max_attempts(),
+            initial_backoff_ms: Self::default_initial_backoff_ms(),
+            max_backoff_ms: Self::default_max_backoff_ms(),
+            backoff_multiplier: Self::default_backoff_multiplier(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    const fn default_max_attempts() -> u32 {
+        3
+    }
+
+    const fn default_initial_backoff_ms() -> u64 {
+        500
+    }
+
+    const fn default_max_backoff_ms() -> u64 {
+        10_000
+    }
+
+    const fn default_backoff_multiplier() -> u32 {
+        2
+    }
+
+    /// Delay before attempt number `attempt + 1`, `attempt` starts from 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = (self.backoff_multiplier as u64).saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(
+            self.initial_backoff_ms
+                .saturating_mul(factor)
+                .min(self.max_backoff_ms),
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CircuitBreakerPolicy {
+    /// Open the circuit after this many consecutive transient failures.
+    #[serde(default = "CircuitBreakerPolicy::default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Allow a trial run after the circuit has been open for this long.
+    #[serde(default = "CircuitBreakerPolicy::default_reset_in_secs")]
+    pub reset_in_secs: u64,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: Self::default_failure_threshold(),
+            reset_in_secs: Self::default_reset_in_secs(),
+        }
+    }
+}
+
+impl CircuitBreakerPolicy {
+    const fn default_failure_threshold() -> u32 {
+        5
+    }
+
+    const fn default_reset_in_secs() -> u64 {
+        30
+    }
+}
+
+#[derive(ThisError, Debug, Clone, Copy)]
+pub enum PolicyError {
+    #[error("node timed out after {0:?}")]
+    TimedOut(Duration),
+    #[error("circuit breaker is open, too many consecutive failures")]
+    CircuitOpen,
+}
+
+/// Check if an error is likely to go away by retrying: network errors, RPC node errors, HTTP 429
+/// and 5xx status codes, and [`PolicyError::TimedOut`].
+///
+/// Errors of the [`execute`] service are never transient, the transaction could have been sent.
+pub fn is_transient(error: &CommandError) -> bool {
+    if error.chain().any(|e| e.is::<execute::Error>()) {
+        return false;
+    }
+    error.chain().any(|e| {
+        if let Some(e) = e.downcast_ref::<ClientError>() {
+            is_transient_client_error(e)
+        } else if let Some(e) = e.downcast_ref::<reqwest::Error>() {
+            is_transient_reqwest_error(e)
+        } else {
+            matches!(
+                e.downcast_ref::<PolicyError>(),
+                Some(PolicyError::TimedOut(_))
+            )
+        }
+    })
+}
+
+fn is_transient_reqwest_error(e: &reqwest::Error) -> bool {
+    e.is_timeout()
+        || e.is_connect()
+        || e.status()
+            .is_some_and(|s| s.as_u16() == 429 || s.is_server_error())
+}
+
+fn is_transient_client_error(e: &ClientError) -> bool {
+    // https://github.com/solana-labs/solana/blob/master/rpc-client-api/src/custom_error.rs
+    const BLOCK_NOT_AVAILABLE: i64 = -32004;
+    const NODE_UNHEALTHY: i64 = -32005;
+    match &e.kind {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(e) => is_transient_reqwest_error(e),
+        ClientErrorKind::RpcError(RpcError::RpcRequestError(_)) => true,
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            matches!(*code, BLOCK_NOT_AVAILABLE | NODE_UNHEALTHY)
+        }
+        _ => false,
+    }
+}
+
+pub struct Request {
+    pub ctx: Context,
+    pub inputs: ValueSet,
+}
+
+pub type Svc = BoxCloneService<Request, ValueSet, CommandError>;
+
+/// Build a service that runs `cmd` with `policy` applied, with a new circuit breaker.
+/// Use [`Breakers::service`] to keep the circuit-breaker state between services.
+pub fn service(cmd: Arc<dyn CommandTrait>, policy: &Policy) -> Svc {
+    let breaker = policy.circuit_breaker.clone().map(CircuitBreakerLayer::new);
+    build(cmd, policy, breaker)
+}
+
+fn build(cmd: Arc<dyn CommandTrait>, policy: &Policy, breaker: Option<CircuitBreakerLayer>) -> Svc {
+    let mut svc = Svc::new(CommandService::new(cmd));
+    if let Some(secs) = policy.timeout_in_secs {
+        svc = Svc::new(TimeoutLayer::new(Duration::from_secs(secs.get())).layer(svc));
+    }
+    if let Some(retry) = &policy.retry {
+        svc = Svc::new(RetryLayer::new(retry.clone()).layer(svc));
+    }
+    if let Some(breaker) = breaker {
+        svc = Svc::new(breaker.layer(svc));
+    }
+    svc
+}
+
+/// Circuit breakers of nodes, cheap to clone.
+#[derive(Clone, Default)]
+pub struct Breakers(Arc<Mutex<HashMap<NodeId, CircuitBreakerLayer>>>);
+
+impl Breakers {
+    /// Build a service like [`service`], reusing the circuit breaker of `node_id`. The breaker
+    /// is reset if its policy has changed.
+    pub fn service(&self, node_id: NodeId, cmd: Arc<dyn CommandTrait>, policy: &Policy) -> Svc {
+        let breaker = policy.circuit_breaker.as_ref().map(|p| {
+            let mut breakers = self.0.lock().unwrap();
+            match breakers.get(&node_id) {
+                Some(layer) if layer.policy == *p => layer.clone(),
+                _ => {
+                    let layer = CircuitBreakerLayer::new(p.clone());
+                    breakers.insert(node_id, layer.clone());
+                    layer
+                }
+            }
+        });
+        build(cmd, policy, breaker)
+    }
+}
+
+/// Call [`CommandTrait::run`].
+#[derive(Clone)]
+pub struct CommandService {
+    cmd: Arc<dyn CommandTrait>,
+}
+
+impl CommandService {
+    pub fn new(cmd: Arc<dyn CommandTrait>) -> Self {
+        Self { cmd }
+    }
+}
+
+impl Service<Request> for CommandService {
+    type Response = ValueSet;
+    type Error = CommandError;
+    type Future = BoxFuture<'static, Result<ValueSet, CommandError>>;
+
+    fn poll_ready(&mut self, _: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let cmd = self.cmd.clone();
+        Box::pin(async move { cmd.run(req.ctx, req.inputs).await })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TimeoutLayer {
+    timeout: Duration,
+}
+
+impl TimeoutLayer {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+impl<S> Layer<S> for TimeoutLayer {
+    type Service = Timeout<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Timeout {
+            inner,
+            timeout: self.timeout,
+        }
+    }
+}
+
+/// Fail with [`PolicyError::TimedOut`] if an attempt takes too long.
+#[derive(Clone)]
+pub struct Timeout<S> {
+    inner: S,
+    timeout: Duration,
+}
+
+impl<S> Service<Request> for Timeout<S>
+where
+    S: Service<Request, Response = ValueSet, Error = CommandError>,
+    S::Future: Send + 'static,
+{
+    type Response = ValueSet;
+    type Error = CommandError;
+    type Future = BoxFuture<'static, Result<ValueSet, CommandError>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let timeout = self.timeout;
+        let fut = self.inner.call(req);
+        Box::pin(async move {
+            tokio::time::timeout(timeout, fut)
+                .await
+                .map_err(|_| PolicyError::TimedOut(timeout))?
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RetryLayer {
+    policy: RetryPolicy,
+}
+
+impl RetryLayer {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+impl<S> Layer<S> for RetryLayer {
+    type Service = Retry<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Retry {
+            inner,
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+/// Retry transient failures, unless the [`execute`] or [`stream`] service has been called.
+#[derive(Clone)]
+pub struct Retry<S> {
+    inner: S,
+    policy: RetryPolicy,
+}
+
+/// Wrap [`execute`] and [`stream`] services of `ctx` to record if one of them has been called.
+///
+/// The flag is set before calling the inner service, a transaction can be sent even if the call
+/// fails or is dropped by [`Timeout`].
+fn track_effects(ctx: &mut Context) -> Arc<AtomicBool> {
+    let called = Arc::new(AtomicBool::new(false));
+    if let Some(c) = ctx.command.as_mut() {
+        let inner = c.svc.clone();
+        let flag = called.clone();
+        let handle = move |req: execute::Request| {
+            flag.store(true, Ordering::SeqCst);
+            let inner = inner.clone();
+            async move { inner.call_ref(req).await }
+        };
+        c.svc = execute::Svc::from_service(tower::service_fn(handle), execute::Error::worker, 1);
+
+        let inner = c.stream.clone();
+        let flag = called.clone();
+        let handle = move |req: stream::Request| {
+            flag.store(true, Ordering::SeqCst);
+            let inner = inner.clone();
+            async move { inner.call_ref(req).await }
+        };
+        c.stream = stream::Svc::from_service(tower::service_fn(handle), stream::Error::worker, 1);
+    }
+    called
+}
+
+impl<S> Service<Request> for Retry<S>
+where
+    S: Service<Request, Response = ValueSet, Error = CommandError> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = ValueSet;
+    type Error = CommandError;
+    type Future = BoxFuture<'static, Result<ValueSet, CommandError>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let clone = self.inner.clone();
+        // use the service that was polled ready for the first attempt
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let policy = self.policy.clone();
+        Box::pin(async move {
+            let Request { mut ctx, inputs } = req;
+            let executed = track_effects(&mut ctx);
+            let mut attempt = 1;
+            loop {
+                let result = inner
+                    .call(Request {
+                        ctx: ctx.clone(),
+                        inputs: inputs.clone(),
+                    })
+                    .await;
+                let error = match result {
+                    Ok(output) => return Ok(output),
+                    Err(error) => error,
+                };
+                if attempt >= policy.max_attempts
+                    || executed.load(Ordering::SeqCst)
+                    || !is_transient(&error)
+                    || ctx.check_canceled().is_err()
+                {
+                    return Err(error);
+                }
+                let delay = policy.backoff(attempt);
+                tracing::warn!(
+                    "attempt {} failed, retrying in {:?}: {}",
+                    attempt,
+                    delay,
+                    error
+                );
+                ctx.cancelable(tokio::time::sleep(delay)).await?;
+                attempt += 1;
+                futures::future::poll_fn(|cx| inner.poll_ready(cx)).await?;
+            }
+        })
+    }
+}
+
+/// Services built by the same layer, or its clones, share the circuit-breaker state.
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerLayer {
+    policy: CircuitBreakerPolicy,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+impl CircuitBreakerLayer {
+    pub fn new(policy: CircuitBreakerPolicy) -> Self {
+        Self {
+            policy,
+            state: <_>::default(),
+        }
+    }
+}
+
+impl<S> Layer<S> for CircuitBreakerLayer {
+    type Service = CircuitBreaker<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CircuitBreaker {
+            inner,
+            policy: self.policy.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Fail with [`PolicyError::CircuitOpen`] without running the command, after
+/// [`failure_threshold`][CircuitBreakerPolicy::failure_threshold] consecutive transient failures.
+#[derive(Clone)]
+pub struct CircuitBreaker<S> {
+    inner: S,
+    policy: CircuitBreakerPolicy,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+impl<S> Service<Request> for CircuitBreaker<S>
+where
+    S: Service<Request, Response = ValueSet, Error = CommandError>,
+    S::Future: Send + 'static,
+{
+    type Response = ValueSet;
+    type Error = CommandError;
+    type Future = BoxFuture<'static, Result<ValueSet, CommandError>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        {
+            let mut state = self.state.lock().unwrap();
+            match state.open_until {
+                Some(until) if Instant::now() < until => {
+                    return Box::pin(async { Err(PolicyError::CircuitOpen.into()) });
+                }
+                // half-open: let this call through, a failure will open the circuit again
+                Some(_) => {
+                    state.open_until = None;
+                    state.consecutive_failures = self.policy.failure_threshold.saturating_sub(1);
+                }
+                None => {}
+            }
+        }
+
+        let fut = self.inner.call(req);
+        let state = self.state.clone();
+        let policy = self.policy.clone();
+        Box::pin(async move {
+            let result = fut.await;
+            let mut state = state.lock().unwrap();
+            match &result {
+                Err(error) if is_transient(error) => {
+                    state.consecutive_failures += 1;
+                    if state.consecutive_failures >= policy.failure_threshold {
+                        state.open_until =
+                            Some(Instant::now() + Duration::from_secs(policy.reset_in_secs));
+                    }
+                }
+                _ => state.consecutive_failures = 0,
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{command::builder::CmdBuilder, solana::Instructions};
+    use std::sync::atomic::AtomicU32;
+    use tower::ServiceExt;
+
+    const DEF: &str = r#"{
+        "type": "native",
+        "data": { "node_id": "flaky" },
+        "sources": [],
+        "targets": [],
+        "policy": { "retry": { "max_attempts": 3, "initial_backoff_ms": 1 } }
+    }"#;
+
+    #[derive(Serialize, Deserialize)]
+    struct Empty {}
+
+    fn flaky(
+        calls: Arc<AtomicU32>,
+        fail_times: u32,
+        send_tx: bool,
+    ) -> (Arc<dyn CommandTrait>, Policy) {
+        let cmd = CmdBuilder::new(DEF)
+            .unwrap()
+            .build(move |mut ctx: Context, _: Empty| {
+                let calls = calls.clone();
+                async move {
+                    let n = calls.fetch_add(1, Ordering::SeqCst);
+                    if send_tx {
+                        ctx.execute(Instructions::default(), <_>::default()).await?;
+                    }
+                    if n < fail_times {
+                        Err(PolicyError::TimedOut(Duration::ZERO).into())
+                    } else {
+                        Ok(Empty {})
+                    }
+                }
+            });
+        let policy = cmd.policy();
+        (Arc::from(cmd), policy)
+    }
+
+    fn fake_execute(ctx: &mut Context) {
+        let handle = |_: execute::Request| async {
+            Ok(execute::Response {
+                signature: Some(<_>::default()),
+            })
+        };
+        ctx.command.as_mut().unwrap().svc =
+            execute::Svc::from_service(tower::service_fn(handle), execute::Error::worker, 1);
+    }
+
+    /// Count sends, then fail with `error` or never respond if `error` is `None`.
+    fn failing_execute(ctx: &mut Context, sends: Arc<AtomicU32>, error: Option<execute::Error>) {
+        let handle = move |_: execute::Request| {
+            sends.fetch_add(1, Ordering::SeqCst);
+            let error = error.clone();
+            async move {
+                match error {
+                    Some(error) => Err(error),
+                    None => futures::future::pending().await,
+                }
+            }
+        };
+        ctx.command.as_mut().unwrap().svc =
+            execute::Svc::from_service(tower::service_fn(handle), execute::Error::worker, 1);
+    }
+
+    fn request(ctx: Context) -> Request {
+        Request {
+            ctx,
+            inputs: <_>::default(),
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let def = Policy {
+            retry: Some(RetryPolicy::default()),
+            timeout_in_secs: NonZeroU64::new(10),
+            circuit_breaker: None,
+        };
+        let node = Policy {
+            timeout_in_secs: NonZeroU64::new(1),
+            ..<_>::default()
+        };
+        let merged = def.merge(Some(&node));
+        assert_eq!(merged.retry, def.retry);
+        assert_eq!(merged.timeout_in_secs, NonZeroU64::new(1));
+        assert_eq!(
+            RetryPolicy::default().backoff(1),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            RetryPolicy::default().backoff(3),
+            Duration::from_millis(2000)
+        );
+        assert_eq!(
+            RetryPolicy::default().backoff(30),
+            Duration::from_millis(10_000)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let (cmd, policy) = flaky(calls.clone(), 2, false);
+        service(cmd, &policy)
+            .oneshot(request(Context::default()))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let (cmd, policy) = flaky(calls.clone(), 5, false);
+        service(cmd, &policy)
+            .oneshot(request(Context::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_after_emit() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let cmd = CmdBuilder::new(DEF)
+            .unwrap()
+            .build(move |ctx: Context, _: Empty| {
+                let calls = counter.clone();
+                async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    ctx.emit(value::map! { "element" => 1u64 }).await?;
+                    Err::<Empty, _>(PolicyError::TimedOut(Duration::ZERO).into())
+                }
+            });
+        let policy = cmd.policy();
+        let mut ctx = Context::default();
+        let (svc, _rx) = stream::channel(1);
+        ctx.command.as_mut().unwrap().stream = svc;
+        service(Arc::from(cmd), &policy)
+            .oneshot(request(ctx))
+            .await
+            .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_after_signature() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let (cmd, policy) = flaky(calls.clone(), 2, true);
+        let mut ctx = Context::default();
+        fake_execute(&mut ctx);
+        service(cmd, &policy)
+            .oneshot(request(ctx))
+            .await
+            .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_after_failed_execute() {
+        let transient = || ClientError::from(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+        assert!(is_transient(&transient().into()));
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let sends = Arc::new(AtomicU32::new(0));
+        let (cmd, policy) = flaky(calls.clone(), 0, true);
+        let mut ctx = Context::default();
+        failing_execute(&mut ctx, sends.clone(), Some(transient().into()));
+        let error = service(cmd, &policy)
+            .oneshot(request(ctx))
+            .await
+            .unwrap_err();
+        assert!(!is_transient(&error));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(sends.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_after_execute_timeout() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let sends = Arc::new(AtomicU32::new(0));
+        let (cmd, mut policy) = flaky(calls.clone(), 0, true);
+        policy.timeout_in_secs = NonZeroU64::new(1);
+        let mut ctx = Context::default();
+        failing_execute(&mut ctx, sends.clone(), None);
+        tokio::time::pause();
+        let error = service(cmd, &policy)
+            .oneshot(request(ctx))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<PolicyError>(),
+            Some(PolicyError::TimedOut(_))
+        ));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(sends.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_and_circuit_breaker() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let cmd = CmdBuilder::new(DEF)
+            .unwrap()
+            .build(move |_: Context, _: Empty| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Ok(Empty {})
+                }
+            });
+        let policy = Policy {
+            retry: None,
+            timeout_in_secs: NonZeroU64::new(1),
+            circuit_breaker: Some(CircuitBreakerPolicy {
+                failure_threshold: 2,
+                reset_in_secs: 60,
+            }),
+        };
+        tokio::time::pause();
+        let cmd = Arc::<dyn CommandTrait>::from(cmd);
+        let breakers = Breakers::default();
+        let node_id = NodeId::new_v4();
+        // a new service for each run, like the runner does
+        let svc = || breakers.service(node_id, cmd.clone(), &policy);
+        for _ in 0..2 {
+            let error = svc()
+                .oneshot(request(Context::default()))
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                error.downcast_ref::<PolicyError>(),
+                Some(PolicyError::TimedOut(_))
+            ));
+        }
+        let error = svc()
+            .oneshot(request(Context::default()))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<PolicyError>(),
+            Some(PolicyError::CircuitOpen)
+        ));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // other nodes have their own breaker
+        breakers
+            .service(NodeId::new_v4(), cmd.clone(), &policy)
+            .oneshot(request(Context::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+}
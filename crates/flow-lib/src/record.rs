@@ -0,0 +1,794 @@
+//! Record runs into a portable [`RunLog`], and replay them without network access.
+//!
+//! A [`Recorder`] records:
+//! - inputs and results of commands run by [`FlowRunner`][crate::runner::FlowRunner].
+//! - Solana RPC requests of [`Context::solana_client`] and their responses.
+//! - HTTP requests sent with [`Context::send_http`] and their responses. Request headers are not
+//!   recorded because they often contain secrets.
+//! - signatures returned by the [`signer`] service.
+//!
+//! A [`Replay`] serves RPC, HTTP and signature requests from a log. A request is answered with the
+//! first unused entry that has the same request, so concurrent nodes can be replayed in any
+//! order. Transactions sent with [`execute`][crate::context::execute] are replayed through the
+//! RPC client and the signer.
+//!
+//! Secrets and JWTs are not recorded, in replay mode their value is [`REDACTED`]. Keypairs in
+//! inputs and results of commands are replaced by their public keys, see [`Keypairs`]. In replay
+//! mode, a redacted keypair input is replaced by a [dummy keypair][Replay::dummy_keypair], its
+//! public key and signatures are mapped to the recorded ones when matching requests.
+
+use crate::{
+    command::CommandTrait,
+    context::{get_jwt, secrets, signer},
+    Context, FlowError, Name, NodeId, Value, ValueSet, ValueType,
+};
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use serde_with::{serde_as, DisplayFromStr};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    nonblocking::rpc_client::RpcClient,
+    rpc_client::RpcClientConfig,
+    rpc_request::{RpcError, RpcRequest, RpcResponseErrorData},
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use solana_sdk::{
+    bs58,
+    hash::hashv,
+    pubkey::Pubkey,
+    short_vec::decode_shortu16_len,
+    signature::{Keypair, Signature, Signer},
+    signer::keypair::keypair_from_seed,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Value of secrets and JWTs in replay mode.
+pub const REDACTED: &str = "REDACTED";
+
+/// Recorded entries of a run, in the order they finished.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunLog {
+    pub entries: Vec<Entry>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Entry {
+    Command {
+        node_id: NodeId,
+        times: u32,
+        name: Name,
+        inputs: ValueSet,
+        result: Result<ValueSet, FlowError>,
+    },
+    Rpc {
+        method: String,
+        params: JsonValue,
+        result: Result<JsonValue, RpcFailure>,
+    },
+    Http {
+        method: String,
+        url: String,
+        body: Option<Body>,
+        result: Result<HttpResponse, String>,
+    },
+    Signature {
+        #[serde_as(as = "DisplayFromStr")]
+        pubkey: Pubkey,
+        message: Body,
+        /// Base58 signature.
+        result: Result<String, String>,
+    },
+}
+
+/// A failed RPC request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcFailure {
+    /// JSON-RPC error response, `simulation` is the result of a failed preflight simulation.
+    Response {
+        code: i64,
+        message: String,
+        simulation: Option<JsonValue>,
+    },
+    Other(String),
+}
+
+impl RpcFailure {
+    fn new(error: &ClientError) -> Self {
+        match error.kind() {
+            ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                code,
+                message,
+                data,
+            }) => RpcFailure::Response {
+                code: *code,
+                message: message.clone(),
+                simulation: match data {
+                    RpcResponseErrorData::SendTransactionPreflightFailure(result) => {
+                        serde_json::to_value(result).ok()
+                    }
+                    _ => None,
+                },
+            },
+            _ => RpcFailure::Other(error.to_string()),
+        }
+    }
+
+    fn into_error(self) -> ClientError {
+        match self {
+            RpcFailure::Response {
+                code,
+                message,
+                simulation,
+            } => RpcError::RpcResponseError {
+                code,
+                message,
+                data: match simulation.and_then(|v| serde_json::from_value(v).ok()) {
+                    Some(result) => RpcResponseErrorData::SendTransactionPreflightFailure(result),
+                    None => RpcResponseErrorData::Empty,
+                },
+            },
+            RpcFailure::Other(message) => RpcError::ForUser(message),
+        }
+        .into()
+    }
+}
+
+/// Bytes of a body, as text if it is valid UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Body {
+    Text(String),
+    Binary { base64: String },
+}
+
+impl From<&[u8]> for Body {
+    fn from(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Body::Text(text.to_owned()),
+            Err(_) => Body::Binary {
+                base64: base64::encode(bytes),
+            },
+        }
+    }
+}
+
+impl Body {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        match self {
+            Body::Text(text) => Ok(text.as_bytes().to_vec()),
+            Body::Binary { base64 } => base64::decode(base64),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Body,
+}
+
+impl HttpResponse {
+    async fn read(resp: reqwest::Response) -> Result<Self, reqwest::Error> {
+        let status = resp.status().as_u16();
+        // the body is stored decoded
+        let headers = resp
+            .headers()
+            .iter()
+            .filter(|(k, _)| {
+                !matches!(
+                    k.as_str(),
+                    "content-encoding" | "content-length" | "transfer-encoding"
+                )
+            })
+            .map(|(k, v)| {
+                let v = String::from_utf8_lossy(v.as_bytes()).into_owned();
+                (k.as_str().to_owned(), v)
+            })
+            .collect();
+        let body = Body::from(&resp.bytes().await?[..]);
+        Ok(Self {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    fn into_response(self) -> Result<reqwest::Response, anyhow::Error> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (k, v) in self.headers {
+            builder = builder.header(k, v);
+        }
+        Ok(builder.body(self.body.to_bytes()?)?.into())
+    }
+}
+
+/// Names of the inputs and outputs of a command that are declared as keypairs.
+#[derive(Debug, Clone, Default)]
+pub struct Keypairs {
+    inputs: Vec<Name>,
+    outputs: Vec<Name>,
+}
+
+impl Keypairs {
+    pub fn new(cmd: &dyn CommandTrait) -> Self {
+        Self {
+            inputs: cmd
+                .inputs()
+                .into_iter()
+                .filter(|i| i.type_bounds.contains(&ValueType::Keypair))
+                .map(|i| i.name)
+                .collect(),
+            outputs: cmd
+                .outputs()
+                .into_iter()
+                .filter(|o| o.r#type == ValueType::Keypair)
+                .map(|o| o.name)
+                .collect(),
+        }
+    }
+
+    /// Inputs to record, with keypairs replaced by their public keys.
+    pub fn redact_inputs(&self, inputs: &ValueSet) -> ValueSet {
+        redact(inputs, &self.inputs)
+    }
+
+    /// Outputs to record, with keypairs replaced by their public keys.
+    pub fn redact_outputs(&self, outputs: &ValueSet) -> ValueSet {
+        redact(outputs, &self.outputs)
+    }
+}
+
+/// Public key of a keypair, if `bytes` is one.
+fn valid_keypair(bytes: &[u8]) -> Option<Pubkey> {
+    if bytes.len() != 64 {
+        return None;
+    }
+    // public keys are not checked when parsing a keypair
+    let keypair = keypair_from_seed(&bytes[..32]).ok()?;
+    (keypair.pubkey().as_ref() == &bytes[32..]).then(|| keypair.pubkey())
+}
+
+/// Replace values of `names` that are keypairs, and any valid keypair in 64-byte values, with
+/// their public keys.
+fn redact(values: &ValueSet, names: &[Name]) -> ValueSet {
+    values
+        .iter()
+        .map(|(name, value)| {
+            let keypair = names
+                .contains(name)
+                .then(|| value::keypair::deserialize(value.clone()).ok())
+                .flatten();
+            let value = match keypair {
+                Some(keypair) => Value::from(keypair.pubkey()),
+                None => redact_value(value),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+fn redact_value(value: &Value) -> Value {
+    match value {
+        Value::B64(bytes) => match valid_keypair(bytes) {
+            Some(pubkey) => Value::from(pubkey),
+            None => value.clone(),
+        },
+        Value::Bytes(bytes) => match valid_keypair(bytes) {
+            Some(pubkey) => Value::from(pubkey),
+            None => value.clone(),
+        },
+        Value::Array(array) => Value::Array(array.iter().map(redact_value).collect()),
+        Value::Map(map) => Value::Map(
+            map.iter()
+                .map(|(k, v)| (k.clone(), redact_value(v)))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// Signatures and message of a transaction sent with `sendTransaction` or `simulateTransaction`.
+fn transaction(method: &str, params: &JsonValue) -> Option<(Vec<Signature>, Vec<u8>)> {
+    if !matches!(method, "sendTransaction" | "simulateTransaction") {
+        return None;
+    }
+    let data = params.get(0)?.as_str()?;
+    let encoding = params.get(1).and_then(|c| c.get("encoding"));
+    let bytes = match encoding.and_then(JsonValue::as_str) {
+        Some("base64") => base64::decode(data).ok()?,
+        _ => bs58::decode(data).into_vec().ok()?,
+    };
+    let (count, offset) = decode_shortu16_len(&bytes).ok()?;
+    let end = offset.checked_add(count.checked_mul(64)?)?;
+    let signatures = bytes
+        .get(offset..end)?
+        .chunks(64)
+        .map(Signature::try_from)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    Some((signatures, bytes[end..].to_vec()))
+}
+
+/// Collect entries of a run, clones share the same log.
+#[derive(Clone, Default)]
+pub struct Recorder(Arc<Mutex<RunLog>>);
+
+impl Recorder {
+    pub fn push(&self, entry: Entry) {
+        self.0.lock().unwrap().entries.push(entry);
+    }
+
+    pub fn log(&self) -> RunLog {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn signer_svc(&self, inner: signer::Svc) -> signer::Svc {
+        let recorder = self.clone();
+        let handle = move |req: signer::SignatureRequest| {
+            let inner = inner.clone();
+            let recorder = recorder.clone();
+            async move {
+                let pubkey = req.pubkey;
+                let message = Body::from(&req.message[..]);
+                let result = inner.call_ref(req).await;
+                recorder.push(Entry::Signature {
+                    pubkey,
+                    message,
+                    result: match &result {
+                        Ok(resp) => Ok(resp.signature.to_string()),
+                        Err(error) => Err(error.to_string()),
+                    },
+                });
+                result
+            }
+        };
+        signer::Svc::from_service(tower::service_fn(handle), signer::Error::Worker, 16)
+    }
+}
+
+struct RecordSender {
+    inner: Arc<RpcClient>,
+    recorder: Recorder,
+}
+
+#[async_trait::async_trait]
+impl RpcSender for RecordSender {
+    async fn send(&self, request: RpcRequest, params: JsonValue) -> ClientResult<JsonValue> {
+        let result = self.inner.send(request, params.clone()).await;
+        self.recorder.push(Entry::Rpc {
+            method: request.to_string(),
+            params,
+            result: match &result {
+                Ok(value) => Ok(JsonValue::clone(value)),
+                Err(error) => Err(RpcFailure::new(error)),
+            },
+        });
+        result
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+/// Serve requests from a [`RunLog`], clones share the same log.
+#[derive(Clone)]
+pub struct Replay {
+    entries: Arc<Mutex<Vec<Option<Entry>>>>,
+    replaced: Arc<Mutex<Replaced>>,
+}
+
+/// Values that differ from the log because of dummy keypairs.
+#[derive(Default)]
+struct Replaced {
+    /// Public keys of dummy keypairs to recorded public keys.
+    pubkeys: HashMap<Pubkey, Pubkey>,
+    /// Signatures of replayed transactions to recorded signatures.
+    signatures: HashMap<String, String>,
+}
+
+impl Replay {
+    pub fn new(log: RunLog) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(log.entries.into_iter().map(Some).collect())),
+            replaced: <_>::default(),
+        }
+    }
+
+    /// Take the first unused entry matching `f`.
+    fn take(&self, f: impl Fn(&Entry) -> bool) -> Option<Entry> {
+        let mut entries = self.entries.lock().unwrap();
+        entries
+            .iter_mut()
+            .find(|e| e.as_ref().is_some_and(&f))
+            .and_then(Option::take)
+    }
+
+    /// A keypair derived from the redacted keypair `pubkey`, requests that use its public key
+    /// are replayed as if they used `pubkey`.
+    pub fn dummy_keypair(&self, pubkey: &Pubkey) -> Keypair {
+        let seed = hashv(&[b"replay", pubkey.as_ref()]);
+        // seeds of 32 bytes are valid
+        let keypair = keypair_from_seed(seed.as_ref()).unwrap();
+        self.replaced
+            .lock()
+            .unwrap()
+            .pubkeys
+            .insert(keypair.pubkey(), *pubkey);
+        keypair
+    }
+
+    /// Replace redacted keypair inputs with [dummy keypairs][Self::dummy_keypair].
+    pub fn restore_keypairs(&self, keypairs: &Keypairs, mut inputs: ValueSet) -> ValueSet {
+        for name in &keypairs.inputs {
+            if let Some(value) = inputs.get_mut(name) {
+                if let Value::B32(pubkey) = *value {
+                    *value = Value::from(self.dummy_keypair(&Pubkey::new_from_array(pubkey)));
+                }
+            }
+        }
+        inputs
+    }
+
+    /// Replace public keys of dummy keypairs and signatures of replayed transactions with the
+    /// recorded ones.
+    fn recorded_params(&self, params: JsonValue) -> JsonValue {
+        let replaced = self.replaced.lock().unwrap();
+        if replaced.pubkeys.is_empty() && replaced.signatures.is_empty() {
+            return params;
+        }
+        let strings = replaced
+            .pubkeys
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .chain(replaced.signatures.clone())
+            .collect::<HashMap<_, _>>();
+        fn replace(value: JsonValue, strings: &HashMap<String, String>) -> JsonValue {
+            match value {
+                JsonValue::String(s) => JsonValue::String(strings.get(&s).cloned().unwrap_or(s)),
+                JsonValue::Array(array) => {
+                    JsonValue::Array(array.into_iter().map(|v| replace(v, strings)).collect())
+                }
+                JsonValue::Object(object) => JsonValue::Object(
+                    object
+                        .into_iter()
+                        .map(|(k, v)| (k, replace(v, strings)))
+                        .collect(),
+                ),
+                value => value,
+            }
+        }
+        replace(params, &strings)
+    }
+
+    /// Replace public keys of dummy keypairs in a transaction message with the recorded ones.
+    fn recorded_message(&self, mut message: Vec<u8>) -> Vec<u8> {
+        let replaced = self.replaced.lock().unwrap();
+        for (dummy, recorded) in &replaced.pubkeys {
+            let mut i = 0;
+            while i + 32 <= message.len() {
+                if message[i..i + 32] == dummy.as_ref()[..] {
+                    message[i..i + 32].copy_from_slice(recorded.as_ref());
+                    i += 32;
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        message
+    }
+
+    /// Entries that were not used by the replay.
+    pub fn unused(&self) -> Vec<Entry> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    fn signer_svc(&self) -> signer::Svc {
+        let replay = self.clone();
+        let handle = move |req: signer::SignatureRequest| {
+            let message = Body::from(&req.message[..]);
+            let entry = replay.take(|e| {
+                matches!(e, Entry::Signature { pubkey, message: m, .. }
+                    if *pubkey == req.pubkey && *m == message)
+            });
+            let result = match entry {
+                Some(Entry::Signature { result, .. }) => result
+                    .and_then(|s| s.parse().map_err(|e| format!("{e}")))
+                    .map(|signature| signer::SignatureResponse { signature })
+                    .map_err(|e| signer::Error::Other(e.into())),
+                _ => Err(signer::Error::Other(
+                    format!("no recorded signature of {}", req.pubkey).into(),
+                )),
+            };
+            std::future::ready(result)
+        };
+        signer::Svc::from_service(tower::service_fn(handle), signer::Error::Worker, 16)
+    }
+}
+
+struct ReplaySender {
+    replay: Replay,
+    url: String,
+}
+
+#[async_trait::async_trait]
+impl RpcSender for ReplaySender {
+    async fn send(&self, request: RpcRequest, params: JsonValue) -> ClientResult<JsonValue> {
+        let method = request.to_string();
+        let params = self.replay.recorded_params(params);
+        // transactions signed by dummy keypairs are matched by their message
+        let tx = transaction(&method, &params);
+        let message = tx
+            .as_ref()
+            .map(|(_, message)| self.replay.recorded_message(message.clone()));
+        let entry = self.replay.take(|e| match e {
+            Entry::Rpc {
+                method: m,
+                params: p,
+                ..
+            } if *m == method => {
+                *p == params
+                    || message.as_ref().is_some_and(|message| {
+                        p.get(1) == params.get(1)
+                            && transaction(m, p).is_some_and(|(_, m)| m == *message)
+                    })
+            }
+            _ => false,
+        });
+        match entry {
+            Some(Entry::Rpc { result, .. }) => {
+                let result = result.map_err(RpcFailure::into_error)?;
+                let replayed = tx.and_then(|(signatures, _)| signatures.first().copied());
+                match (request, replayed, result.as_str()) {
+                    (RpcRequest::SendTransaction, Some(replayed), Some(recorded))
+                        if replayed.to_string() != recorded =>
+                    {
+                        let replayed = replayed.to_string();
+                        self.replay
+                            .replaced
+                            .lock()
+                            .unwrap()
+                            .signatures
+                            .insert(replayed.clone(), recorded.to_owned());
+                        Ok(JsonValue::String(replayed))
+                    }
+                    _ => Ok(result),
+                }
+            }
+            _ => {
+                Err(RpcError::ForUser(format!("no recorded response of {method} {params}")).into())
+            }
+        }
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        self.url.clone()
+    }
+}
+
+/// Whether a [`Context`] records or replays its requests.
+#[derive(Clone, Default)]
+pub enum Mode {
+    #[default]
+    Off,
+    Record(Recorder),
+    Replay(Replay),
+}
+
+impl Mode {
+    /// Record or replay the services of `ctx`.
+    pub fn install(&self, ctx: &mut Context) {
+        self.install_client(ctx);
+        match self {
+            Mode::Off => {}
+            Mode::Record(recorder) => ctx.signer = recorder.signer_svc(ctx.signer.clone()),
+            Mode::Replay(replay) => {
+                ctx.signer = replay.signer_svc();
+                ctx.get_jwt = get_jwt::Svc::from_service(
+                    tower::service_fn(|_: get_jwt::Request| {
+                        std::future::ready(Ok(get_jwt::Response {
+                            access_token: REDACTED.to_owned(),
+                        }))
+                    }),
+                    get_jwt::Error::worker,
+                    1,
+                );
+                ctx.secrets = secrets::Svc::from_service(
+                    tower::service_fn(|_: secrets::Request| {
+                        std::future::ready(Ok(secrets::Response {
+                            value: secrets::Secret::new(REDACTED.to_owned()),
+                        }))
+                    }),
+                    secrets::Error::worker,
+                    1,
+                );
+            }
+        }
+    }
+
+    /// Only replace services created by [`Context::from_cfg`], for contexts that use the
+    /// services of an installed context.
+    pub(crate) fn install_client(&self, ctx: &mut Context) {
+        ctx.record = self.clone();
+        let config = RpcClientConfig::with_commitment(ctx.solana_client.commitment());
+        match self {
+            Mode::Off => {}
+            Mode::Record(recorder) => {
+                let sender = RecordSender {
+                    inner: ctx.solana_client.clone(),
+                    recorder: recorder.clone(),
+                };
+                ctx.solana_client = Arc::new(RpcClient::new_sender(sender, config));
+            }
+            Mode::Replay(replay) => {
+                let sender = ReplaySender {
+                    replay: replay.clone(),
+                    url: ctx.solana_client.url(),
+                };
+                ctx.solana_client = Arc::new(RpcClient::new_sender(sender, config));
+            }
+        }
+    }
+
+    pub(crate) async fn send_http(
+        &self,
+        req: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, anyhow::Error> {
+        let (client, req) = req.build_split();
+        let req = req?;
+        let method = req.method().to_string();
+        let url = req.url().to_string();
+        let body = req.body().and_then(|b| b.as_bytes()).map(Body::from);
+        match self {
+            Mode::Off => Ok(client.execute(req).await?),
+            Mode::Record(recorder) => {
+                let result = match client.execute(req).await {
+                    Ok(resp) => HttpResponse::read(resp).await,
+                    Err(error) => Err(error),
+                };
+                recorder.push(Entry::Http {
+                    method,
+                    url,
+                    body,
+                    result: match &result {
+                        Ok(resp) => Ok(resp.clone()),
+                        Err(error) => Err(error.to_string()),
+                    },
+                });
+                result?.into_response()
+            }
+            Mode::Replay(replay) => {
+                let entry = replay.take(|e| {
+                    matches!(e, Entry::Http { method: m, url: u, body: b, .. }
+                        if *m == method && *u == url && *b == body)
+                });
+                match entry {
+                    Some(Entry::Http { result, .. }) => {
+                        result.map_err(anyhow::Error::msg)?.into_response()
+                    }
+                    _ => Err(anyhow!("no recorded response of {method} {url}")),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{system_instruction, transaction::Transaction};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_rpc() {
+        let recorder = Recorder::default();
+        let mut ctx = Context {
+            solana_client: Arc::new(RpcClient::new_mock("succeeds".to_owned())),
+            ..Context::default()
+        };
+        Mode::Record(recorder.clone()).install(&mut ctx);
+        let balance = ctx.solana_client.get_balance(&Pubkey::new_unique()).await;
+        let log = recorder.log();
+        let params = log.entries.iter().find_map(|e| match e {
+            Entry::Rpc { method, params, .. } if method == "getBalance" => Some(params),
+            _ => None,
+        });
+        let pubkey: Pubkey = params.unwrap()[0].as_str().unwrap().parse().unwrap();
+
+        let json = serde_json::to_string(&log).unwrap();
+        let replay = Replay::new(serde_json::from_str(&json).unwrap());
+        let mut ctx = Context::default();
+        Mode::Replay(replay.clone()).install(&mut ctx);
+        assert_eq!(
+            ctx.solana_client.get_balance(&pubkey).await.unwrap(),
+            balance.unwrap()
+        );
+        assert!(replay.unused().is_empty());
+        // each entry is used once
+        assert!(ctx.solana_client.get_balance(&pubkey).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dummy_keypair() {
+        async fn transfer(client: &RpcClient, from: &Keypair) -> ClientResult<()> {
+            let ix =
+                system_instruction::transfer(&from.pubkey(), &Pubkey::new_from_array([1; 32]), 1);
+            let blockhash = client.get_latest_blockhash().await?;
+            let tx =
+                Transaction::new_signed_with_payer(&[ix], Some(&from.pubkey()), &[from], blockhash);
+            let signature = client.send_transaction(&tx).await?;
+            assert_eq!(signature, tx.signatures[0]);
+            client.get_signature_status(&signature).await?;
+            Ok(())
+        }
+
+        let keypair = Keypair::new();
+        let recorder = Recorder::default();
+        let mut ctx = Context {
+            solana_client: Arc::new(RpcClient::new_mock("succeeds".to_owned())),
+            ..Context::default()
+        };
+        Mode::Record(recorder.clone()).install(&mut ctx);
+        transfer(&ctx.solana_client, &keypair).await.unwrap();
+
+        let replay = Replay::new(recorder.log());
+        let dummy = replay.dummy_keypair(&keypair.pubkey());
+        assert_ne!(dummy.pubkey(), keypair.pubkey());
+        let mut ctx = Context::default();
+        Mode::Replay(replay.clone()).install(&mut ctx);
+        transfer(&ctx.solana_client, &dummy).await.unwrap();
+        assert!(replay.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_http() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/path", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let n = socket.read(&mut buf).await.unwrap();
+            assert!(n > 0);
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 5\r\nx-test: 1\r\n\r\nhello")
+                .await
+                .unwrap();
+        });
+
+        let recorder = Recorder::default();
+        let mut ctx = Context::default();
+        Mode::Record(recorder.clone()).install(&mut ctx);
+        let resp = ctx.send_http(ctx.http.get(&url)).await.unwrap();
+        assert_eq!(resp.text().await.unwrap(), "hello");
+
+        // the server only accepts one connection
+        let mut ctx = Context::default();
+        Mode::Replay(Replay::new(recorder.log())).install(&mut ctx);
+        let resp = ctx.send_http(ctx.http.get(&url)).await.unwrap();
+        assert_eq!(resp.headers()["x-test"], "1");
+        assert_eq!(resp.text().await.unwrap(), "hello");
+        assert!(ctx.send_http(ctx.http.post(&url)).await.is_err());
+    }
+}
@@ -0,0 +1,1959 @@
+//! Run a [`FlowConfig`] locally, for self-hosting, development and tests.
+//!
+//! Nodes are built from commands registered with [`inventory`] and run as soon as all of their
+//! upstream nodes have finished, each node runs once. A node is not run if an upstream node failed
+//! or was not run.
+//!
+//! Besides registered commands, these nodes are implemented by the runner:
+//! - [`FLOW_INPUT`]: output the flow input named by `form_data.label` on each of its sources.
+//! - [`FLOW_OUTPUT`]: store its input in [`FlowRunResult::output`] with `form_data.label` as the
+//!   key.
+//! - [`CONST`]: output `form_data.value` on each of its sources.
+//! - [`WAIT`]: output its inputs, so that nodes downstream of it wait for all of them.
+//! - [`IF`]: evaluate [`expr`] `form_data.expression` with the inputs of the node as variables,
+//!   and output the `value` input on `true` or `false`.
+//! - [`SWITCH`]: evaluate `form_data.expression` the same way, and output the `value` input on
+//!   the source named by the result, or on `default` if there is no such source.
+//! - [`FOREACH`] and [`COLLECT`], see below.
+//!
+//! # Loops
+//!
+//! Nodes downstream of a [`FOREACH`] node, up to its [`COLLECT`] nodes, run once for each element
+//! of its `array` input, which can also be a JSON string. Elements run concurrently, at most
+//! `concurrency` at a time if it is set in the inputs or the form data. A [`COLLECT`] node
+//! outputs the values of its `element` input as an `array`, in the order of the elements.
+//! Loops can be nested, events of nodes in a loop have the index of the element as `times`.
+//!
+//! # Streams
+//!
+//! Outputs of a command listed in [`CommandTrait::stream_outputs`] are emitted with
+//! [`Context::emit`] while the command runs. Nodes downstream of these outputs, up to
+//! [`COLLECT`] nodes, run once for each emitted value like the body of a [`FOREACH`] loop, at most
+//! [`STREAM_CONCURRENCY`] at a time. The command waits in [`Context::emit`] while they are busy,
+//! so emitted values are not buffered in memory. Nodes downstream of the other outputs run after
+//! the command has returned, and [`COLLECT`] nodes output the values of their `element` input in
+//! the order they were emitted. Emitted values are also reported as [`FlowEvent::NodeOutput`].
+//!
+//! # Skipped branches
+//!
+//! Outputs of [`IF`] and [`SWITCH`] nodes that were not given a value are dead, and so are all
+//! outputs of skipped nodes. A node is skipped if all edges connected to one of its inputs are
+//! dead, connecting several branches to the same input merges them. Skipped nodes are listed in
+//! [`FlowRunResult::skipped`] and do not make a run fail. A [`COLLECT`] node leaves out elements
+//! in which its input is dead.
+//!
+//! With [`FlowConfig::partial_config`], only nodes in
+//! [`only_nodes`][crate::config::client::PartialConfig::only_nodes] are run. Outputs of the other
+//! nodes are loaded from previous runs with the [`run_store`] service, see
+//! [`FlowRunner::run_store`]. A node that is needed by a node to run fails if its output can not
+//! be loaded.
+//!
+//! `interflow` nodes run other flows with [`run_interflow`], nesting is limited by
+//! [`MAX_CALL_DEPTH`].
+//!
+//! Requests and command results can be recorded and replayed, see [`FlowRunner::record`].
+//!
+//! Progress of a run is reported with [`FlowEvent`]s, see [`FlowRunner::events`].
+
+use crate::{
+    command::{self, CommandDescription, CommandError, CommandTrait},
+    context::{
+        execute, get_flow, get_jwt, run_store, secrets, signer, stream, Cancellation,
+        CommandContext,
+    },
+    events::{self, Event},
+    policy,
+    record::{self, Entry},
+    utils::Extensions,
+    Context, FlowConfig, FlowError, FlowId, FlowRunId, Name, NodeConfig, NodeId, User, ValueSet,
+};
+use futures::{
+    channel::{mpsc, oneshot},
+    future::{BoxFuture, FutureExt},
+    stream::FuturesUnordered,
+    Stream, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+use tower::ServiceExt;
+use value::Value;
+
+pub mod expr;
+
+/// Name of the node that reads flow inputs.
+pub const FLOW_INPUT: &str = "flow_input";
+/// Name of the node that writes flow outputs.
+pub const FLOW_OUTPUT: &str = "flow_output";
+/// Name of the node that outputs a value from its form data.
+pub const CONST: &str = "const";
+/// Name of the node that waits for all of its inputs.
+pub const WAIT: &str = "wait";
+/// Name of the node that routes a value to `true` or `false`.
+pub const IF: &str = "if";
+/// Name of the node that routes a value to one of its sources.
+pub const SWITCH: &str = "switch";
+/// Name of the node that runs its downstream nodes for each element of an array.
+pub const FOREACH: &str = "foreach";
+/// Name of the node that collects values of a [`FOREACH`] loop into an array.
+pub const COLLECT: &str = "collect";
+
+/// Number of emitted values of a streaming node whose downstream nodes run at the same time.
+pub const STREAM_CONCURRENCY: usize = 16;
+
+/// Maximum [`ClientConfig::call_depth`][crate::config::client::ClientConfig::call_depth],
+/// [`run_interflow`] fails if running the flow would exceed it.
+pub const MAX_CALL_DEPTH: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum FlowEvent {
+    FlowStart {
+        flow_run_id: FlowRunId,
+        time: SystemTime,
+    },
+    NodeStart {
+        node_id: NodeId,
+        times: u32,
+        time: SystemTime,
+        input: ValueSet,
+    },
+    /// A value emitted with [`Context::emit`].
+    NodeOutput {
+        node_id: NodeId,
+        times: u32,
+        time: SystemTime,
+        output: ValueSet,
+    },
+    NodeFinish {
+        node_id: NodeId,
+        times: u32,
+        time: SystemTime,
+        output: ValueSet,
+    },
+    NodeError {
+        node_id: NodeId,
+        times: u32,
+        time: SystemTime,
+        error: FlowError,
+    },
+    /// Logs, progress and custom events sent by nodes, see [`events`].
+    NodeEvent(Event),
+    FlowFinish(FlowRunResult),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowRunResult {
+    pub flow_run_id: FlowRunId,
+    /// Values of [`FLOW_OUTPUT`] nodes, by label.
+    pub output: ValueSet,
+    pub node_outputs: HashMap<NodeId, ValueSet>,
+    pub node_errors: HashMap<NodeId, FlowError>,
+    /// Nodes that did not run because an upstream node failed or was not run, or because the
+    /// flow was canceled.
+    pub not_run: Vec<NodeId>,
+    /// Nodes that did not run because of a branch that was not taken, or because a loop had no
+    /// elements.
+    #[serde(default)]
+    pub skipped: Vec<NodeId>,
+}
+
+impl FlowRunResult {
+    /// Whether all nodes finished or were skipped.
+    pub fn is_success(&self) -> bool {
+        self.node_errors.is_empty() && self.not_run.is_empty()
+    }
+}
+
+/// Send node events to the runner's channel.
+struct NodeEvents(mpsc::UnboundedSender<FlowEvent>);
+
+impl events::Sink for NodeEvents {
+    fn send(&self, event: Event) {
+        self.0.unbounded_send(FlowEvent::NodeEvent(event)).ok();
+    }
+}
+
+#[derive(Clone)]
+enum NodeState {
+    Finished(ValueSet),
+    Failed(FlowError),
+    NotRun,
+    Skipped,
+}
+
+/// States of nodes that are visible to the nodes being run, a loop runs each element in a copy of
+/// its scope.
+type Scope = HashMap<NodeId, NodeState>;
+
+/// Nodes that run for each element of a [`FOREACH`] node, or each value emitted by a streaming
+/// node.
+struct Loop {
+    /// Nodes downstream of the [`FOREACH`] node or of the stream outputs, including nested loops,
+    /// in the order of the flow.
+    body: Vec<NodeId>,
+    /// [`COLLECT`] nodes that end the loop.
+    collects: Vec<NodeId>,
+}
+
+/// States of the nodes of a loop, merged from the scopes of its elements.
+struct LoopState {
+    /// Number of elements that have run.
+    count: usize,
+    body: HashMap<NodeId, NodeState>,
+    /// Values of [`COLLECT`] nodes, `None` if the node will not run.
+    arrays: HashMap<NodeId, Option<Vec<Value>>>,
+}
+
+impl LoopState {
+    fn new(l: &Loop) -> Self {
+        Self {
+            count: 0,
+            body: l.body.iter().map(|id| (*id, NodeState::Skipped)).collect(),
+            arrays: l
+                .collects
+                .iter()
+                .map(|id| (*id, Some(Vec::new())))
+                .collect(),
+        }
+    }
+}
+
+/// Values emitted by a streaming node, ends after the node has returned and the values it emitted
+/// before are read.
+fn emitted(
+    rx: mpsc::Receiver<value::Map>,
+    returned: oneshot::Receiver<()>,
+) -> impl Stream<Item = value::Map> {
+    futures::stream::unfold((rx, Some(returned)), |(mut rx, returned)| async move {
+        if let Some(mut returned) = returned {
+            tokio::select! {
+                output = rx.next() => return output.map(|output| (output, (rx, Some(returned)))),
+                _ = &mut returned => {}
+            }
+        }
+        // don't wait for copies of the stream service that the node gave to other tasks
+        rx.close();
+        rx.next().await.map(|output| (output, (rx, None)))
+    })
+}
+
+/// Find the loop of each [`FOREACH`] node and each node in `streams`, or the reason why it is
+/// invalid. `streams` are the stream outputs of nodes, see [`CommandTrait::stream_outputs`].
+fn find_loops(
+    flow: &FlowConfig,
+    streams: &HashMap<NodeId, Vec<Name>>,
+) -> HashMap<NodeId, Result<Loop, String>> {
+    let name = |id: &NodeId| {
+        flow.nodes
+            .iter()
+            .find(|n| n.id == *id)
+            .map(|n| n.command_name.as_str())
+    };
+    // whether values of `output` of `from` start a loop
+    let starts_loop = |from: &NodeId, output: &str| {
+        name(from) == Some(FOREACH)
+            || streams
+                .get(from)
+                .is_some_and(|outputs| outputs.iter().any(|o| o == output))
+    };
+    let mut downstream = HashMap::<NodeId, Vec<(NodeId, bool)>>::new();
+    for ((from, output), (to, _)) in &flow.edges {
+        let inner = starts_loop(from, output);
+        downstream.entry(*from).or_default().push((*to, inner));
+    }
+    let next = |id: &NodeId| downstream.get(id).into_iter().flatten().copied();
+
+    let mut loops = HashMap::new();
+    let heads = flow
+        .nodes
+        .iter()
+        .filter(|n| n.command_name == FOREACH || streams.contains_key(&n.id));
+    for head in heads {
+        // depth is the number of nested loops a node is in
+        let mut depths = HashMap::<NodeId, u32>::new();
+        let mut collects = Vec::new();
+        let mut stack = next(&head.id)
+            .filter(|(_, inner)| *inner)
+            .map(|(id, _)| (id, 0))
+            .collect::<Vec<_>>();
+        let mut error = None;
+        while let Some((id, depth)) = stack.pop() {
+            if id == head.id {
+                error = Some(format!("{} node is in a cycle", head.command_name));
+                break;
+            }
+            match depths.insert(id, depth) {
+                Some(d) if d == depth => continue,
+                Some(_) => {
+                    error = Some(format!("node {id} is in different levels of nested loops"));
+                    break;
+                }
+                None => {}
+            }
+            let depth = match name(&id) {
+                Some(COLLECT) if depth == 0 => {
+                    collects.push(id);
+                    continue;
+                }
+                Some(COLLECT) => depth - 1,
+                _ => depth,
+            };
+            stack.extend(next(&id).map(|(id, inner)| (id, depth + u32::from(inner))));
+        }
+        let body = flow
+            .nodes
+            .iter()
+            .map(|n| n.id)
+            .filter(|id| depths.contains_key(id) && !collects.contains(id))
+            .collect();
+        let result = match error {
+            Some(error) => Err(error),
+            None => Ok(Loop { body, collects }),
+        };
+        loops.insert(head.id, result);
+    }
+
+    // loops can only share nodes if one is nested in the other
+    let mut overlaps = Vec::new();
+    for (a, loop_a) in &loops {
+        let Ok(loop_a) = loop_a else { continue };
+        for (b, loop_b) in &loops {
+            let Ok(loop_b) = loop_b else { continue };
+            let shared = loop_a
+                .body
+                .iter()
+                .chain(&loop_a.collects)
+                .any(|id| loop_b.body.contains(id) || loop_b.collects.contains(id));
+            if a != b && shared && !loop_a.body.contains(b) && !loop_b.body.contains(a) {
+                overlaps.push((*a, *b));
+            }
+        }
+    }
+    for (a, b) in overlaps {
+        loops.insert(a, Err(format!("loop overlaps the loop of node {b}")));
+    }
+    loops
+}
+
+pub struct FlowRunner {
+    flow: FlowConfig,
+    user: User,
+    signer: Option<signer::Svc>,
+    get_jwt: Option<get_jwt::Svc>,
+    secrets: Option<secrets::Svc>,
+    get_flow: Option<get_flow::Svc>,
+    run_store: Option<run_store::Svc>,
+    execute: Option<execute::Svc>,
+    extensions: Extensions,
+    cancel: Cancellation,
+    events: Option<mpsc::UnboundedSender<FlowEvent>>,
+    record: record::Mode,
+    breakers: policy::Breakers,
+    /// Services are from a context where `record` is already installed.
+    nested: bool,
+}
+
+impl FlowRunner {
+    pub fn new(flow: FlowConfig) -> Self {
+        Self {
+            flow,
+            user: User::default(),
+            signer: None,
+            get_jwt: None,
+            secrets: None,
+            get_flow: None,
+            run_store: None,
+            execute: None,
+            extensions: Extensions::default(),
+            cancel: Cancellation::default(),
+            events: None,
+            record: record::Mode::Off,
+            breakers: policy::Breakers::default(),
+            nested: false,
+        }
+    }
+
+    pub fn user(mut self, user: User) -> Self {
+        self.user = user;
+        self
+    }
+
+    pub fn signer(mut self, svc: signer::Svc) -> Self {
+        self.signer = Some(svc);
+        self
+    }
+
+    pub fn get_jwt(mut self, svc: get_jwt::Svc) -> Self {
+        self.get_jwt = Some(svc);
+        self
+    }
+
+    /// Read secrets with this service, usually created from server-side config with
+    /// [`secret_store::from_config`][crate::secret_store::from_config]. Flows cannot read
+    /// secrets without it.
+    pub fn secrets(mut self, svc: secrets::Svc) -> Self {
+        self.secrets = Some(svc);
+        self
+    }
+
+    pub fn get_flow(mut self, svc: get_flow::Svc) -> Self {
+        self.get_flow = Some(svc);
+        self
+    }
+
+    /// Load outputs of nodes that are not run by a partial run.
+    pub fn run_store(mut self, svc: run_store::Svc) -> Self {
+        self.run_store = Some(svc);
+        self
+    }
+
+    /// Use this [`execute`] service for all nodes, instead of sending transactions of each node
+    /// with [`execute::simple`].
+    pub fn execute(mut self, svc: execute::Svc) -> Self {
+        self.execute = Some(svc);
+        self
+    }
+
+    pub fn extensions(mut self, extensions: Extensions) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    pub fn cancel(mut self, cancel: Cancellation) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Record requests and results of commands, or replay requests from a log, see [`record`].
+    pub fn record(mut self, mode: record::Mode) -> Self {
+        self.record = mode;
+        self
+    }
+
+    /// Share circuit breakers of nodes with other runs, so that a breaker opened in one run
+    /// stays open in the next ones. By default breakers are only shared within this run.
+    pub fn breakers(mut self, breakers: policy::Breakers) -> Self {
+        self.breakers = breakers;
+        self
+    }
+
+    /// Send [`FlowEvent`]s of the run to this channel, the last event is
+    /// [`FlowEvent::FlowFinish`].
+    pub fn events(mut self, tx: mpsc::UnboundedSender<FlowEvent>) -> Self {
+        self.events = Some(tx);
+        self
+    }
+
+    pub async fn run(self, flow_run_id: FlowRunId, inputs: ValueSet) -> FlowRunResult {
+        // events are discarded when the receiver is dropped
+        let tx = self.events.clone().unwrap_or_else(|| mpsc::unbounded().0);
+        let result = self.run_nodes(flow_run_id, inputs, tx.clone()).await;
+        tx.unbounded_send(FlowEvent::FlowFinish(result.clone()))
+            .ok();
+        result
+    }
+
+    async fn run_nodes(
+        self,
+        flow_run_id: FlowRunId,
+        inputs: ValueSet,
+        tx: mpsc::UnboundedSender<FlowEvent>,
+    ) -> FlowRunResult {
+        let send = |event: FlowEvent| {
+            tx.unbounded_send(event).ok();
+        };
+        send(FlowEvent::FlowStart {
+            flow_run_id,
+            time: SystemTime::now(),
+        });
+
+        let mut ctx = Context::from_cfg(
+            &self.flow.ctx,
+            self.user,
+            self.signer.unwrap_or_else(signer::unimplemented_svc),
+            self.get_jwt.unwrap_or_else(get_jwt::unimplemented_svc),
+            self.extensions,
+        );
+        ctx.cancel = self.cancel;
+        ctx.events = events::Sender::new(NodeEvents(tx.clone()));
+        if let Some(svc) = self.secrets {
+            ctx.secrets = svc;
+        }
+        if let Some(svc) = self.get_flow {
+            ctx.get_flow = svc;
+        }
+        if self.nested {
+            self.record.install_client(&mut ctx);
+        } else {
+            self.record.install(&mut ctx);
+        }
+
+        let streams = self
+            .flow
+            .nodes
+            .iter()
+            .filter_map(|n| {
+                let outputs = build(n).ok()?.stream_outputs();
+                (!outputs.is_empty()).then_some((n.id, outputs))
+            })
+            .collect();
+        let loops = find_loops(&self.flow, &streams);
+        let mut inputs_of = HashMap::<NodeId, Vec<(NodeId, &str, &str)>>::new();
+        for ((from, output), (to, input)) in &self.flow.edges {
+            inputs_of
+                .entry(*to)
+                .or_default()
+                .push((*from, output.as_str(), input.as_str()));
+        }
+        let run = Run {
+            nodes: self.flow.nodes.iter().map(|n| (n.id, n)).collect(),
+            inputs_of,
+            loops,
+            ctx,
+            flow_run_id,
+            call_depth: self.flow.call_depth,
+            flow_inputs: inputs,
+            execute: self.execute,
+            breakers: self.breakers,
+            tx: tx.clone(),
+            result: Mutex::new(FlowRunResult {
+                flow_run_id,
+                output: ValueSet::new(),
+                node_outputs: HashMap::new(),
+                node_errors: HashMap::new(),
+                not_run: Vec::new(),
+                skipped: Vec::new(),
+            }),
+        };
+
+        let mut scope = Scope::new();
+        let mut members = self.flow.nodes.iter().map(|n| n.id).collect::<Vec<_>>();
+        if let Some(partial) = &self.flow.partial_config {
+            let only = partial.only_nodes.iter().collect::<HashSet<_>>();
+            let needed = self
+                .flow
+                .edges
+                .iter()
+                .filter(|((from, _), (to, _))| only.contains(to) && !only.contains(from))
+                .map(|((from, _), _)| *from)
+                .collect::<HashSet<_>>();
+            let store = self.run_store.unwrap_or_else(run_store::unimplemented_svc);
+            let (only, skip) = members.into_iter().partition(|id| only.contains(id));
+            members = only;
+            for node_id in skip {
+                let output = match partial.values_config.flow_run_id(&node_id) {
+                    Some(flow_run_id) => store
+                        .call_ref(run_store::Request {
+                            user_id: run.ctx.user.id,
+                            flow_run_id,
+                            node_id,
+                        })
+                        .await
+                        .map(|resp| resp.output)
+                        .map_err(|e| FlowError::other(e.to_string())),
+                    None => Err(FlowError::other(format!(
+                        "no previous run of node {node_id}"
+                    ))),
+                };
+                let state = match output {
+                    Ok(output) => NodeState::Finished(output),
+                    // only fail if the output is used
+                    Err(error) if needed.contains(&node_id) => {
+                        send(FlowEvent::NodeError {
+                            node_id,
+                            times: 0,
+                            time: SystemTime::now(),
+                            error: error.clone(),
+                        });
+                        NodeState::Failed(error)
+                    }
+                    Err(_) => continue,
+                };
+                run.finish(node_id, &state);
+                scope.insert(node_id, state);
+            }
+        }
+
+        run.run_scope(members, scope, 0).await;
+        run.result.into_inner().unwrap()
+    }
+}
+
+/// A unit of scheduling in a scope.
+enum Unit<'a> {
+    Node(NodeId),
+    /// A [`FOREACH`] or streaming node with its loop.
+    Loop(NodeId, &'a Loop),
+}
+
+impl Unit<'_> {
+    fn nodes(&self) -> Vec<NodeId> {
+        match self {
+            Unit::Node(id) => [*id].to_vec(),
+            Unit::Loop(id, l) => std::iter::once(id)
+                .chain(&l.body)
+                .chain(&l.collects)
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+/// State of a run, shared by all scopes.
+struct Run<'a> {
+    nodes: HashMap<NodeId, &'a NodeConfig>,
+    /// `(from, output, input)` of edges connected to each node.
+    inputs_of: HashMap<NodeId, Vec<(NodeId, &'a str, &'a str)>>,
+    loops: HashMap<NodeId, Result<Loop, String>>,
+    ctx: Context,
+    flow_run_id: FlowRunId,
+    call_depth: u32,
+    flow_inputs: ValueSet,
+    execute: Option<execute::Svc>,
+    breakers: policy::Breakers,
+    tx: mpsc::UnboundedSender<FlowEvent>,
+    result: Mutex<FlowRunResult>,
+}
+
+impl<'a> Run<'a> {
+    fn send(&self, event: FlowEvent) {
+        self.tx.unbounded_send(event).ok();
+    }
+
+    fn send_result(&self, node_id: NodeId, times: u32, output: &Result<ValueSet, FlowError>) {
+        self.send(match output {
+            Ok(output) => FlowEvent::NodeFinish {
+                node_id,
+                times,
+                time: SystemTime::now(),
+                output: output.clone(),
+            },
+            Err(error) => FlowEvent::NodeError {
+                node_id,
+                times,
+                time: SystemTime::now(),
+                error: error.clone(),
+            },
+        });
+    }
+
+    /// Add the final state of a node to the result, outputs of a node in a loop are overwritten
+    /// by later elements.
+    fn finish(&self, node_id: NodeId, state: &NodeState) {
+        let mut result = self.result.lock().unwrap();
+        match state {
+            NodeState::Finished(output) => {
+                if self.nodes[&node_id].command_name == FLOW_OUTPUT {
+                    result.output.extend(output.clone());
+                }
+                result.node_outputs.insert(node_id, output.clone());
+            }
+            NodeState::Failed(error) => {
+                result.node_errors.insert(node_id, error.clone());
+            }
+            NodeState::NotRun if !result.not_run.contains(&node_id) => {
+                result.not_run.push(node_id);
+            }
+            NodeState::Skipped if !result.skipped.contains(&node_id) => {
+                result.skipped.push(node_id);
+            }
+            NodeState::NotRun | NodeState::Skipped => {}
+        }
+    }
+
+    fn upstream(&self, node_id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let edges = self.inputs_of.get(&node_id).into_iter().flatten();
+        edges.map(|(from, _, _)| *from)
+    }
+
+    /// Whether the edge from `output` of `from` can not have a value.
+    fn is_dead(&self, scope: &Scope, from: NodeId, output: &str) -> bool {
+        match scope.get(&from) {
+            Some(NodeState::Skipped) => true,
+            Some(NodeState::Finished(outputs)) => {
+                let branch = self
+                    .nodes
+                    .get(&from)
+                    .is_some_and(|n| matches!(n.command_name.as_str(), IF | SWITCH));
+                branch && !outputs.contains_key(output)
+            }
+            _ => false,
+        }
+    }
+
+    /// Read inputs of a node whose upstream nodes are done, or the state of the node if it should
+    /// not run.
+    fn prepare(&self, scope: &Scope, node_id: NodeId) -> Result<ValueSet, NodeState> {
+        let edges = self.inputs_of.get(&node_id).map(Vec::as_slice);
+        let edges = edges.unwrap_or_default();
+        let failed = edges.iter().any(|(from, _, _)| {
+            matches!(
+                scope.get(from),
+                Some(NodeState::Failed(_) | NodeState::NotRun)
+            )
+        });
+        if failed || self.ctx.check_canceled().is_err() {
+            return Err(NodeState::NotRun);
+        }
+
+        let mut live = HashMap::<&str, bool>::new();
+        let mut input = ValueSet::new();
+        for (from, output, name) in edges {
+            let gate = live.entry(*name).or_default();
+            if self.is_dead(scope, *from, output) {
+                continue;
+            }
+            *gate = true;
+            if let Some(NodeState::Finished(outputs)) = scope.get(from) {
+                if let Some(value) = outputs.get(*output) {
+                    input.insert((*name).to_owned(), value.clone());
+                }
+            }
+        }
+        if live.values().any(|live| !live) {
+            return Err(NodeState::Skipped);
+        }
+        Ok(input)
+    }
+
+    /// Run `members` in `scope` and return the states of all nodes.
+    fn run_scope(
+        &'a self,
+        members: Vec<NodeId>,
+        mut scope: Scope,
+        times: u32,
+    ) -> BoxFuture<'a, Scope> {
+        async move {
+            let valid = |id: &NodeId| match self.loops.get(id) {
+                Some(Ok(l)) if members.contains(id) => Some(l),
+                _ => None,
+            };
+            // loops that are not nested in another loop of this scope
+            let outer = members
+                .iter()
+                .filter_map(|id| Some((*id, valid(id)?)))
+                .filter(|(id, _)| {
+                    !members
+                        .iter()
+                        .filter_map(valid)
+                        .any(|l| l.body.contains(id))
+                })
+                .collect::<Vec<_>>();
+            let mut pending = members
+                .iter()
+                .filter_map(|id| match outer.iter().find(|(f, _)| f == id) {
+                    Some((f, l)) => Some(Unit::Loop(*f, l)),
+                    None if outer
+                        .iter()
+                        .any(|(_, l)| l.body.contains(id) || l.collects.contains(id)) =>
+                    {
+                        None
+                    }
+                    None => Some(Unit::Node(*id)),
+                })
+                .map(|unit| {
+                    let nodes = unit.nodes();
+                    let deps = nodes
+                        .iter()
+                        .flat_map(|id| self.upstream(*id))
+                        .filter(|id| !nodes.contains(id))
+                        .collect::<Vec<_>>();
+                    (unit, deps)
+                })
+                .collect::<Vec<_>>();
+
+            let mut running = FuturesUnordered::<BoxFuture<'a, Vec<(NodeId, NodeState)>>>::new();
+            loop {
+                // marking a node as not run can make its downstream nodes ready
+                let mut changed = true;
+                while changed {
+                    changed = false;
+                    pending.retain(|(unit, deps)| {
+                        if !deps.iter().all(|id| scope.contains_key(id)) {
+                            return true;
+                        }
+                        changed = true;
+                        let (node_id, l) = match unit {
+                            Unit::Node(id) => (*id, None),
+                            Unit::Loop(id, l) => (*id, Some(*l)),
+                        };
+                        match (self.prepare(&scope, node_id), l) {
+                            (Ok(input), None) => running.push(
+                                async move {
+                                    let state = self.run_node(node_id, input, times).await;
+                                    [(node_id, state)].to_vec()
+                                }
+                                .boxed(),
+                            ),
+                            (Ok(input), Some(l)) => {
+                                let scope = scope.clone();
+                                running.push(if self.nodes[&node_id].command_name == FOREACH {
+                                    self.run_loop(node_id, l, input, scope, times)
+                                } else {
+                                    self.run_stream(node_id, l, input, scope, times)
+                                });
+                            }
+                            (Err(state), _) => {
+                                for id in unit.nodes() {
+                                    self.finish(id, &state);
+                                    scope.insert(id, state.clone());
+                                }
+                            }
+                        }
+                        false
+                    });
+                }
+
+                let Some(states) = running.next().await else {
+                    break;
+                };
+                scope.extend(states);
+            }
+
+            // nodes in a cycle are never ready
+            for (unit, _) in pending {
+                for id in unit.nodes() {
+                    self.finish(id, &NodeState::NotRun);
+                    scope.insert(id, NodeState::NotRun);
+                }
+            }
+            scope
+        }
+        .boxed()
+    }
+
+    /// Run the body of a loop for each element, and then its [`COLLECT`] nodes.
+    fn run_loop(
+        &'a self,
+        foreach: NodeId,
+        l: &'a Loop,
+        input: ValueSet,
+        scope: Scope,
+        times: u32,
+    ) -> BoxFuture<'a, Vec<(NodeId, NodeState)>> {
+        async move {
+            let node = self.nodes[&foreach];
+            self.send(FlowEvent::NodeStart {
+                node_id: foreach,
+                times,
+                time: SystemTime::now(),
+                input: input.clone(),
+            });
+            let (elements, concurrency) = match loop_input(node, input) {
+                Ok(input) => input,
+                Err(error) => {
+                    self.send_result(foreach, times, &Err(error.clone()));
+                    let state = LoopState::new(l);
+                    return self.end_loop(foreach, NodeState::Failed(error), l, state, times);
+                }
+            };
+
+            let count = elements.len();
+            let mut last = ValueSet::new();
+            let mut state = LoopState::new(l);
+            {
+                let runs = elements.into_iter().enumerate().map(|(i, element)| {
+                    let output = value::map! { "element" => element };
+                    self.send_result(foreach, i as u32, &Ok(output.clone()));
+                    let mut scope = scope.clone();
+                    scope.insert(foreach, NodeState::Finished(output.clone()));
+                    last = output;
+                    self.run_scope(l.body.clone(), scope, i as u32)
+                });
+                let mut scopes =
+                    futures::stream::iter(runs).buffered(concurrency.unwrap_or(count).max(1));
+                while let Some(scope) = scopes.next().await {
+                    self.merge_element(l, &mut state, &scope);
+                }
+            }
+            self.end_loop(foreach, NodeState::Finished(last), l, state, times)
+        }
+        .boxed()
+    }
+
+    /// Run a streaming node, and the body of its loop for each value it emits while running.
+    fn run_stream(
+        &'a self,
+        node_id: NodeId,
+        l: &'a Loop,
+        input: ValueSet,
+        scope: Scope,
+        times: u32,
+    ) -> BoxFuture<'a, Vec<(NodeId, NodeState)>> {
+        async move {
+            let (stream, rx) = stream::channel(STREAM_CONCURRENCY);
+            let (done, returned) = oneshot::channel();
+            let run = async move {
+                let output = self
+                    .run_command(self.nodes[&node_id], input, times, stream)
+                    .await;
+                done.send(()).ok();
+                output
+            };
+            let scopes = emitted(rx, returned)
+                .enumerate()
+                .map(|(i, output)| {
+                    self.send(FlowEvent::NodeOutput {
+                        node_id,
+                        times,
+                        time: SystemTime::now(),
+                        output: output.clone(),
+                    });
+                    let mut scope = scope.clone();
+                    scope.insert(node_id, NodeState::Finished(output));
+                    self.run_scope(l.body.clone(), scope, i as u32)
+                })
+                .buffered(STREAM_CONCURRENCY)
+                .fold(LoopState::new(l), |mut state, scope| {
+                    self.merge_element(l, &mut state, &scope);
+                    std::future::ready(state)
+                });
+            let (output, state) = futures::join!(run, scopes);
+
+            self.send_result(node_id, times, &output);
+            let head = match output {
+                Ok(output) => NodeState::Finished(output),
+                Err(error) => NodeState::Failed(error),
+            };
+            self.end_loop(node_id, head, l, state, times)
+        }
+        .boxed()
+    }
+
+    /// Merge the states of the nodes of a loop in the scope of one element.
+    fn merge_element(&self, l: &Loop, state: &mut LoopState, scope: &Scope) {
+        state.count += 1;
+        for (id, merged) in &mut state.body {
+            match (&*merged, &scope[id]) {
+                (NodeState::Failed(_), _) => {}
+                (_, s @ NodeState::Failed(_)) | (_, s @ NodeState::NotRun) => *merged = s.clone(),
+                (NodeState::NotRun, _) | (_, NodeState::Skipped) => {}
+                (_, s @ NodeState::Finished(_)) => *merged = s.clone(),
+            }
+        }
+        for id in &l.collects {
+            let Some(Some(array)) = state.arrays.get_mut(id) else {
+                continue;
+            };
+            match self.collect_element(*id, scope) {
+                Ok(Some(value)) => array.push(value),
+                Ok(None) => {}
+                Err(_) => {
+                    state.arrays.insert(*id, None);
+                }
+            }
+        }
+    }
+
+    /// Finish the node that started a loop with `head`, then the body and [`COLLECT`] nodes of
+    /// the loop. Nothing is collected if `head` failed.
+    fn end_loop(
+        &self,
+        head_id: NodeId,
+        head: NodeState,
+        l: &Loop,
+        mut state: LoopState,
+        times: u32,
+    ) -> Vec<(NodeId, NodeState)> {
+        let failed = matches!(head, NodeState::Failed(_));
+        self.finish(head_id, &head);
+        let mut states = [(head_id, head)].to_vec();
+        for id in &l.body {
+            let mut node = state.body.remove(id).unwrap_or(NodeState::Skipped);
+            // nodes that ran are already finished
+            if state.count == 0 {
+                if failed {
+                    node = NodeState::NotRun;
+                }
+                self.finish(*id, &node);
+            }
+            states.push((*id, node));
+        }
+        for id in &l.collects {
+            let node = match state.arrays.remove(id).flatten() {
+                Some(array) if !failed => self.collect(*id, array, times),
+                _ => NodeState::NotRun,
+            };
+            self.finish(*id, &node);
+            states.push((*id, node));
+        }
+        states
+    }
+
+    /// Read the `element` input of a [`COLLECT`] node in the scope of one element, `None` if the
+    /// input is dead, or the state of the node if it should not run.
+    fn collect_element(&self, node_id: NodeId, scope: &Scope) -> Result<Option<Value>, NodeState> {
+        let edges = self.inputs_of.get(&node_id).map(Vec::as_slice);
+        let edges = edges.unwrap_or_default();
+        let mut live = false;
+        let mut value = None;
+        for (from, output, _) in edges {
+            match scope.get(from) {
+                Some(NodeState::Failed(_) | NodeState::NotRun) | None => {
+                    return Err(NodeState::NotRun);
+                }
+                _ if self.is_dead(scope, *from, output) => {}
+                Some(NodeState::Finished(outputs)) => {
+                    live = true;
+                    value = value.or_else(|| outputs.get(*output).cloned());
+                }
+                Some(NodeState::Skipped) => {}
+            }
+        }
+        Ok(live.then(|| value.unwrap_or(Value::Null)))
+    }
+
+    /// Output the collected values of a [`COLLECT`] node.
+    fn collect(&self, node_id: NodeId, array: Vec<Value>, times: u32) -> NodeState {
+        self.send(FlowEvent::NodeStart {
+            node_id,
+            times,
+            time: SystemTime::now(),
+            input: ValueSet::new(),
+        });
+        let output = value::map! { "array" => array };
+        self.send_result(node_id, times, &Ok(output.clone()));
+        NodeState::Finished(output)
+    }
+
+    /// Report values emitted by a node that does not stream to downstream nodes.
+    fn report_emitted(&self, node_id: NodeId, times: u32) -> stream::Svc {
+        let tx = self.tx.clone();
+        let handle = move |req: stream::Request| {
+            let event = FlowEvent::NodeOutput {
+                node_id,
+                times,
+                time: SystemTime::now(),
+                output: req.output,
+            };
+            // emitted values are only reported, the run continues without a receiver
+            tx.unbounded_send(event).ok();
+            std::future::ready(Ok(stream::Response))
+        };
+        stream::Svc::from_service(tower::service_fn(handle), stream::Error::worker, 1)
+    }
+
+    fn node_context(&self, node_id: NodeId, times: u32, stream: stream::Svc) -> Context {
+        let mut ctx = self.ctx.clone();
+        ctx.command = Some(CommandContext {
+            svc: match &self.execute {
+                Some(svc) => svc.clone(),
+                None => execute::simple(&ctx, 1),
+            },
+            stream,
+            flow_run_id: self.flow_run_id,
+            node_id,
+            times,
+            call_depth: self.call_depth,
+        });
+        ctx
+    }
+
+    async fn run_node(&self, node_id: NodeId, input: ValueSet, times: u32) -> NodeState {
+        let node = self.nodes[&node_id];
+        let stream = self.report_emitted(node_id, times);
+        let output = self.run_command(node, input, times, stream).await;
+        self.send_result(node_id, times, &output);
+        let state = match output {
+            Ok(output) => NodeState::Finished(output),
+            Err(error) => NodeState::Failed(error),
+        };
+        self.finish(node_id, &state);
+        state
+    }
+
+    async fn run_command(
+        &self,
+        node: &NodeConfig,
+        input: ValueSet,
+        times: u32,
+        stream: stream::Svc,
+    ) -> Result<ValueSet, FlowError> {
+        let cmd = match node.command_name.as_str() {
+            FLOW_INPUT | FLOW_OUTPUT | CONST | WAIT | IF | SWITCH | FOREACH | COLLECT => None,
+            name => match build(node) {
+                Ok(cmd) => Some(cmd),
+                Err(error) => {
+                    return Err(FlowError::other(format!(
+                        "failed to build {name}: {error:#}"
+                    )))
+                }
+            },
+        };
+
+        let keypairs = cmd
+            .as_deref()
+            .map(record::Keypairs::new)
+            .unwrap_or_default();
+        let input = match &cmd {
+            Some(cmd) => {
+                let mut form = cmd.read_form_data(node.form_data.clone());
+                form.extend(input);
+                form
+            }
+            None => input,
+        };
+        let input = match &self.ctx.record {
+            record::Mode::Replay(replay) => replay.restore_keypairs(&keypairs, input),
+            _ => input,
+        };
+        // keypairs are not sent to clients of the run or recorded
+        let redacted = keypairs.redact_inputs(&input);
+        self.send(FlowEvent::NodeStart {
+            node_id: node.id,
+            times,
+            time: SystemTime::now(),
+            input: redacted.clone(),
+        });
+        let recorder = match &self.ctx.record {
+            record::Mode::Record(recorder) => Some((recorder.clone(), redacted)),
+            _ => None,
+        };
+
+        let output = match (node.command_name.as_str(), cmd) {
+            (FLOW_INPUT, _) => flow_input(node, &self.flow_inputs),
+            (FLOW_OUTPUT, _) => Ok(flow_output(node, input)),
+            (CONST, _) => Ok(const_output(node)),
+            (WAIT, _) => Ok(input),
+            (IF | SWITCH, _) => branch(node, input),
+            (FOREACH, _) => Err(match self.loops.get(&node.id) {
+                Some(Err(error)) => FlowError::other(error.clone()),
+                _ => FlowError::other("foreach node is not run as a loop"),
+            }),
+            (COLLECT, _) => Err(FlowError::other(
+                "collect node is not downstream of a foreach node",
+            )),
+            (_, Some(cmd)) => match command::coerce_inputs(&cmd.inputs(), input) {
+                Err(error) => Err(error),
+                Ok(input) => {
+                    let policy = cmd.policy().merge(node.policy.as_ref());
+                    let passthrough = cmd.passthrough_outputs(&input);
+                    let svc = self.breakers.service(node.id, Arc::from(cmd), &policy);
+                    let ctx = self.node_context(node.id, times, stream);
+                    match svc.oneshot(policy::Request { ctx, inputs: input }).await {
+                        Ok(output) => {
+                            let mut result = passthrough;
+                            result.extend(output);
+                            Ok(result)
+                        }
+                        Err(error) => Err(FlowError::from_command_error(&error)),
+                    }
+                }
+            },
+            (_, None) => unreachable!(),
+        };
+        if let Some((recorder, inputs)) = recorder {
+            recorder.push(Entry::Command {
+                node_id: node.id,
+                times,
+                name: node.command_name.clone(),
+                inputs,
+                result: output
+                    .as_ref()
+                    .map(|output| keypairs.redact_outputs(output))
+                    .map_err(Clone::clone),
+            });
+        }
+        output
+    }
+}
+
+/// Run flow `flow_id` for an `interflow` node of the flow of `ctx`.
+///
+/// The flow is loaded with [`Context::get_flow`] and run with the services of `ctx`, `execute`
+/// replaces the [`execute`] service of its nodes. Fails if a node of the flow failed or was not
+/// run.
+pub async fn run_interflow(
+    ctx: &Context,
+    flow_id: FlowId,
+    inputs: ValueSet,
+    execute: Option<execute::Svc>,
+) -> Result<FlowRunResult, CommandError> {
+    let call_depth = ctx.command.as_ref().map(|c| c.call_depth).unwrap_or(0) + 1;
+    if call_depth > MAX_CALL_DEPTH {
+        let message = format!("maximum call depth of {MAX_CALL_DEPTH} exceeded");
+        return Err(FlowError::other(message).into());
+    }
+
+    let mut config = ctx.get_flow(flow_id).await?;
+    config.call_depth = call_depth;
+    config.partial_config = None;
+    if let Some(origin) = ctx.new_interflow_origin() {
+        config.origin = origin;
+    }
+    let mut runner = FlowRunner::new(FlowConfig::new(config))
+        .user(ctx.user.clone())
+        .signer(ctx.signer.clone())
+        .get_jwt(ctx.get_jwt.clone())
+        .secrets(ctx.secrets.clone())
+        .get_flow(ctx.get_flow.clone())
+        .cancel(ctx.cancel.clone());
+    if let Some(svc) = execute {
+        runner = runner.execute(svc);
+    }
+    runner.record = ctx.record.clone();
+    runner.nested = true;
+
+    let result = runner.run(FlowRunId::new_v4(), inputs).await;
+    if let Some(error) = result.node_errors.values().next() {
+        let error = anyhow::Error::new(error.clone());
+        return Err(error.context(format!("flow {flow_id} failed")));
+    }
+    if !result.not_run.is_empty() {
+        ctx.check_canceled()?;
+        return Err(FlowError::other(format!("flow {flow_id} did not finish")).into());
+    }
+    Ok(result)
+}
+
+fn build(node: &NodeConfig) -> Result<Box<dyn CommandTrait>, CommandError> {
+    let d = inventory::iter::<CommandDescription>()
+        .find(|d| d.name == node.command_name)
+        .ok_or_else(|| anyhow::anyhow!("command not found"))?;
+    (d.fn_new)(&node.client_node_data)
+}
+
+fn label(node: &NodeConfig) -> Option<&str> {
+    node.form_data
+        .get("label")
+        .and_then(|l| l.as_str())
+        .filter(|l| !l.is_empty())
+}
+
+fn flow_input(node: &NodeConfig, flow_inputs: &ValueSet) -> Result<ValueSet, FlowError> {
+    let sources = &node.client_node_data.sources;
+    let name = label(node)
+        .or_else(|| sources.first().map(|s| s.name.as_str()))
+        .unwrap_or_default();
+    let value = flow_inputs
+        .get(name)
+        .ok_or_else(|| FlowError::input_validation(Some(name), "missing flow input"))?;
+    Ok(sources
+        .iter()
+        .map(|s| (s.name.clone(), value.clone()))
+        .collect())
+}
+
+fn flow_output(node: &NodeConfig, input: ValueSet) -> ValueSet {
+    let Some((name, value)) = input.into_iter().next() else {
+        return ValueSet::new();
+    };
+    let label = label(node).map(str::to_owned).unwrap_or(name);
+    [(label, value)].into_iter().collect()
+}
+
+fn const_output(node: &NodeConfig) -> ValueSet {
+    let value = node.form_data.get("value").cloned().unwrap_or_default();
+    let value = Value::from(value);
+    node.client_node_data
+        .sources
+        .iter()
+        .map(|s| (s.name.clone(), value.clone()))
+        .collect()
+}
+
+fn branch(node: &NodeConfig, input: ValueSet) -> Result<ValueSet, FlowError> {
+    let expression = node
+        .form_data
+        .get("expression")
+        .and_then(|e| e.as_str())
+        .ok_or_else(|| FlowError::input_validation(Some("expression"), "missing expression"))?;
+    let result = expr::eval(expression, &input)
+        .map_err(|error| FlowError::input_validation(Some("expression"), error))?;
+    let output = match (node.command_name.as_str(), result) {
+        (IF, Value::Bool(b)) => b.to_string(),
+        (IF, _) => {
+            let error = "expression must be a boolean";
+            return Err(FlowError::input_validation(Some("expression"), error));
+        }
+        (_, Value::String(name)) => name,
+        (_, Value::U64(n)) => n.to_string(),
+        (_, Value::I64(n)) => n.to_string(),
+        (_, Value::Decimal(n)) => n.normalize().to_string(),
+        (_, Value::Bool(b)) => b.to_string(),
+        (_, _) => {
+            let error = "expression must be a string, a number or a boolean";
+            return Err(FlowError::input_validation(Some("expression"), error));
+        }
+    };
+    let sources = &node.client_node_data.sources;
+    let output = if node.command_name == IF || sources.iter().any(|s| s.name == output) {
+        output
+    } else if sources.iter().any(|s| s.name == "default") {
+        "default".to_owned()
+    } else {
+        return Err(FlowError::other(format!("no output named {output}")));
+    };
+    let value = input.get("value").cloned().unwrap_or_default();
+    Ok([(output, value)].into_iter().collect())
+}
+
+/// Read the elements and concurrency of a [`FOREACH`] node.
+fn loop_input(
+    node: &NodeConfig,
+    input: ValueSet,
+) -> Result<(Vec<Value>, Option<usize>), FlowError> {
+    let read = |name: &str| {
+        input
+            .get(name)
+            .cloned()
+            .or_else(|| node.form_data.get(name).cloned().map(Value::from))
+    };
+    let elements = match read("array") {
+        Some(Value::Array(array)) => array,
+        Some(Value::String(json)) => {
+            match serde_json::from_str::<serde_json::Value>(&json).map(Value::from) {
+                Ok(Value::Array(array)) => array,
+                _ => {
+                    let error = "not a JSON array";
+                    return Err(FlowError::input_validation(Some("array"), error));
+                }
+            }
+        }
+        _ => {
+            return Err(FlowError::input_validation(
+                Some("array"),
+                "expected an array",
+            ))
+        }
+    };
+    let concurrency = match read("concurrency") {
+        None | Some(Value::Null) => None,
+        Some(Value::U64(n)) if n > 0 => Some(n as usize),
+        Some(Value::I64(n)) if n > 0 => Some(n as usize),
+        Some(_) => {
+            let error = "must be a positive integer";
+            return Err(FlowError::input_validation(Some("concurrency"), error));
+        }
+    };
+    Ok((elements, concurrency))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command::derive::*,
+        config::client::{self, Extra, NodeData, TargetsForm},
+        CommandType, ContextConfig, Value,
+    };
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use solana_sdk::{
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    };
+
+    #[derive(Deserialize, CommandInputs)]
+    struct Input {
+        a: u64,
+    }
+
+    #[derive(Serialize, CommandOutputs)]
+    struct Output {
+        b: u64,
+    }
+
+    #[command(name = "runner_test_increase")]
+    async fn increase(ctx: Context, input: Input) -> Result<Output, CommandError> {
+        if input.a == 0 {
+            return Err(FlowError::input_validation(Some("a"), "must not be 0").into());
+        }
+        ctx.emit(value::map! { "b" => input.a }).await?;
+        Ok(Output { b: input.a + 1 })
+    }
+
+    #[derive(Deserialize, CommandInputs)]
+    struct KeypairInput {
+        #[serde(with = "value::keypair")]
+        keypair: Keypair,
+    }
+
+    #[derive(Serialize, CommandOutputs)]
+    struct KeypairOutput {
+        #[serde(with = "value::pubkey")]
+        pubkey: Pubkey,
+    }
+
+    const COUNT: &str = r#"{
+        "type": "native",
+        "data": { "node_id": "runner_test_count" },
+        "sources": [
+            { "name": "element", "type": "u64" },
+            { "name": "count", "type": "u64" }
+        ],
+        "targets": [
+            { "name": "n", "type_bounds": ["u64"], "required": true, "passthrough": false }
+        ]
+    }"#;
+
+    #[derive(Deserialize)]
+    struct CountInput {
+        n: u64,
+    }
+
+    #[derive(Serialize)]
+    struct CountOutput {
+        count: u64,
+    }
+
+    inventory::submit!(CommandDescription::new("runner_test_count", |_| {
+        let cmd = command::builder::CmdBuilder::new(COUNT)?
+            .stream_output("element")?
+            .build(|ctx: Context, input: CountInput| async move {
+                for i in 1..=input.n {
+                    ctx.emit(value::map! { "element" => i }).await?;
+                }
+                Ok(CountOutput { count: input.n })
+            });
+        Ok(cmd)
+    }));
+
+    #[command(name = "runner_test_pubkey")]
+    async fn pubkey(_: Context, input: KeypairInput) -> Result<KeypairOutput, CommandError> {
+        Ok(KeypairOutput {
+            pubkey: input.keypair.pubkey(),
+        })
+    }
+
+    fn node(id: u128, name: &str, form_data: serde_json::Value, outputs: &[&str]) -> NodeConfig {
+        NodeConfig {
+            id: NodeId::from_u128(id),
+            command_name: name.to_owned(),
+            form_data: form_data.clone(),
+            client_node_data: NodeData {
+                r#type: CommandType::Native,
+                node_id: name.to_owned(),
+                sources: outputs
+                    .iter()
+                    .map(|name| client::Source {
+                        id: NodeId::new_v4(),
+                        name: (*name).to_owned(),
+                        r#type: crate::ValueType::Free,
+                    })
+                    .collect(),
+                targets: Vec::new(),
+                targets_form: TargetsForm {
+                    form_data,
+                    extra: Extra::default(),
+                    wasm_bytes: None,
+                },
+                policy: None,
+            },
+            policy: None,
+        }
+    }
+
+    fn edge(
+        from: u128,
+        output: &str,
+        to: u128,
+        input: &str,
+    ) -> ((NodeId, String), (NodeId, String)) {
+        (
+            (NodeId::from_u128(from), output.to_owned()),
+            (NodeId::from_u128(to), input.to_owned()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let flow = FlowConfig {
+            id: 0,
+            ctx: ContextConfig::default(),
+            nodes: vec![
+                node(1, FLOW_INPUT, json!({ "label": "x" }), &["x"]),
+                node(2, "runner_test_increase", json!({}), &[]),
+                node(3, "runner_test_increase", json!({}), &[]),
+                node(4, FLOW_OUTPUT, json!({ "label": "result" }), &[]),
+                node(5, "runner_test_increase", json!({ "a": 0 }), &[]),
+                node(6, "runner_test_increase", json!({}), &[]),
+                node(7, "not_registered", json!({}), &[]),
+            ],
+            edges: vec![
+                edge(1, "x", 2, "a"),
+                edge(2, "b", 3, "a"),
+                edge(3, "b", 4, "value"),
+                edge(5, "b", 6, "a"),
+            ],
+            instructions_bundling: <_>::default(),
+            call_depth: 0,
+            partial_config: None,
+        };
+
+        let (tx, rx) = mpsc::unbounded();
+        let id = FlowRunId::new_v4();
+        let result = FlowRunner::new(flow)
+            .events(tx)
+            .run(id, value::map! { "x" => 1u64 })
+            .await;
+
+        assert_eq!(result.output, value::map! { "result" => 3u64 });
+        assert_eq!(
+            result.node_outputs[&NodeId::from_u128(2)]["b"],
+            Value::U64(2)
+        );
+        assert_eq!(
+            result.node_errors[&NodeId::from_u128(5)].code(),
+            crate::ErrorCode::InputValidation
+        );
+        assert!(result.node_errors.contains_key(&NodeId::from_u128(7)));
+        assert_eq!(result.not_run, [NodeId::from_u128(6)]);
+        assert!(!result.is_success());
+
+        let events = rx.collect::<Vec<_>>().await;
+        assert!(matches!(events[0], FlowEvent::FlowStart { flow_run_id, .. } if flow_run_id == id));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            FlowEvent::NodeOutput { node_id, output, .. }
+                if *node_id == NodeId::from_u128(2) && output["b"] == Value::U64(1)
+        )));
+        assert_eq!(events.last(), Some(&FlowEvent::FlowFinish(result)));
+
+        let json = serde_json::to_value(&events[0]).unwrap();
+        assert_eq!(json["event"], "flow_start");
+    }
+
+    #[tokio::test]
+    async fn test_partial_run() {
+        let mut flow = FlowConfig {
+            id: 0,
+            ctx: ContextConfig::default(),
+            nodes: vec![
+                node(1, FLOW_INPUT, json!({ "label": "x" }), &["x"]),
+                node(2, "runner_test_increase", json!({}), &[]),
+                node(3, "runner_test_increase", json!({}), &[]),
+                node(4, FLOW_OUTPUT, json!({ "label": "result" }), &[]),
+            ],
+            edges: vec![
+                edge(1, "x", 2, "a"),
+                edge(2, "b", 3, "a"),
+                edge(3, "b", 4, "value"),
+            ],
+            instructions_bundling: <_>::default(),
+            call_depth: 0,
+            partial_config: None,
+        };
+        let store = run_store::MemoryStore::default();
+        let first = FlowRunner::new(flow.clone())
+            .run(FlowRunId::new_v4(), value::map! { "x" => 1u64 })
+            .await;
+        assert!(first.is_success());
+        store.insert(first.flow_run_id, first.node_outputs.clone());
+
+        // node 2 is loaded, node 1 is not needed
+        flow.partial_config = Some(client::PartialConfig {
+            only_nodes: vec![NodeId::from_u128(3), NodeId::from_u128(4)],
+            values_config: client::ValuesConfig {
+                nodes: [(NodeId::from_u128(1), FlowRunId::new_v4())].into(),
+                default_run_id: Some(first.flow_run_id),
+            },
+        });
+        let result = FlowRunner::new(flow.clone())
+            .run_store(store.svc(1))
+            .run(FlowRunId::new_v4(), ValueSet::new())
+            .await;
+        assert!(result.is_success());
+        assert_eq!(result.output, value::map! { "result" => 3u64 });
+        assert_eq!(
+            result.node_outputs[&NodeId::from_u128(2)],
+            first.node_outputs[&NodeId::from_u128(2)]
+        );
+
+        flow.partial_config
+            .as_mut()
+            .unwrap()
+            .values_config
+            .default_run_id = None;
+        let result = FlowRunner::new(flow)
+            .run_store(store.svc(1))
+            .run(FlowRunId::new_v4(), ValueSet::new())
+            .await;
+        assert!(result.node_errors.contains_key(&NodeId::from_u128(2)));
+        assert_eq!(result.not_run, [NodeId::from_u128(3), NodeId::from_u128(4)]);
+    }
+
+    #[tokio::test]
+    async fn test_record() {
+        let flow = FlowConfig {
+            id: 0,
+            ctx: ContextConfig::default(),
+            nodes: vec![
+                node(1, FLOW_INPUT, json!({ "label": "x" }), &["x"]),
+                node(2, "runner_test_increase", json!({}), &[]),
+            ],
+            edges: vec![edge(1, "x", 2, "a")],
+            instructions_bundling: <_>::default(),
+            call_depth: 0,
+            partial_config: None,
+        };
+        let recorder = record::Recorder::default();
+        FlowRunner::new(flow)
+            .record(record::Mode::Record(recorder.clone()))
+            .run(FlowRunId::new_v4(), value::map! { "x" => 0u64 })
+            .await;
+
+        let log = recorder.log();
+        assert_eq!(log.entries.len(), 2);
+        assert!(log.entries.iter().any(|e| matches!(
+            e,
+            Entry::Command { node_id, inputs, result: Err(_), .. }
+                if *node_id == NodeId::from_u128(2) && inputs["a"] == Value::U64(0)
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_record_keypair() {
+        let keypair = Keypair::new();
+        let pubkey = Value::from(keypair.pubkey());
+        let flow = flow(
+            vec![
+                node(1, FLOW_INPUT, json!({ "label": "k" }), &["k"]),
+                node(2, "runner_test_pubkey", json!({}), &[]),
+                node(
+                    3,
+                    "runner_test_pubkey",
+                    json!({ "keypair": keypair.to_base58_string() }),
+                    &[],
+                ),
+                node(4, FLOW_OUTPUT, json!({ "label": "pubkey" }), &[]),
+            ],
+            vec![edge(1, "k", 2, "keypair"), edge(2, "pubkey", 4, "value")],
+        );
+        let recorder = record::Recorder::default();
+        let (tx, rx) = mpsc::unbounded();
+        let result = FlowRunner::new(flow.clone())
+            .record(record::Mode::Record(recorder.clone()))
+            .events(tx)
+            .run(
+                FlowRunId::new_v4(),
+                value::map! { "k" => Value::from(keypair.insecure_clone()) },
+            )
+            .await;
+        assert!(result.is_success());
+
+        let events = rx.collect::<Vec<_>>().await;
+        for id in [2, 3] {
+            assert!(events.iter().any(|e| matches!(
+                e,
+                FlowEvent::NodeStart { node_id, input, .. }
+                    if *node_id == NodeId::from_u128(id) && input["keypair"] == pubkey
+            )));
+        }
+
+        let json = serde_json::to_string(&recorder.log()).unwrap();
+        assert!(!json.contains(&keypair.to_base58_string()));
+        assert!(!json.contains(&base64::encode(keypair.to_bytes())));
+        let log: record::RunLog = serde_json::from_str(&json).unwrap();
+        for id in [2, 3] {
+            assert!(log.entries.iter().any(|e| matches!(
+                e,
+                Entry::Command { node_id, inputs, .. }
+                    if *node_id == NodeId::from_u128(id) && inputs["keypair"] == pubkey
+            )));
+        }
+
+        // redacted keypairs are replayed with a dummy keypair
+        let replay = record::Replay::new(log);
+        let dummy = replay.dummy_keypair(&keypair.pubkey()).pubkey();
+        let result = FlowRunner::new(flow)
+            .record(record::Mode::Replay(replay))
+            .run(FlowRunId::new_v4(), value::map! { "k" => pubkey })
+            .await;
+        assert!(result.is_success());
+        assert_eq!(result.output, value::map! { "pubkey" => dummy });
+    }
+
+    fn flow(
+        nodes: Vec<NodeConfig>,
+        edges: Vec<((NodeId, String), (NodeId, String))>,
+    ) -> FlowConfig {
+        FlowConfig {
+            id: 0,
+            ctx: ContextConfig::default(),
+            nodes,
+            edges,
+            instructions_bundling: <_>::default(),
+            call_depth: 0,
+            partial_config: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_loop() {
+        let flow = flow(
+            vec![
+                node(1, FLOW_INPUT, json!({ "label": "xs" }), &["xs"]),
+                node(2, FOREACH, json!({ "concurrency": 1 }), &["element"]),
+                node(3, "runner_test_increase", json!({}), &[]),
+                node(4, COLLECT, json!({}), &["array"]),
+                node(5, FLOW_OUTPUT, json!({ "label": "ys" }), &[]),
+            ],
+            vec![
+                edge(1, "xs", 2, "array"),
+                edge(2, "element", 3, "a"),
+                edge(3, "b", 4, "element"),
+                edge(4, "array", 5, "value"),
+            ],
+        );
+
+        let (tx, rx) = mpsc::unbounded();
+        let xs = [1u64, 2, 3].map(Value::from).to_vec();
+        let result = FlowRunner::new(flow.clone())
+            .events(tx)
+            .run(FlowRunId::new_v4(), value::map! { "xs" => xs })
+            .await;
+        assert!(result.is_success());
+        let ys = [2u64, 3, 4].map(Value::from).to_vec();
+        assert_eq!(result.output, value::map! { "ys" => ys });
+        let times = rx
+            .filter_map(|e| async move {
+                match e {
+                    FlowEvent::NodeFinish { node_id, times, .. }
+                        if node_id == NodeId::from_u128(3) =>
+                    {
+                        Some(times)
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(times, [0, 1, 2]);
+
+        // the array can be a JSON string
+        let result = FlowRunner::new(flow.clone())
+            .run(FlowRunId::new_v4(), value::map! { "xs" => "[]" })
+            .await;
+        assert!(result.is_success());
+        assert_eq!(result.output, value::map! { "ys" => Vec::<Value>::new() });
+        assert_eq!(result.skipped, [NodeId::from_u128(3)]);
+
+        let xs = [1u64, 0].map(Value::from).to_vec();
+        let result = FlowRunner::new(flow)
+            .run(FlowRunId::new_v4(), value::map! { "xs" => xs })
+            .await;
+        assert!(result.node_errors.contains_key(&NodeId::from_u128(3)));
+        assert_eq!(result.not_run, [NodeId::from_u128(4), NodeId::from_u128(5)]);
+    }
+
+    #[tokio::test]
+    async fn test_stream() {
+        let flow = flow(
+            vec![
+                node(1, FLOW_INPUT, json!({ "label": "n" }), &["n"]),
+                node(2, "runner_test_count", json!({}), &["element", "count"]),
+                node(3, "runner_test_increase", json!({}), &[]),
+                node(4, COLLECT, json!({}), &["array"]),
+                node(5, FLOW_OUTPUT, json!({ "label": "ys" }), &[]),
+                node(6, FLOW_OUTPUT, json!({ "label": "count" }), &[]),
+            ],
+            vec![
+                edge(1, "n", 2, "n"),
+                edge(2, "element", 3, "a"),
+                edge(3, "b", 4, "element"),
+                edge(4, "array", 5, "value"),
+                edge(2, "count", 6, "value"),
+            ],
+        );
+
+        let (tx, rx) = mpsc::unbounded();
+        let result = FlowRunner::new(flow.clone())
+            .events(tx)
+            .run(FlowRunId::new_v4(), value::map! { "n" => 3u64 })
+            .await;
+        assert!(result.is_success());
+        let ys = [2u64, 3, 4].map(Value::from).to_vec();
+        assert_eq!(result.output, value::map! { "ys" => ys, "count" => 3u64 });
+        let times = rx
+            .filter_map(|e| async move {
+                match e {
+                    FlowEvent::NodeFinish { node_id, times, .. }
+                        if node_id == NodeId::from_u128(3) =>
+                    {
+                        Some(times)
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<HashSet<_>>()
+            .await;
+        assert_eq!(times, HashSet::from([0, 1, 2]));
+
+        // more values than run at the same time, in the order they were emitted
+        let n = 3 * STREAM_CONCURRENCY as u64;
+        let result = FlowRunner::new(flow.clone())
+            .run(FlowRunId::new_v4(), value::map! { "n" => n })
+            .await;
+        let ys = (2..n + 2).map(Value::from).collect::<Vec<_>>();
+        assert_eq!(result.output["ys"], Value::Array(ys));
+
+        let result = FlowRunner::new(flow)
+            .run(FlowRunId::new_v4(), value::map! { "n" => 0u64 })
+            .await;
+        assert!(result.is_success());
+        assert_eq!(result.output["ys"], Value::Array(Vec::new()));
+        assert_eq!(result.skipped, [NodeId::from_u128(3)]);
+    }
+
+    #[tokio::test]
+    async fn test_branch() {
+        let flow = flow(
+            vec![
+                node(1, FLOW_INPUT, json!({ "label": "x" }), &["x"]),
+                node(
+                    2,
+                    IF,
+                    json!({ "expression": "value > 1" }),
+                    &["true", "false"],
+                ),
+                node(3, "runner_test_increase", json!({}), &[]),
+                node(4, WAIT, json!({}), &[]),
+                node(5, FLOW_OUTPUT, json!({ "label": "out" }), &[]),
+                node(6, "runner_test_increase", json!({}), &[]),
+                node(7, CONST, json!({ "value": "[1, 2, 3, 4]" }), &["Source"]),
+                node(8, FOREACH, json!({}), &["element"]),
+                node(
+                    9,
+                    SWITCH,
+                    json!({ "expression": "value % 2" }),
+                    &["0", "default"],
+                ),
+                node(10, COLLECT, json!({}), &["array"]),
+                node(11, FLOW_OUTPUT, json!({ "label": "even" }), &[]),
+            ],
+            vec![
+                edge(1, "x", 2, "value"),
+                edge(2, "true", 3, "a"),
+                edge(2, "false", 4, "value"),
+                edge(1, "x", 4, "wait_for"),
+                // merge of both branches
+                edge(3, "b", 5, "value"),
+                edge(4, "value", 5, "value"),
+                edge(3, "b", 6, "a"),
+                edge(7, "Source", 8, "array"),
+                edge(8, "element", 9, "value"),
+                edge(9, "0", 10, "element"),
+                edge(10, "array", 11, "value"),
+            ],
+        );
+
+        let result = FlowRunner::new(flow.clone())
+            .run(FlowRunId::new_v4(), value::map! { "x" => 5u64 })
+            .await;
+        assert!(result.is_success());
+        assert_eq!(result.output["out"], Value::U64(6));
+        assert_eq!(
+            result.output["even"],
+            Value::from([2u64, 4].map(Value::from).to_vec())
+        );
+        assert_eq!(result.skipped, [NodeId::from_u128(4)]);
+
+        let result = FlowRunner::new(flow.clone())
+            .run(FlowRunId::new_v4(), value::map! { "x" => 1u64 })
+            .await;
+        assert!(result.is_success());
+        assert_eq!(result.output["out"], Value::U64(1));
+        assert_eq!(result.skipped, [NodeId::from_u128(3), NodeId::from_u128(6)]);
+
+        let result = FlowRunner::new(flow)
+            .run(FlowRunId::new_v4(), value::map! { "x" => "a" })
+            .await;
+        assert!(result.node_errors.contains_key(&NodeId::from_u128(2)));
+    }
+
+    #[tokio::test]
+    async fn test_interflow() {
+        let sub_flow = json!({
+            "id": 2,
+            "nodes": [
+                {
+                    "id": "00000000-0000-0000-0000-000000000001",
+                    "data": {
+                        "type": "native",
+                        "node_id": FLOW_INPUT,
+                        "sources": [{ "id": "00000000-0000-0000-0000-0000000000a1", "name": "a", "type": "free" }],
+                        "targets": [],
+                        "targets_form": { "form_data": { "label": "a" } }
+                    }
+                },
+                {
+                    "id": "00000000-0000-0000-0000-000000000002",
+                    "data": {
+                        "type": "native",
+                        "node_id": "runner_test_increase",
+                        "sources": [{ "id": "00000000-0000-0000-0000-0000000000b1", "name": "b", "type": "u64" }],
+                        "targets": [{ "id": "00000000-0000-0000-0000-0000000000b2", "name": "a", "type_bounds": ["u64"] }],
+                        "targets_form": { "form_data": {} }
+                    }
+                },
+                {
+                    "id": "00000000-0000-0000-0000-000000000003",
+                    "data": {
+                        "type": "native",
+                        "node_id": FLOW_OUTPUT,
+                        "sources": [],
+                        "targets": [{ "id": "00000000-0000-0000-0000-0000000000c1", "name": "value", "type_bounds": ["free"] }],
+                        "targets_form": { "form_data": { "label": "b" } }
+                    }
+                }
+            ],
+            "edges": [
+                {
+                    "source": "00000000-0000-0000-0000-000000000001",
+                    "sourceHandle": "00000000-0000-0000-0000-0000000000a1",
+                    "target": "00000000-0000-0000-0000-000000000002",
+                    "targetHandle": "00000000-0000-0000-0000-0000000000b2"
+                },
+                {
+                    "source": "00000000-0000-0000-0000-000000000002",
+                    "sourceHandle": "00000000-0000-0000-0000-0000000000b1",
+                    "target": "00000000-0000-0000-0000-000000000003",
+                    "targetHandle": "00000000-0000-0000-0000-0000000000c1"
+                }
+            ]
+        });
+        let handle = move |req: get_flow::Request| {
+            let result = match req.flow_id {
+                2 => Ok(get_flow::Response {
+                    config: serde_json::from_value(sub_flow.clone()).unwrap(),
+                }),
+                id => Err(get_flow::Error::NotFound(id)),
+            };
+            std::future::ready(result)
+        };
+        let mut ctx = Context {
+            get_flow: get_flow::Svc::from_service(
+                tower::service_fn(handle),
+                get_flow::Error::worker,
+                1,
+            ),
+            ..Context::default()
+        };
+
+        let result = run_interflow(&ctx, 2, value::map! { "a" => 1u64 }, None)
+            .await
+            .unwrap();
+        assert_eq!(result.output, value::map! { "b" => 2u64 });
+
+        let error = run_interflow(&ctx, 2, value::map! { "a" => 0u64 }, None)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            FlowError::from_command_error(&error).code(),
+            crate::ErrorCode::InputValidation
+        );
+
+        assert!(run_interflow(&ctx, 3, ValueSet::new(), None).await.is_err());
+
+        ctx.command.as_mut().unwrap().call_depth = MAX_CALL_DEPTH;
+        let error = run_interflow(&ctx, 2, value::map! { "a" => 1u64 }, None)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("maximum call depth"));
+    }
+}
@@ -0,0 +1,445 @@
+//! Expressions of [`IF`][super::IF] and [`SWITCH`][super::SWITCH] nodes.
+//!
+//! ```text
+//! value.amount > 100 && value.kind == "transfer"
+//! value[0] * 2 + offset
+//! ```
+//!
+//! - Identifiers are inputs of the node, `.name` and `[index]` read fields and elements.
+//! - Literals: numbers, strings in `"` or `'`, `true`, `false` and `null`.
+//! - Operators from the lowest precedence: `||`, `&&`, comparisons (`==`, `!=`, `<`, `<=`, `>`,
+//!   `>=`), `+ -`, `* / %`, and unary `!` and `-`.
+//!
+//! Numbers are compared and computed as [`Decimal`]. `&&`, `||` and `!` only accept booleans.
+//! Expressions can be nested at most [`MAX_DEPTH`] deep, counting operators and parentheses.
+
+use crate::ValueSet;
+use value::{Decimal, Value};
+
+/// Maximum nesting of expressions, deeper expressions would overflow the stack.
+pub const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(Decimal),
+    String(String),
+    Ident(String),
+    Op(&'static str),
+}
+
+const OPS: &[&str] = &[
+    "||", "&&", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "!", "(", ")", "[", "]",
+    ".",
+];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if c.is_ascii_digit() {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '_'))
+                .unwrap_or(rest.len());
+            let number = rest[..end].replace('_', "");
+            let number = number
+                .parse()
+                .map_err(|_| format!("invalid number: {}", &rest[..end]))?;
+            tokens.push(Token::Number(number));
+            rest = &rest[end..];
+        } else if c == '"' || c == '\'' {
+            let mut s = String::new();
+            let mut chars = rest[1..].char_indices();
+            let end = loop {
+                match chars.next() {
+                    Some((i, q)) if q == c => break i + 2,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, 'n')) => s.push('\n'),
+                        Some((_, 't')) => s.push('\t'),
+                        Some((_, e)) => s.push(e),
+                        None => return Err("unterminated string".to_owned()),
+                    },
+                    Some((_, ch)) => s.push(ch),
+                    None => return Err("unterminated string".to_owned()),
+                }
+            };
+            tokens.push(Token::String(s));
+            rest = &rest[end..];
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..end].to_owned()));
+            rest = &rest[end..];
+        } else {
+            let op = OPS
+                .iter()
+                .find(|op| rest.starts_with(*op))
+                .ok_or_else(|| format!("unexpected character: {c}"))?;
+            tokens.push(Token::Op(op));
+            rest = &rest[op.len()..];
+        }
+    }
+    Ok(tokens)
+}
+
+enum Expr {
+    Literal(Value),
+    Var(String),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Index(Box<Expr>, Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek_op(&self, ops: &[&'static str]) -> Option<&'static str> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) if ops.contains(op) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn eat(&mut self, ops: &[&'static str]) -> Option<&'static str> {
+        let op = self.peek_op(ops)?;
+        self.pos += 1;
+        Some(op)
+    }
+
+    fn expect(&mut self, op: &'static str) -> Result<(), String> {
+        self.eat(&[op])
+            .map(|_| ())
+            .ok_or_else(|| format!("expected {op}"))
+    }
+
+    /// Go one level deeper, the depth is restored by the caller.
+    fn enter(&mut self) -> Result<(), String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!("expression is nested deeper than {MAX_DEPTH}"));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Parse left-associative binary operators of one precedence level.
+    fn binary(
+        &mut self,
+        ops: &[&'static str],
+        next: fn(&mut Self) -> Result<Expr, String>,
+    ) -> Result<Expr, String> {
+        let depth = self.depth;
+        let mut expr = next(self)?;
+        while let Some(op) = self.eat(ops) {
+            // each operator nests the expression on its left
+            self.enter()?;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(next(self)?));
+        }
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        self.binary(&["||"], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        self.binary(&["&&"], Self::cmp)
+    }
+
+    fn cmp(&mut self) -> Result<Expr, String> {
+        let lhs = self.sum()?;
+        match self.eat(&["==", "!=", "<", "<=", ">", ">="]) {
+            Some(op) => {
+                self.enter()?;
+                let rhs = self.sum()?;
+                self.depth -= 1;
+                Ok(Expr::Binary(op, Box::new(lhs), Box::new(rhs)))
+            }
+            None => Ok(lhs),
+        }
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        self.binary(&["+", "-"], Self::product)
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        self.binary(&["*", "/", "%"], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        let expr: fn(Box<Expr>) -> Expr = if self.eat(&["!"]).is_some() {
+            Expr::Not
+        } else if self.eat(&["-"]).is_some() {
+            Expr::Neg
+        } else {
+            return self.postfix();
+        };
+        self.enter()?;
+        let operand = self.unary()?;
+        self.depth -= 1;
+        Ok(expr(Box::new(operand)))
+    }
+
+    fn postfix(&mut self) -> Result<Expr, String> {
+        let depth = self.depth;
+        let mut expr = self.primary()?;
+        loop {
+            let key = if self.eat(&["."]).is_some() {
+                self.enter()?;
+                let key = match self.tokens.get(self.pos) {
+                    Some(Token::Ident(name)) => Value::String(name.clone()),
+                    Some(Token::Number(n)) if n.scale() == 0 => Value::Decimal(*n),
+                    _ => return Err("expected a field name after .".to_owned()),
+                };
+                self.pos += 1;
+                Expr::Literal(key)
+            } else if self.eat(&["["]).is_some() {
+                self.enter()?;
+                let key = self.or()?;
+                self.expect("]")?;
+                key
+            } else {
+                self.depth = depth;
+                return Ok(expr);
+            };
+            expr = Expr::Index(Box::new(expr), Box::new(key));
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| "unexpected end of expression".to_owned())?;
+        self.pos += 1;
+        match token {
+            Token::Number(n) => Ok(Expr::Literal(Value::Decimal(n))),
+            Token::String(s) => Ok(Expr::Literal(Value::String(s))),
+            Token::Ident(name) => Ok(match name.as_str() {
+                "true" => Expr::Literal(Value::Bool(true)),
+                "false" => Expr::Literal(Value::Bool(false)),
+                "null" => Expr::Literal(Value::Null),
+                _ => Expr::Var(name),
+            }),
+            Token::Op("(") => {
+                self.enter()?;
+                let expr = self.or()?;
+                self.expect(")")?;
+                self.depth -= 1;
+                Ok(expr)
+            }
+            Token::Op(op) => Err(format!("unexpected {op}")),
+        }
+    }
+}
+
+fn number(value: &Value) -> Option<Decimal> {
+    match value {
+        Value::U64(x) => Some((*x).into()),
+        Value::I64(x) => Some((*x).into()),
+        Value::U128(x) => Decimal::try_from_i128_with_scale(i128::try_from(*x).ok()?, 0).ok(),
+        Value::I128(x) => Decimal::try_from_i128_with_scale(*x, 0).ok(),
+        Value::F64(x) => Decimal::from_f64_retain(*x),
+        Value::Decimal(x) => Some(*x),
+        _ => None,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::String(_) => "string",
+        Value::Bool(_) => "boolean",
+        Value::Array(_) => "array",
+        Value::Map(_) => "map",
+        v if number(v).is_some() => "number",
+        _ => "bytes",
+    }
+}
+
+fn equals(a: &Value, b: &Value) -> bool {
+    match (number(a), number(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn arithmetic(op: &str, a: &Value, b: &Value) -> Result<Value, String> {
+    let (x, y) = match (number(a), number(b)) {
+        (Some(x), Some(y)) => (x, y),
+        _ => {
+            return Err(format!(
+                "can't apply {op} to {} and {}",
+                type_name(a),
+                type_name(b)
+            ))
+        }
+    };
+    let result = match op {
+        "+" => x.checked_add(y),
+        "-" => x.checked_sub(y),
+        "*" => x.checked_mul(y),
+        "/" => x.checked_div(y),
+        _ => x.checked_rem(y),
+    };
+    result
+        .map(Value::Decimal)
+        .ok_or_else(|| format!("arithmetic overflow or division by zero in {op}"))
+}
+
+impl Expr {
+    fn boolean(&self, vars: &ValueSet) -> Result<bool, String> {
+        match self.eval(vars)? {
+            Value::Bool(b) => Ok(b),
+            other => Err(format!("expected a boolean, got {}", type_name(&other))),
+        }
+    }
+
+    fn eval(&self, vars: &ValueSet) -> Result<Value, String> {
+        Ok(match self {
+            Expr::Literal(value) => value.clone(),
+            Expr::Var(name) => vars
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("unknown input: {name}"))?,
+            Expr::Not(expr) => Value::Bool(!expr.boolean(vars)?),
+            Expr::Neg(expr) => {
+                let value = expr.eval(vars)?;
+                let x =
+                    number(&value).ok_or_else(|| format!("can't negate {}", type_name(&value)))?;
+                Value::Decimal(-x)
+            }
+            Expr::Index(expr, key) => match (expr.eval(vars)?, key.eval(vars)?) {
+                (Value::Map(map), Value::String(name)) => {
+                    map.get(&name).cloned().unwrap_or(Value::Null)
+                }
+                (Value::Array(array), key) => {
+                    let index = number(&key)
+                        .filter(|n| n.scale() == 0)
+                        .and_then(|n| usize::try_from(n.mantissa()).ok())
+                        .ok_or_else(|| "array index must be a non-negative integer".to_owned())?;
+                    array.get(index).cloned().unwrap_or(Value::Null)
+                }
+                (Value::Null, _) => Value::Null,
+                (value, _) => return Err(format!("can't index {}", type_name(&value))),
+            },
+            // short-circuit
+            Expr::Binary("||", lhs, rhs) => Value::Bool(lhs.boolean(vars)? || rhs.boolean(vars)?),
+            Expr::Binary("&&", lhs, rhs) => Value::Bool(lhs.boolean(vars)? && rhs.boolean(vars)?),
+            Expr::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(vars)?, rhs.eval(vars)?);
+                match *op {
+                    "==" => Value::Bool(equals(&lhs, &rhs)),
+                    "!=" => Value::Bool(!equals(&lhs, &rhs)),
+                    "<" | "<=" | ">" | ">=" => {
+                        let ordering = match (number(&lhs), number(&rhs), &lhs, &rhs) {
+                            (Some(a), Some(b), _, _) => a.cmp(&b),
+                            (_, _, Value::String(a), Value::String(b)) => a.cmp(b),
+                            _ => {
+                                return Err(format!(
+                                    "can't compare {} with {}",
+                                    type_name(&lhs),
+                                    type_name(&rhs)
+                                ))
+                            }
+                        };
+                        Value::Bool(match *op {
+                            "<" => ordering.is_lt(),
+                            "<=" => ordering.is_le(),
+                            ">" => ordering.is_gt(),
+                            _ => ordering.is_ge(),
+                        })
+                    }
+                    "+" => match (lhs, rhs) {
+                        (Value::String(a), Value::String(b)) => Value::String(a + &b),
+                        (a, b) => arithmetic(op, &a, &b)?,
+                    },
+                    _ => arithmetic(op, &lhs, &rhs)?,
+                }
+            }
+        })
+    }
+}
+
+/// Evaluate `text` with `vars` as variables.
+pub fn eval(text: &str, vars: &ValueSet) -> Result<Value, String> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+        depth: 0,
+    };
+    let expr = parser.or()?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(format!("unexpected {token:?}"));
+    }
+    expr.eval(vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval() {
+        let vars = value::map! {
+            "value" => value::map! {
+                "amount" => 150u64,
+                "kind" => "transfer",
+                "list" => vec![Value::from(1u64), Value::from("a")],
+            },
+            "limit" => 100i64,
+        };
+        let t = |text: &str| eval(text, &vars);
+        assert_eq!(t("value.amount > limit").unwrap(), Value::Bool(true));
+        assert_eq!(
+            t("value.amount >= 150 && value.kind == 'transfer'").unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            t("!(value.list[0] == 1) || false").unwrap(),
+            Value::Bool(false)
+        );
+        assert_eq!(
+            t(r#"value["kind"] + "!""#).unwrap(),
+            Value::from("transfer!")
+        );
+        assert_eq!(t("value.list.1").unwrap(), Value::from("a"));
+        assert_eq!(t("value.missing").unwrap(), Value::Null);
+        assert_eq!(
+            t("(value.amount - 50) * 2 % 7").unwrap(),
+            Value::Decimal(Decimal::from(4))
+        );
+        assert_eq!(t("1 + 2 * 3 == 7").unwrap(), Value::Bool(true));
+        assert_eq!(t("-1 < 0.5").unwrap(), Value::Bool(true));
+        // `value.x` is not evaluated
+        assert_eq!(t("false && value.x.y > 1").unwrap(), Value::Bool(false));
+
+        assert!(t("unknown").is_err());
+        assert!(t("value.kind && true").is_err());
+        assert!(t("1 / 0").is_err());
+        assert!(t("1 +").is_err());
+        assert!(t("(1").is_err());
+        assert!(t("1 2").is_err());
+        assert!(t("'abc").is_err());
+
+        let deep = |n: usize, open: &str, close: &str| open.repeat(n) + "1" + &close.repeat(n);
+        assert_eq!(
+            t(&deep(MAX_DEPTH, "(", ")")).unwrap(),
+            Value::Decimal(Decimal::ONE)
+        );
+        assert!(t(&deep(MAX_DEPTH + 1, "(", ")")).is_err());
+        assert!(t(&deep(100_000, "(", ")")).is_err());
+        assert!(t(&deep(100_000, "-", "")).is_err());
+        assert!(t(&deep(100_000, "value[", "]")).is_err());
+        assert!(t(&deep(100_000, "", " + 1")).is_err());
+    }
+}
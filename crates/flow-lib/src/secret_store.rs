@@ -0,0 +1,372 @@
+//! Backends for the [`secrets`] service.
+//!
+//! Use [`from_config`] to create a service from server-side [`SecretsConfig`]:
+//! - [`EnvStore`]: environment variables.
+//! - [`FileStore`]: a local file encrypted with AES-256-GCM-SIV.
+//! - [`VaultStore`]: a Vault-style KV version 2 HTTP API.
+
+use crate::{
+    config::{SecretsBackend, SecretsConfig},
+    context::secrets::{self, Error, Secret},
+    NodeId,
+};
+use aes_gcm_siv::{
+    aead::{Aead, NewAead},
+    Aes256GcmSiv, Nonce,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+/// Create a [`secrets::Svc`] from config, nodes can only read secrets granted to them in
+/// [`SecretsConfig::grants`].
+pub fn from_config(config: &SecretsConfig) -> secrets::Svc {
+    match store(&config.backend) {
+        Some(store) => service(Granted {
+            store,
+            config: config.clone(),
+        }),
+        None => secrets::unimplemented_svc(),
+    }
+}
+
+/// Create a [`secrets::Svc`] from a backend, without checking grants.
+///
+/// Only use this when the caller chose the command that will read secrets,
+/// such as a CLI running a single command.
+pub fn from_backend(backend: &SecretsBackend) -> secrets::Svc {
+    match store(backend) {
+        Some(store) => service(store),
+        None => secrets::unimplemented_svc(),
+    }
+}
+
+fn store(backend: &SecretsBackend) -> Option<Box<dyn SecretStore>> {
+    Some(match backend {
+        SecretsBackend::None => return None,
+        SecretsBackend::Env { prefix } => Box::new(EnvStore {
+            prefix: prefix.clone(),
+        }),
+        SecretsBackend::File { path, key_env } => Box::new(FileStore {
+            path: path.clone(),
+            key_env: key_env.clone(),
+        }),
+        SecretsBackend::Vault {
+            url,
+            mount,
+            path,
+            token_env,
+        } => Box::new(VaultStore {
+            http: reqwest::Client::new(),
+            url: url.clone(),
+            mount: mount.clone(),
+            path: path.clone(),
+            token_env: token_env.clone(),
+        }),
+    })
+}
+
+/// A source of secrets.
+#[async_trait::async_trait]
+pub trait SecretStore: Send + Sync + 'static {
+    async fn get(&self, name: &str) -> Result<Secret, Error>;
+
+    /// Get secret `name` for node `node_id`, stores that don't check grants ignore `node_id`.
+    async fn get_for(&self, node_id: Option<NodeId>, name: &str) -> Result<Secret, Error> {
+        let _ = node_id;
+        self.get(name).await
+    }
+}
+
+#[async_trait::async_trait]
+impl SecretStore for Box<dyn SecretStore> {
+    async fn get(&self, name: &str) -> Result<Secret, Error> {
+        (**self).get(name).await
+    }
+
+    async fn get_for(&self, node_id: Option<NodeId>, name: &str) -> Result<Secret, Error> {
+        (**self).get_for(node_id, name).await
+    }
+}
+
+/// Wrap a [`SecretStore`] in a [`secrets::Svc`].
+pub fn service<S: SecretStore>(store: S) -> secrets::Svc {
+    let store = Arc::new(store);
+    let handle = move |req: secrets::Request| {
+        let store = store.clone();
+        async move {
+            Ok(secrets::Response {
+                value: store.get_for(req.node_id, &req.name).await?,
+            })
+        }
+    };
+    secrets::Svc::from_service(tower::service_fn(handle), Error::worker, 32)
+}
+
+/// Only return secrets that [`SecretsConfig::grants`] allows the requesting node to read.
+struct Granted {
+    store: Box<dyn SecretStore>,
+    config: SecretsConfig,
+}
+
+#[async_trait::async_trait]
+impl SecretStore for Granted {
+    async fn get(&self, name: &str) -> Result<Secret, Error> {
+        self.get_for(None, name).await
+    }
+
+    async fn get_for(&self, node_id: Option<NodeId>, name: &str) -> Result<Secret, Error> {
+        if !self.config.is_granted(node_id, name) {
+            return Err(Error::NotAllowed(name.to_owned()));
+        }
+        self.store.get(name).await
+    }
+}
+
+fn env_var(name: &str) -> Result<String, Error> {
+    std::env::var(name).map_err(|_| Error::other(format!("environment variable not set: {name}")))
+}
+
+/// Read secret `NAME` from environment variable `{prefix}NAME`.
+pub struct EnvStore {
+    pub prefix: String,
+}
+
+#[async_trait::async_trait]
+impl SecretStore for EnvStore {
+    async fn get(&self, name: &str) -> Result<Secret, Error> {
+        std::env::var(format!("{}{}", self.prefix, name))
+            .map(Secret::new)
+            .map_err(|_| Error::NotFound(name.to_owned()))
+    }
+}
+
+/// Content of an encrypted secrets file, the plaintext is a JSON object of names and values.
+#[derive(Serialize, Deserialize)]
+pub struct EncryptedFile {
+    /// Base64-encoded 12 bytes nonce.
+    pub nonce: String,
+    /// Base64-encoded ciphertext.
+    pub ciphertext: String,
+}
+
+/// Read secrets from a file encrypted with [`FileStore::seal`].
+///
+/// The file is read on every request, so that it can be updated without restarting.
+pub struct FileStore {
+    pub path: PathBuf,
+    /// Name of the environment variable containing the base64-encoded 32 bytes key.
+    pub key_env: String,
+}
+
+impl FileStore {
+    fn cipher(key: &[u8]) -> Result<Aes256GcmSiv, Error> {
+        Aes256GcmSiv::new_from_slice(key).map_err(|_| Error::other("key must be 32 bytes"))
+    }
+
+    /// Encrypt `secrets` with `key`, the result can be written to a file as JSON.
+    pub fn seal(secrets: &HashMap<String, String>, key: &[u8]) -> Result<EncryptedFile, Error> {
+        let cipher = Self::cipher(key)?;
+        let nonce = rand::random::<[u8; 12]>();
+        let plaintext = serde_json::to_vec(secrets).map_err(Error::other)?;
+        let ciphertext = cipher
+            .encrypt(&Nonce::from(nonce), plaintext.as_ref())
+            .map_err(|_| Error::other("encryption failed"))?;
+        Ok(EncryptedFile {
+            nonce: base64::encode(nonce),
+            ciphertext: base64::encode(ciphertext),
+        })
+    }
+
+    /// Decrypt a file created by [`FileStore::seal`].
+    pub fn open(file: &EncryptedFile, key: &[u8]) -> Result<HashMap<String, String>, Error> {
+        let cipher = Self::cipher(key)?;
+        let nonce = <[u8; 12]>::try_from(base64::decode(&file.nonce).map_err(Error::other)?)
+            .map_err(|_| Error::other("nonce must be 12 bytes"))?;
+        let ciphertext = base64::decode(&file.ciphertext).map_err(Error::other)?;
+        let plaintext = cipher
+            .decrypt(&Nonce::from(nonce), ciphertext.as_ref())
+            .map_err(|_| Error::other("decryption failed, wrong key or corrupted file"))?;
+        serde_json::from_slice(&plaintext).map_err(Error::other)
+    }
+}
+
+#[async_trait::async_trait]
+impl SecretStore for FileStore {
+    async fn get(&self, name: &str) -> Result<Secret, Error> {
+        let key = base64::decode(env_var(&self.key_env)?).map_err(Error::other)?;
+        let content = tokio::fs::read(&self.path).await.map_err(Error::other)?;
+        let file = serde_json::from_slice::<EncryptedFile>(&content).map_err(Error::other)?;
+        Self::open(&file, &key)?
+            .remove(name)
+            .map(Secret::new)
+            .ok_or_else(|| Error::NotFound(name.to_owned()))
+    }
+}
+
+/// Read secrets from a Vault-style KV version 2 API.
+pub struct VaultStore {
+    pub http: reqwest::Client,
+    pub url: String,
+    pub mount: String,
+    pub path: String,
+    /// Name of the environment variable containing the access token.
+    pub token_env: String,
+}
+
+#[derive(Deserialize)]
+struct VaultResponse {
+    data: VaultData,
+}
+
+#[derive(Deserialize)]
+struct VaultData {
+    data: HashMap<String, String>,
+}
+
+#[async_trait::async_trait]
+impl SecretStore for VaultStore {
+    async fn get(&self, name: &str) -> Result<Secret, Error> {
+        let token = env_var(&self.token_env)?;
+        let url = format!(
+            "{}/v1/{}/data/{}",
+            self.url.trim_end_matches('/'),
+            self.mount,
+            self.path
+        );
+        let resp = self
+            .http
+            .get(url)
+            .header("X-Vault-Token", token)
+            .send()
+            .await
+            .map_err(Error::other)?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(Error::other(format!(
+                "vault returned status code: {status}"
+            )));
+        }
+        let body = resp.bytes().await.map_err(Error::other)?;
+        serde_json::from_slice::<VaultResponse>(&body)
+            .map_err(Error::other)?
+            .data
+            .data
+            .remove(name)
+            .map(Secret::new)
+            .ok_or_else(|| Error::NotFound(name.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::CommandContext, Context};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn test_debug_redacted() {
+        let s = Secret::new("hunter2".to_owned());
+        assert!(!format!("{:?}", s).contains("hunter2"));
+        let resp = secrets::Response { value: s };
+        assert!(!format!("{:?}", resp).contains("hunter2"));
+    }
+
+    #[test]
+    fn test_expand() {
+        let text = "Bearer {{secrets.TOKEN}} {{secrets.OTHER }}";
+        assert_eq!(secrets::references(text).unwrap(), ["TOKEN", "OTHER"]);
+        let result = secrets::expand(text, |name| Some(name.to_lowercase())).unwrap();
+        assert_eq!(result, "Bearer token other");
+        assert!(secrets::references("{{secrets.TOKEN").is_err());
+    }
+
+    #[test]
+    fn test_file_roundtrip() {
+        let key = [7u8; 32];
+        let secrets = HashMap::from([("API_KEY".to_owned(), "abc".to_owned())]);
+        let file = FileStore::seal(&secrets, &key).unwrap();
+        assert!(!file.ciphertext.contains("abc"));
+        assert_eq!(FileStore::open(&file, &key).unwrap(), secrets);
+        assert!(FileStore::open(&file, &[8u8; 32]).is_err());
+    }
+
+    fn context(backend: SecretsBackend, granted: &[&str]) -> Context {
+        let mut ctx = Context::default();
+        let node_id = uuid::Uuid::new_v4();
+        ctx.secrets = from_config(&SecretsConfig {
+            backend,
+            grants: [(node_id, granted.iter().map(|s| s.to_string()).collect())].into(),
+        });
+        ctx.command = Some(CommandContext {
+            node_id,
+            ..ctx.command.unwrap()
+        });
+        ctx
+    }
+
+    #[tokio::test]
+    async fn test_env_grants() {
+        std::env::set_var("TEST_SECRET_STORE_A", "value_a");
+        std::env::set_var("TEST_SECRET_STORE_B", "value_b");
+        let ctx = context(
+            SecretsBackend::Env {
+                prefix: "TEST_SECRET_STORE_".to_owned(),
+            },
+            &["A"],
+        );
+        assert_eq!(ctx.get_secret("A").await.unwrap().expose(), "value_a");
+        assert!(matches!(
+            ctx.get_secret("B").await,
+            Err(secrets::Error::NotAllowed(_))
+        ));
+        assert_eq!(
+            ctx.expand_secrets("key={{secrets.A}}").await.unwrap(),
+            "key=value_a"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_vault_stub() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 4096];
+                let n = stream.read(&mut buf).await.unwrap();
+                let req = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                let (status, body) = if req.starts_with("get /v1/secret/data/flows ")
+                    && req.contains("x-vault-token: test-token")
+                {
+                    ("200 OK", r#"{"data":{"data":{"API_KEY":"from_vault"}}}"#)
+                } else {
+                    ("403 Forbidden", r#"{"errors":[]}"#)
+                };
+                let resp = format!(
+                    "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(resp.as_bytes()).await.unwrap();
+            }
+        });
+
+        std::env::set_var("TEST_VAULT_TOKEN", "test-token");
+        let ctx = context(
+            SecretsBackend::Vault {
+                url: format!("http://{addr}/"),
+                mount: "secret".to_owned(),
+                path: "flows".to_owned(),
+                token_env: "TEST_VAULT_TOKEN".to_owned(),
+            },
+            &["API_KEY", "MISSING"],
+        );
+        assert_eq!(
+            ctx.get_secret("API_KEY").await.unwrap().expose(),
+            "from_vault"
+        );
+        assert!(matches!(
+            ctx.get_secret("MISSING").await,
+            Err(secrets::Error::NotFound(_))
+        ));
+    }
+}
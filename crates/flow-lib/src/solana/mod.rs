@@ -1,3 +1,5 @@
+pub mod program_errors;
+
 use crate::{context::execute::Error, context::signer, UserId};
 use bytes::Bytes;
 use futures::TryStreamExt;
@@ -49,6 +51,9 @@ pub fn verbose_solana_error(err: &ClientError) -> String {
     {
         let mut s = String::new();
         writeln!(s, "{} ({})", message, code).unwrap();
+        if let Some(decoded) = program_errors::decode(err) {
+            writeln!(s, "{}", decoded).unwrap();
+        }
         if let RpcResponseErrorData::SendTransactionPreflightFailure(
             RpcSimulateTransactionResult {
                 logs: Some(logs), ..
@@ -105,12 +110,13 @@ impl Instructions {
         Ok(())
     }
 
-    pub async fn execute(
+    /// Build and sign a transaction, signatures of user wallets are requested from `signer`.
+    pub async fn sign(
         self,
         rpc: &RpcClient,
         signer: signer::Svc,
         user_id: UserId,
-    ) -> Result<Signature, Error> {
+    ) -> Result<Transaction, Error> {
         let recent_blockhash = rpc.get_latest_blockhash().await?;
         let balance: u64 = rpc.get_balance(&self.fee_payer).await?;
 
@@ -184,6 +190,16 @@ impl Instructions {
 
             tx.try_sign(&signers, recent_blockhash)?;
         }
+        Ok(tx)
+    }
+
+    pub async fn execute(
+        self,
+        rpc: &RpcClient,
+        signer: signer::Svc,
+        user_id: UserId,
+    ) -> Result<Signature, Error> {
+        let tx = self.sign(rpc, signer, user_id).await?;
         let commitment = CommitmentConfig::confirmed();
         tracing::trace!("submitting transaction");
         let sig = rpc
@@ -192,4 +208,28 @@ impl Instructions {
 
         Ok(sig)
     }
+
+    /// Sign and simulate the transaction without sending it.
+    ///
+    /// A failed simulation is returned as the same [`ClientError`] that a preflight failure
+    /// would return, so that it can be decoded with [`verbose_solana_error`].
+    pub async fn simulate(
+        self,
+        rpc: &RpcClient,
+        signer: signer::Svc,
+        user_id: UserId,
+    ) -> Result<RpcSimulateTransactionResult, Error> {
+        let tx = self.sign(rpc, signer, user_id).await?;
+        tracing::trace!("simulating transaction");
+        let result = rpc.simulate_transaction(&tx).await?.value;
+        match &result.err {
+            None => Ok(result),
+            Some(err) => Err(ClientError::from(RpcError::RpcResponseError {
+                code: -32002,
+                message: format!("Transaction simulation failed: {}", err),
+                data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+            })
+            .into()),
+        }
+    }
 }
@@ -0,0 +1,273 @@
+//! Names and messages of custom errors returned by well-known programs.
+//!
+//! A failed instruction only reports `custom program error: 0x1`, [`decode`] finds the failing
+//! program in the simulation logs and looks the code up in this registry.
+//!
+//! Registered programs: SPL Token, Token-2022, Associated Token Account, Token Metadata, Candy
+//! Machine Core, Candy Guard, Bubblegum, Account Compression and Wormhole Core Bridge. Errors of
+//! programs built with Anchor also fall back to Anchor's framework errors.
+//!
+//! Tables are generated from the error enums of the programs' crates, run
+//! `cargo run -p cmds-solana --features gen-program-errors --bin gen-program-errors` after
+//! updating them.
+
+use generated::*;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_request::{RpcError, RpcResponseErrorData},
+    rpc_response::RpcSimulateTransactionResult,
+};
+use solana_sdk::{pubkey, pubkey::Pubkey};
+use std::fmt;
+
+#[rustfmt::skip]
+mod generated;
+
+/// `(code, name, message)`
+type ErrorEntry = (u32, &'static str, &'static str);
+
+pub struct Program {
+    pub name: &'static str,
+    pub id: Pubkey,
+    errors: &'static [&'static [ErrorEntry]],
+    anchor: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorInfo {
+    pub program: &'static str,
+    pub name: &'static str,
+    pub message: &'static str,
+}
+
+/// A custom error found in simulation logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodedError {
+    pub program_id: Pubkey,
+    pub code: u32,
+    pub info: Option<ErrorInfo>,
+}
+
+impl fmt::Display for DecodedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.info {
+            Some(info) => {
+                write!(f, "{} error {:#x} {}", info.program, self.code, info.name)?;
+                if !info.message.is_empty() {
+                    write!(f, ": {}", info.message)?;
+                }
+                Ok(())
+            }
+            None => write!(
+                f,
+                "program {} error {:#x} ({})",
+                self.program_id, self.code, self.code
+            ),
+        }
+    }
+}
+
+pub fn program(id: &Pubkey) -> Option<&'static Program> {
+    PROGRAMS.iter().find(|p| p.id == *id)
+}
+
+/// Look up a custom error code of a program.
+pub fn lookup(program_id: &Pubkey, code: u32) -> Option<ErrorInfo> {
+    let program = program(program_id)?;
+    let errors = if program.anchor && code < ANCHOR_ERROR_OFFSET {
+        &[ANCHOR_ERRORS][..]
+    } else {
+        program.errors
+    };
+    errors
+        .iter()
+        .flat_map(|e| e.iter())
+        .find(|e| e.0 == code)
+        .map(|e| ErrorInfo {
+            program: program.name,
+            name: e.1,
+            message: e.2,
+        })
+}
+
+/// Find the last `Program <id> failed: custom program error: 0x<code>` line.
+pub fn find_custom_error<S: AsRef<str>>(logs: &[S]) -> Option<(Pubkey, u32)> {
+    logs.iter().rev().find_map(|log| {
+        let s = log.as_ref().strip_prefix("Program ")?;
+        let (id, rest) = s.split_once(' ')?;
+        let code = rest.strip_prefix("failed: custom program error: 0x")?;
+        Some((id.parse().ok()?, u32::from_str_radix(code.trim(), 16).ok()?))
+    })
+}
+
+/// Decode the custom error of a failed transaction simulation.
+pub fn decode(err: &ClientError) -> Option<DecodedError> {
+    if let ClientErrorKind::RpcError(RpcError::RpcResponseError {
+        data:
+            RpcResponseErrorData::SendTransactionPreflightFailure(RpcSimulateTransactionResult {
+                logs: Some(logs),
+                ..
+            }),
+        ..
+    }) = &err.kind
+    {
+        let (program_id, code) = find_custom_error(logs)?;
+        Some(DecodedError {
+            program_id,
+            code,
+            info: lookup(&program_id, code),
+        })
+    } else {
+        None
+    }
+}
+
+const ANCHOR_ERROR_OFFSET: u32 = 6000;
+
+static PROGRAMS: &[Program] = &[
+    Program {
+        name: "SPL Token",
+        id: pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+        errors: &[TOKEN_ERRORS],
+        anchor: false,
+    },
+    Program {
+        name: "Token-2022",
+        id: pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"),
+        errors: &[TOKEN_2022_ERRORS],
+        anchor: false,
+    },
+    Program {
+        name: "Associated Token Account",
+        id: pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+        errors: &[ASSOCIATED_TOKEN_ACCOUNT_ERRORS],
+        anchor: false,
+    },
+    Program {
+        name: "Token Metadata",
+        id: pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"),
+        errors: &[TOKEN_METADATA_ERRORS],
+        anchor: false,
+    },
+    Program {
+        name: "Candy Machine Core",
+        id: pubkey!("CndyV3LdqHUfDLmE5naZjVN8rBZz4tqhdefbAnjHG3JR"),
+        errors: &[CANDY_MACHINE_CORE_ERRORS],
+        anchor: true,
+    },
+    Program {
+        name: "Candy Guard",
+        id: pubkey!("Guard1JwRhJkVH6XZhzoYxeBVQe872VH6QggF4BWmS9g"),
+        errors: &[CANDY_GUARD_ERRORS],
+        anchor: true,
+    },
+    Program {
+        name: "Bubblegum",
+        id: pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY"),
+        errors: &[BUBBLEGUM_ERRORS],
+        anchor: true,
+    },
+    Program {
+        name: "Account Compression",
+        id: pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK"),
+        errors: &[ACCOUNT_COMPRESSION_ERRORS],
+        anchor: true,
+    },
+    Program {
+        name: "Wormhole Core Bridge",
+        id: pubkey!("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth"),
+        errors: &[WORMHOLE_ERRORS],
+        anchor: false,
+    },
+];
+
+/// Not a dependency of this workspace, written from the Core Bridge sources.
+const WORMHOLE_ERRORS: &[ErrorEntry] = &[
+    (
+        0,
+        "GuardianSetMismatch",
+        "Guardian set does not match the one that signed the VAA",
+    ),
+    (
+        1,
+        "InstructionAtWrongIndex",
+        "Instruction is at the wrong index in the transaction",
+    ),
+    (2, "InsufficientFees", "Message fee was not paid"),
+    (3, "InvalidFeeRecipient", "Invalid fee recipient"),
+    (4, "InvalidGovernanceAction", "Invalid governance action"),
+    (5, "InvalidGovernanceChain", "Invalid governance chain"),
+    (6, "InvalidGovernanceKey", "Invalid governance key"),
+    (7, "InvalidGovernanceModule", "Invalid governance module"),
+    (
+        8,
+        "InvalidGovernanceWithdrawal",
+        "Invalid governance withdrawal",
+    ),
+    (
+        9,
+        "InvalidGuardianSetUpgrade",
+        "Invalid guardian set upgrade",
+    ),
+    (10, "InvalidHash", "Invalid hash"),
+    (
+        11,
+        "InvalidSecpInstruction",
+        "Invalid secp256k1 instruction",
+    ),
+    (12, "MathOverflow", "Math overflow"),
+    (
+        13,
+        "PostVAAConsensusFailed",
+        "Not enough guardian signatures",
+    ),
+    (14, "PostVAAGuardianSetExpired", "Guardian set has expired"),
+    (15, "TooManyGuardians", "Too many guardians"),
+    (16, "VAAAlreadyExecuted", "VAA has already been executed"),
+    (17, "VAAInvalid", "Invalid VAA"),
+    (18, "InvalidPayloadLength", "Invalid payload length"),
+    (19, "EmitterChanged", "Emitter changed"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let token = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+        assert_eq!(lookup(&token, 1).unwrap().name, "InsufficientFunds");
+        assert!(lookup(&token, 20).is_none());
+        let token_2022 = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+        assert_eq!(lookup(&token_2022, 1).unwrap().name, "InsufficientFunds");
+        assert_eq!(lookup(&token_2022, 37).unwrap().name, "NonTransferable");
+        let bubblegum = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+        assert_eq!(lookup(&bubblegum, 6000).unwrap().name, "AssetOwnerMismatch");
+        assert_eq!(
+            lookup(&bubblegum, 3012).unwrap().name,
+            "AccountNotInitialized"
+        );
+        assert!(lookup(&Pubkey::new_unique(), 1).is_none());
+    }
+
+    #[test]
+    fn test_find_in_logs() {
+        let logs = [
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+            "Program log: Error: insufficient funds",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 2974 of 200000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: custom program error: 0x1",
+        ];
+        let (id, code) = find_custom_error(&logs).unwrap();
+        assert_eq!(code, 1);
+        let error = DecodedError {
+            program_id: id,
+            code,
+            info: lookup(&id, code),
+        };
+        assert_eq!(
+            error.to_string(),
+            "SPL Token error 0x1 InsufficientFunds: Insufficient funds"
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! Generated by `cargo run -p cmds-solana --features gen-program-errors --bin gen-program-errors`, do not edit.
+
+use super::ErrorEntry;
+
+/// `TokenError` of spl-token 4.0.0.
+pub(super) const TOKEN_ERRORS: &[ErrorEntry] = &[
+    (0, "NotRentExempt", "Lamport balance below rent-exempt threshold"),
+    (1, "InsufficientFunds", "Insufficient funds"),
+    (2, "InvalidMint", "Invalid Mint"),
+    (3, "MintMismatch", "Account not associated with this Mint"),
+    (4, "OwnerMismatch", "Owner does not match"),
+    (5, "FixedSupply", "Fixed supply"),
+    (6, "AlreadyInUse", "Already in use"),
+    (7, "InvalidNumberOfProvidedSigners", "Invalid number of provided signers"),
+    (8, "InvalidNumberOfRequiredSigners", "Invalid number of required signers"),
+    (9, "UninitializedState", "State is uninitialized"),
+    (10, "NativeNotSupported", "Instruction does not support native tokens"),
+    (11, "NonNativeHasBalance", "Non-native account can only be closed if its balance is zero"),
+    (12, "InvalidInstruction", "Invalid instruction"),
+    (13, "InvalidState", "State is invalid for requested operation"),
+    (14, "Overflow", "Operation overflowed"),
+    (15, "AuthorityTypeNotSupported", "Account does not support specified authority type"),
+    (16, "MintCannotFreeze", "This token mint cannot freeze accounts"),
+    (17, "AccountFrozen", "Account is frozen"),
+    (18, "MintDecimalsMismatch", "The provided decimals value different from the Mint decimals"),
+    (19, "NonNativeNotSupported", "Instruction does not support non-native tokens"),
+];
+
+/// `TokenError` of spl-token-2022 0.9.0.
+pub(super) const TOKEN_2022_ERRORS: &[ErrorEntry] = &[
+    (0, "NotRentExempt", "Lamport balance below rent-exempt threshold"),
+    (1, "InsufficientFunds", "Insufficient funds"),
+    (2, "InvalidMint", "Invalid Mint"),
+    (3, "MintMismatch", "Account not associated with this Mint"),
+    (4, "OwnerMismatch", "Owner does not match"),
+    (5, "FixedSupply", "Fixed supply"),
+    (6, "AlreadyInUse", "Already in use"),
+    (7, "InvalidNumberOfProvidedSigners", "Invalid number of provided signers"),
+    (8, "InvalidNumberOfRequiredSigners", "Invalid number of required signers"),
+    (9, "UninitializedState", "State is uninitialized"),
+    (10, "NativeNotSupported", "Instruction does not support native tokens"),
+    (11, "NonNativeHasBalance", "Non-native account can only be closed if its balance is zero"),
+    (12, "InvalidInstruction", "Invalid instruction"),
+    (13, "InvalidState", "State is invalid for requested operation"),
+    (14, "Overflow", "Operation overflowed"),
+    (15, "AuthorityTypeNotSupported", "Account does not support specified authority type"),
+    (16, "MintCannotFreeze", "This token mint cannot freeze accounts"),
+    (17, "AccountFrozen", "Account is frozen"),
+    (18, "MintDecimalsMismatch", "The provided decimals value different from the Mint decimals"),
+    (19, "NonNativeNotSupported", "Instruction does not support non-native tokens"),
+    (20, "ExtensionTypeMismatch", "Extension type does not match already existing extensions"),
+    (21, "ExtensionBaseMismatch", "Extension does not match the base type provided"),
+    (22, "ExtensionAlreadyInitialized", "Extension already initialized on this account"),
+    (23, "ConfidentialTransferAccountHasBalance", "An account can only be closed if its confidential balance is zero"),
+    (24, "ConfidentialTransferAccountNotApproved", "Account not approved for confidential transfers"),
+    (25, "ConfidentialTransferDepositsAndTransfersDisabled", "Account not accepting deposits or transfers"),
+    (26, "ConfidentialTransferElGamalPubkeyMismatch", "ElGamal public key mismatch"),
+    (27, "ConfidentialTransferBalanceMismatch", "Balance mismatch"),
+    (28, "MintHasSupply", "Mint has non-zero supply. Burn all tokens before closing the mint"),
+    (29, "NoAuthorityExists", "No authority exists to perform the desired operation"),
+    (30, "TransferFeeExceedsMaximum", "Transfer fee exceeds maximum of 10,000 basis points"),
+    (31, "MintRequiredForTransfer", "Mint required for this account to transfer tokens, use `transfer_checked` or `transfer_checked_with_fee`"),
+    (32, "FeeMismatch", "Calculated fee does not match expected fee"),
+    (33, "FeeParametersMismatch", "Fee parameters associated with zero-knowledge proofs do not match fee parameters in mint"),
+    (34, "ImmutableOwner", "The owner authority cannot be changed"),
+    (35, "AccountHasWithheldTransferFees", "An account can only be closed if its withheld fee balance is zero, harvest fees to the mint and try again"),
+    (36, "NoMemo", "No memo in previous instruction; required for recipient to receive a transfer"),
+    (37, "NonTransferable", "Transfer is disabled for this mint"),
+    (38, "NonTransferableNeedsImmutableOwnership", "Non-transferable tokens can't be minted to an account without immutable ownership"),
+    (39, "MaximumPendingBalanceCreditCounterExceeded", "The total number of `Deposit` and `Transfer` instructions to an account cannot exceed\n            the associated `maximum_pending_balance_credit_counter`"),
+    (40, "MaximumDepositAmountExceeded", "Deposit amount exceeds maximum limit"),
+    (41, "CpiGuardSettingsLocked", "CPI Guard cannot be enabled or disabled in CPI"),
+    (42, "CpiGuardTransferBlocked", "CPI Guard is enabled, and a program attempted to transfer user funds via CPI without using a delegate"),
+    (43, "CpiGuardBurnBlocked", "CPI Guard is enabled, and a program attempted to burn user funds via CPI without using a delegate"),
+    (44, "CpiGuardCloseAccountBlocked", "CPI Guard is enabled, and a program attempted to close an account via CPI without returning lamports to owner"),
+    (45, "CpiGuardApproveBlocked", "CPI Guard is enabled, and a program attempted to approve a delegate via CPI"),
+    (46, "CpiGuardSetAuthorityBlocked", "CPI Guard is enabled, and a program attempted to add or replace an authority via CPI"),
+    (47, "CpiGuardOwnerChangeBlocked", "Account ownership cannot be changed while CPI Guard is enabled"),
+    (48, "ExtensionNotFound", "Extension not found in account data"),
+    (49, "NonConfidentialTransfersDisabled", "Non-confidential transfers disabled"),
+    (50, "ConfidentialTransferFeeAccountHasWithheldFee", "An account can only be closed if the confidential withheld fee is zero"),
+    (51, "InvalidExtensionCombination", "A mint or an account is initialized to an invalid combination of extensions"),
+    (52, "InvalidLengthForAlloc", "Extension allocation with overwrite must use the same length"),
+    (53, "AccountDecryption", "Failed to decrypt a confidential transfer account"),
+    (54, "ProofGeneration", "Failed to generate proof"),
+    (55, "InvalidProofInstructionOffset", "An invalid proof instruction offset was provided"),
+    (56, "HarvestToMintDisabled", "Harvest of withheld tokens to mint is disabled"),
+    (57, "SplitProofContextStateAccountsNotSupported", "Split proof context state accounts not supported for instruction"),
+    (58, "NotEnoughProofContextStateAccounts", "Not enough proof context state accounts provided"),
+    (59, "MalformedCiphertext", "Ciphertext is malformed"),
+    (60, "CiphertextArithmeticFailed", "Ciphertext arithmetic failed"),
+];
+
+/// `AssociatedTokenAccountError` of spl-associated-token-account 2.2.0.
+pub(super) const ASSOCIATED_TOKEN_ACCOUNT_ERRORS: &[ErrorEntry] = &[
+    (0, "InvalidOwner", "Associated token account owner does not match address derivation"),
+];
+
+/// `MplTokenMetadataError` of mpl-token-metadata 3.1.0.
+pub(super) const TOKEN_METADATA_ERRORS: &[ErrorEntry] = &[
+    (0, "InstructionUnpackError", ""),
+    (1, "InstructionPackError", ""),
+    (2, "NotRentExempt", "Lamport balance below rent-exempt threshold"),
+    (3, "AlreadyInitialized", "Already initialized"),
+    (4, "Uninitialized", "Uninitialized"),
+    (5, "InvalidMetadataKey", "Metadata's key must match seed of ['metadata', program id, mint] provided"),
+    (6, "InvalidEditionKey", "Edition's key must match seed of ['metadata', program id, name, 'edition'] provided"),
+    (7, "UpdateAuthorityIncorrect", "Update Authority given does not match"),
+    (8, "UpdateAuthorityIsNotSigner", "Update Authority needs to be signer to update metadata"),
+    (9, "NotMintAuthority", "You must be the mint authority and signer on this transaction"),
+    (10, "InvalidMintAuthority", "Mint authority provided does not match the authority on the mint"),
+    (11, "NameTooLong", "Name too long"),
+    (12, "SymbolTooLong", "Symbol too long"),
+    (13, "UriTooLong", "URI too long"),
+    (14, "UpdateAuthorityMustBeEqualToMetadataAuthorityAndSigner", ""),
+    (15, "MintMismatch", "Mint given does not match mint on Metadata"),
+    (16, "EditionsMustHaveExactlyOneToken", "Editions must have exactly one token"),
+    (17, "MaxEditionsMintedAlready", ""),
+    (18, "TokenMintToFailed", ""),
+    (19, "MasterRecordMismatch", ""),
+    (20, "DestinationMintMismatch", ""),
+    (21, "EditionAlreadyMinted", ""),
+    (22, "PrintingMintDecimalsShouldBeZero", ""),
+    (23, "OneTimePrintingAuthorizationMintDecimalsShouldBeZero", ""),
+    (24, "EditionMintDecimalsShouldBeZero", "EditionMintDecimalsShouldBeZero"),
+    (25, "TokenBurnFailed", ""),
+    (26, "TokenAccountOneTimeAuthMintMismatch", ""),
+    (27, "DerivedKeyInvalid", "Derived key invalid"),
+    (28, "PrintingMintMismatch", "The Printing mint does not match that on the master edition!"),
+    (29, "OneTimePrintingAuthMintMismatch", "The One Time Printing Auth mint does not match that on the master edition!"),
+    (30, "TokenAccountMintMismatch", "The mint of the token account does not match the Printing mint!"),
+    (31, "TokenAccountMintMismatchV2", "The mint of the token account does not match the master metadata mint!"),
+    (32, "NotEnoughTokens", "Not enough tokens to mint a limited edition"),
+    (33, "PrintingMintAuthorizationAccountMismatch", ""),
+    (34, "AuthorizationTokenAccountOwnerMismatch", ""),
+    (35, "Disabled", ""),
+    (36, "CreatorsTooLong", "Creators list too long"),
+    (37, "CreatorsMustBeAtleastOne", "Creators must be at least one if set"),
+    (38, "MustBeOneOfCreators", ""),
+    (39, "NoCreatorsPresentOnMetadata", "This metadata does not have creators"),
+    (40, "CreatorNotFound", "This creator address was not found"),
+    (41, "InvalidBasisPoints", "Basis points cannot be more than 10000"),
+    (42, "PrimarySaleCanOnlyBeFlippedToTrue", "Primary sale can only be flipped to true and is immutable"),
+    (43, "OwnerMismatch", "Owner does not match that on the account given"),
+    (44, "NoBalanceInAccountForAuthorization", "This account has no tokens to be used for authorization"),
+    (45, "ShareTotalMustBe100", "Share total must equal 100 for creator array"),
+    (46, "ReservationExists", ""),
+    (47, "ReservationDoesNotExist", ""),
+    (48, "ReservationNotSet", ""),
+    (49, "ReservationAlreadyMade", ""),
+    (50, "BeyondMaxAddressSize", ""),
+    (51, "NumericalOverflowError", "NumericalOverflowError"),
+    (52, "ReservationBreachesMaximumSupply", ""),
+    (53, "AddressNotInReservation", ""),
+    (54, "CannotVerifyAnotherCreator", "You cannot unilaterally verify another creator, they must sign"),
+    (55, "CannotUnverifyAnotherCreator", "You cannot unilaterally unverify another creator"),
+    (56, "SpotMismatch", ""),
+    (57, "IncorrectOwner", "Incorrect account owner"),
+    (58, "PrintingWouldBreachMaximumSupply", ""),
+    (59, "DataIsImmutable", "Data is immutable"),
+    (60, "DuplicateCreatorAddress", "No duplicate creator addresses"),
+    (61, "ReservationSpotsRemainingShouldMatchTotalSpotsAtStart", ""),
+    (62, "InvalidTokenProgram", "Invalid token program"),
+    (63, "DataTypeMismatch", "Data type mismatch"),
+    (64, "BeyondAlottedAddressSize", ""),
+    (65, "ReservationNotComplete", ""),
+    (66, "TriedToReplaceAnExistingReservation", ""),
+    (67, "InvalidOperation", "Invalid operation"),
+    (68, "InvalidOwner", "Invalid Owner"),
+    (69, "PrintingMintSupplyMustBeZeroForConversion", "Printing mint supply must be zero for conversion"),
+    (70, "OneTimeAuthMintSupplyMustBeZeroForConversion", "One Time Auth mint supply must be zero for conversion"),
+    (71, "InvalidEditionIndex", "You tried to insert one edition too many into an edition mark pda"),
+    (72, "ReservationArrayShouldBeSizeOne", ""),
+    (73, "IsMutableCanOnlyBeFlippedToFalse", "Is Mutable can only be flipped to false"),
+    (74, "CollectionCannotBeVerifiedInThisInstruction", "Collection cannot be verified in this instruction"),
+    (75, "Removed", "This instruction was deprecated in a previous release and is now removed"),
+    (76, "MustBeBurned", ""),
+    (77, "InvalidUseMethod", "This use method is invalid"),
+    (78, "CannotChangeUseMethodAfterFirstUse", "Cannot Change Use Method after the first use"),
+    (79, "CannotChangeUsesAfterFirstUse", "Cannot Change Remaining or Available uses after the first use"),
+    (80, "CollectionNotFound", "Collection Not Found on Metadata"),
+    (81, "InvalidCollectionUpdateAuthority", "Collection Update Authority is invalid"),
+    (82, "CollectionMustBeAUniqueMasterEdition", "Collection Must Be a Unique Master Edition v2"),
+    (83, "UseAuthorityRecordAlreadyExists", "The Use Authority Record Already Exists, to modify it Revoke, then Approve"),
+    (84, "UseAuthorityRecordAlreadyRevoked", "The Use Authority Record is empty or already revoked"),
+    (85, "Unusable", "This token has no uses"),
+    (86, "NotEnoughUses", "There are not enough Uses left on this token."),
+    (87, "CollectionAuthorityRecordAlreadyExists", "This Collection Authority Record Already Exists."),
+    (88, "CollectionAuthorityDoesNotExist", "This Collection Authority Record Does Not Exist."),
+    (89, "InvalidUseAuthorityRecord", "This Use Authority Record is invalid."),
+    (90, "InvalidCollectionAuthorityRecord", ""),
+    (91, "InvalidFreezeAuthority", "Metadata does not match the freeze authority on the mint"),
+    (92, "InvalidDelegate", "All tokens in this account have not been delegated to this user."),
+    (93, "CannotAdjustVerifiedCreator", ""),
+    (94, "CannotRemoveVerifiedCreator", "Verified creators cannot be removed."),
+    (95, "CannotWipeVerifiedCreators", ""),
+    (96, "NotAllowedToChangeSellerFeeBasisPoints", ""),
+    (97, "EditionOverrideCannotBeZero", "Edition override cannot be zero"),
+    (98, "InvalidUser", "Invalid User"),
+    (99, "RevokeCollectionAuthoritySignerIncorrect", "Revoke Collection Authority signer is incorrect"),
+    (100, "TokenCloseFailed", ""),
+    (101, "UnsizedCollection", "Can't use this function on unsized collection"),
+    (102, "SizedCollection", "Can't use this function on a sized collection"),
+    (103, "MissingCollectionMetadata", "Missing collection metadata account"),
+    (104, "NotAMemberOfCollection", "This NFT is not a member of the specified collection."),
+    (105, "NotVerifiedMemberOfCollection", "This NFT is not a verified member of the specified collection."),
+    (106, "NotACollectionParent", "This NFT is not a collection parent NFT."),
+    (107, "CouldNotDetermineTokenStandard", "Could not determine a TokenStandard type."),
+    (108, "MissingEditionAccount", "This mint account has an edition but none was provided."),
+    (109, "NotAMasterEdition", "This edition is not a Master Edition"),
+    (110, "MasterEditionHasPrints", "This Master Edition has existing prints"),
+    (111, "BorshDeserializationError", ""),
+    (112, "CannotUpdateVerifiedCollection", "Cannot update a verified collection in this command"),
+    (113, "CollectionMasterEditionAccountInvalid", "Edition account doesnt match collection"),
+    (114, "AlreadyVerified", "Item is already verified."),
+    (115, "AlreadyUnverified", ""),
+    (116, "NotAPrintEdition", "This edition is not a Print Edition"),
+    (117, "InvalidMasterEdition", "Invalid Master Edition"),
+    (118, "InvalidPrintEdition", "Invalid Print Edition"),
+    (119, "InvalidEditionMarker", "Invalid Edition Marker"),
+    (120, "ReservationListDeprecated", "Reservation List is Deprecated"),
+    (121, "PrintEditionDoesNotMatchMasterEdition", "Print Edition does not match Master Edition"),
+    (122, "EditionNumberGreaterThanMaxSupply", "Edition Number greater than max supply"),
+    (123, "MustUnverify", "Must unverify before migrating collections."),
+    (124, "InvalidEscrowBumpSeed", "Invalid Escrow Account Bump Seed"),
+    (125, "MustBeEscrowAuthority", "Must Escrow Authority"),
+    (126, "InvalidSystemProgram", "Invalid System Program"),
+    (127, "MustBeNonFungible", "Must be a Non Fungible Token"),
+    (128, "InsufficientTokens", "Insufficient tokens for transfer"),
+    (129, "BorshSerializationError", "Borsh Serialization Error"),
+    (130, "NoFreezeAuthoritySet", "Cannot create NFT with no Freeze Authority."),
+    (131, "InvalidCollectionSizeChange", "Invalid collection size change"),
+    (132, "InvalidBubblegumSigner", "Invalid bubblegum signer"),
+    (133, "EscrowParentHasDelegate", "Escrow parent cannot have a delegate"),
+    (134, "MintIsNotSigner", "Mint needs to be signer to initialize the account"),
+    (135, "InvalidTokenStandard", "Invalid token standard"),
+    (136, "InvalidMintForTokenStandard", "Invalid mint account for specified token standard"),
+    (137, "InvalidAuthorizationRules", "Invalid authorization rules account"),
+    (138, "MissingAuthorizationRules", "Missing authorization rules account"),
+    (139, "MissingProgrammableConfig", "Missing programmable configuration"),
+    (140, "InvalidProgrammableConfig", "Invalid programmable configuration"),
+    (141, "DelegateAlreadyExists", "Delegate already exists"),
+    (142, "DelegateNotFound", "Delegate not found"),
+    (143, "MissingAccountInBuilder", "Required account not set in instruction builder"),
+    (144, "MissingArgumentInBuilder", "Required argument not set in instruction builder"),
+    (145, "FeatureNotSupported", "Feature not supported currently"),
+    (146, "InvalidSystemWallet", "Invalid system wallet"),
+    (147, "OnlySaleDelegateCanTransfer", "Only the sale delegate can transfer while its set"),
+    (148, "MissingTokenAccount", "Missing token account"),
+    (149, "MissingSplTokenProgram", "Missing SPL token program"),
+    (150, "MissingAuthorizationRulesProgram", "Missing authorization rules program"),
+    (151, "InvalidDelegateRoleForTransfer", "Invalid delegate role for transfer"),
+    (152, "InvalidTransferAuthority", "Invalid transfer authority"),
+    (153, "InstructionNotSupported", "Instruction not supported for ProgrammableNonFungible assets"),
+    (154, "KeyMismatch", "Public key does not match expected value"),
+    (155, "LockedToken", "Token is locked"),
+    (156, "UnlockedToken", "Token is unlocked"),
+    (157, "MissingDelegateRole", "Missing delegate role"),
+    (158, "InvalidAuthorityType", "Invalid authority type"),
+    (159, "MissingTokenRecord", "Missing token record account"),
+    (160, "MintSupplyMustBeZero", "Mint supply must be zero for programmable assets"),
+    (161, "DataIsEmptyOrZeroed", "Data is empty or zeroed"),
+    (162, "MissingTokenOwnerAccount", "Missing token owner"),
+    (163, "InvalidMasterEditionAccountLength", "Master edition account has an invalid length"),
+    (164, "IncorrectTokenState", "Incorrect token state"),
+    (165, "InvalidDelegateRole", "Invalid delegate role"),
+    (166, "MissingPrintSupply", "Print supply is required for non-fungibles"),
+    (167, "MissingMasterEditionAccount", "Missing master edition account"),
+    (168, "AmountMustBeGreaterThanZero", "Amount must be greater than zero"),
+    (169, "InvalidDelegateArgs", "Invalid delegate args"),
+    (170, "MissingLockedTransferAddress", "Missing address for locked transfer"),
+    (171, "InvalidLockedTransferAddress", "Invalid destination address for locked transfer"),
+    (172, "DataIncrementLimitExceeded", "Exceeded account realloc increase limit"),
+    (173, "CannotUpdateAssetWithDelegate", "Cannot update the rule set of a programmable asset that has a delegate"),
+    (174, "InvalidAmount", "Invalid token amount for this operation or token standard"),
+    (175, "MissingMasterEditionMintAccount", "Missing master edition mint account"),
+    (176, "MissingMasterEditionTokenAccount", "Missing master edition token account"),
+    (177, "MissingEditionMarkerAccount", "Missing edition marker account"),
+    (178, "CannotBurnWithDelegate", "Cannot burn while persistent delegate is set"),
+    (179, "MissingEdition", "Missing edition account"),
+    (180, "InvalidAssociatedTokenAccountProgram", "Invalid Associated Token Account Program"),
+    (181, "InvalidInstructionsSysvar", "Invalid InstructionsSysvar"),
+    (182, "InvalidParentAccounts", "Invalid or Unneeded parent accounts"),
+    (183, "InvalidUpdateArgs", "Authority cannot apply all update args"),
+    (184, "InsufficientTokenBalance", "Token account does not have enough tokens"),
+    (185, "MissingCollectionMint", "Missing collection account"),
+    (186, "MissingCollectionMasterEdition", "Missing collection master edition account"),
+    (187, "InvalidTokenRecord", "Invalid token record account"),
+    (188, "InvalidCloseAuthority", "The close authority needs to be revoked by the Utility Delegate"),
+    (189, "InvalidInstruction", "Invalid or removed instruction"),
+    (190, "MissingDelegateRecord", "Missing delegate record"),
+    (191, "InvalidFeeAccount", ""),
+    (192, "InvalidMetadataFlags", ""),
+    (193, "CannotChangeUpdateAuthorityWithDelegate", "Cannot change the update authority with a delegate"),
+];
+
+/// `CandyError` of mpl-candy-machine-core 2.0.1.
+pub(super) const CANDY_MACHINE_CORE_ERRORS: &[ErrorEntry] = &[
+    (6000, "IncorrectOwner", "Account does not have correct owner"),
+    (6001, "Uninitialized", "Account is not initialized"),
+    (6002, "MintMismatch", "Mint Mismatch"),
+    (6003, "IndexGreaterThanLength", "Index greater than length"),
+    (6004, "NumericalOverflowError", "Numerical overflow error"),
+    (6005, "TooManyCreators", "Can only provide up to 4 creators to candy machine (because candy machine is one)"),
+    (6006, "CandyMachineEmpty", "Candy machine is empty"),
+    (6007, "HiddenSettingsDoNotHaveConfigLines", "Candy machines using hidden uris do not have config lines, they have a single hash representing hashed order"),
+    (6008, "CannotChangeNumberOfLines", "Cannot change number of lines unless is a hidden config"),
+    (6009, "CannotSwitchToHiddenSettings", "Cannot switch to hidden settings after items available is greater than 0"),
+    (6010, "IncorrectCollectionAuthority", "Incorrect collection NFT authority"),
+    (6011, "MetadataAccountMustBeEmpty", "The metadata account has data in it, and this must be empty to mint a new NFT"),
+    (6012, "NoChangingCollectionDuringMint", "Can't change collection settings after items have begun to be minted"),
+    (6013, "ExceededLengthError", "Value longer than expected maximum value"),
+    (6014, "MissingConfigLinesSettings", "Missing config lines settings"),
+    (6015, "CannotIncreaseLength", "Cannot increase the length in config lines settings"),
+    (6016, "CannotSwitchFromHiddenSettings", "Cannot switch from hidden settings"),
+    (6017, "CannotChangeSequentialIndexGeneration", "Cannot change sequential index generation after items have begun to be minted"),
+    (6018, "CollectionKeyMismatch", "Collection public key mismatch"),
+    (6019, "CouldNotRetrieveConfigLineData", "Could not retrive config line data"),
+    (6020, "NotFullyLoaded", "Not all config lines were added to the candy machine"),
+    (6021, "InstructionBuilderFailed", "Instruction could not be created"),
+    (6022, "MissingCollectionAuthorityRecord", "Missing collection authority record"),
+    (6023, "MissingMetadataDelegateRecord", "Missing metadata delegate record"),
+    (6024, "InvalidTokenStandard", "Invalid token standard"),
+    (6025, "MissingTokenAccount", "Missing token account"),
+    (6026, "MissingTokenRecord", "Missing token record"),
+    (6027, "MissingInstructionsSysvar", "Missing instructions sysvar account"),
+    (6028, "MissingSplAtaProgram", "Missing SPL ATA program"),
+    (6029, "InvalidAccountVersion", "Invalid account version"),
+];
+
+/// `CandyGuardError` of mpl-candy-guard 2.0.0.
+pub(super) const CANDY_GUARD_ERRORS: &[ErrorEntry] = &[
+    (6000, "InvalidAccountSize", "Could not save guard to account"),
+    (6001, "DeserializationError", "Could not deserialize guard"),
+    (6002, "PublicKeyMismatch", "Public key mismatch"),
+    (6003, "DataIncrementLimitExceeded", "Exceeded account increase limit"),
+    (6004, "IncorrectOwner", "Account does not have correct owner"),
+    (6005, "Uninitialized", "Account is not initialized"),
+    (6006, "MissingRemainingAccount", "Missing expected remaining account"),
+    (6007, "NumericalOverflowError", "Numerical overflow error"),
+    (6008, "RequiredGroupLabelNotFound", "Missing required group label"),
+    (6009, "GroupNotFound", "Group not found"),
+    (6010, "ExceededLength", "Value exceeded maximum length"),
+    (6011, "CandyMachineEmpty", "Candy machine is empty"),
+    (6012, "InstructionNotFound", "No instruction was found"),
+    (6013, "CollectionKeyMismatch", "Collection public key mismatch"),
+    (6014, "MissingCollectionAccounts", "Missing collection accounts"),
+    (6015, "CollectionUpdateAuthorityKeyMismatch", "Collection update authority public key mismatch"),
+    (6016, "MintNotLastTransaction", "Mint must be the last instructions of the transaction"),
+    (6017, "MintNotLive", "Mint is not live"),
+    (6018, "NotEnoughSOL", "Not enough SOL to pay for the mint"),
+    (6019, "TokenBurnFailed", "Token burn failed"),
+    (6020, "NotEnoughTokens", "Not enough tokens on the account"),
+    (6021, "TokenTransferFailed", "Token transfer failed"),
+    (6022, "MissingRequiredSignature", "A signature was required but not found"),
+    (6023, "GatewayTokenInvalid", "Gateway token is not valid"),
+    (6024, "AfterEndDate", "Current time is after the set end date"),
+    (6025, "InvalidMintTime", "Current time is not within the allowed mint time"),
+    (6026, "AddressNotFoundInAllowedList", "Address not found on the allowed list"),
+    (6027, "MissingAllowedListProof", "Missing allowed list proof"),
+    (6028, "AllowedListNotEnabled", "Allow list guard is not enabled"),
+    (6029, "AllowedMintLimitReached", "The maximum number of allowed mints was reached"),
+    (6030, "InvalidNftCollection", "Invalid NFT collection"),
+    (6031, "MissingNft", "Missing NFT on the account"),
+    (6032, "MaximumRedeemedAmount", "Current redemeed items is at the set maximum amount"),
+    (6033, "AddressNotAuthorized", "Address not authorized"),
+    (6034, "MissingFreezeInstruction", "Missing freeze instruction data"),
+    (6035, "FreezeGuardNotEnabled", "Freeze guard must be enabled"),
+    (6036, "FreezeNotInitialized", "Freeze must be initialized"),
+    (6037, "MissingFreezePeriod", "Missing freeze period"),
+    (6038, "FreezeEscrowAlreadyExists", "The freeze escrow account already exists"),
+    (6039, "ExceededMaximumFreezePeriod", "Maximum freeze period exceeded"),
+    (6040, "ThawNotEnabled", "Thaw is not enabled"),
+    (6041, "UnlockNotEnabled", "Unlock is not enabled (not all NFTs are thawed)"),
+    (6042, "DuplicatedGroupLabel", "Duplicated group label"),
+    (6043, "DuplicatedMintLimitId", "Duplicated mint limit id"),
+    (6044, "UnauthorizedProgramFound", "An unauthorized program was found in the transaction"),
+    (6045, "ExceededProgramListSize", "Exceeded the maximum number of programs in the additional list"),
+    (6046, "AllocationNotInitialized", "Allocation PDA not initialized"),
+    (6047, "AllocationLimitReached", "Allocation limit was reached"),
+    (6048, "AllocationGuardNotEnabled", "Allocation guard must be enabled"),
+    (6049, "InvalidMintAuthority", "Candy machine has an invalid mint authority"),
+    (6050, "InstructionBuilderFailed", "Instruction could not be created"),
+    (6051, "InvalidAccountVersion", "Invalid account version"),
+];
+
+/// `BubblegumError` of mpl-bubblegum 0.11.0.
+pub(super) const BUBBLEGUM_ERRORS: &[ErrorEntry] = &[
+    (6000, "AssetOwnerMismatch", "Asset Owner Does not match"),
+    (6001, "PublicKeyMismatch", "PublicKeyMismatch"),
+    (6002, "HashingMismatch", "Hashing Mismatch Within Leaf Schema"),
+    (6003, "UnsupportedSchemaVersion", "Unsupported Schema Version"),
+    (6004, "CreatorShareTotalMustBe100", "Creator shares must sum to 100"),
+    (6005, "DuplicateCreatorAddress", "No duplicate creator addresses in metadata"),
+    (6006, "CreatorDidNotVerify", "Creator did not verify the metadata"),
+    (6007, "CreatorNotFound", "Creator not found in creator Vec"),
+    (6008, "NoCreatorsPresent", "No creators in creator Vec"),
+    (6009, "CreatorHashMismatch", "User-provided creator Vec must result in same user-provided creator hash"),
+    (6010, "DataHashMismatch", "User-provided metadata must result in same user-provided data hash"),
+    (6011, "CreatorsTooLong", "Creators list too long"),
+    (6012, "MetadataNameTooLong", "Name in metadata is too long"),
+    (6013, "MetadataSymbolTooLong", "Symbol in metadata is too long"),
+    (6014, "MetadataUriTooLong", "Uri in metadata is too long"),
+    (6015, "MetadataBasisPointsTooHigh", "Basis points in metadata cannot exceed 10000"),
+    (6016, "TreeAuthorityIncorrect", "Tree creator or tree delegate must sign."),
+    (6017, "InsufficientMintCapacity", "Not enough unapproved mints left"),
+    (6018, "NumericalOverflowError", "NumericalOverflowError"),
+    (6019, "IncorrectOwner", "Incorrect account owner"),
+    (6020, "CollectionCannotBeVerifiedInThisInstruction", "Cannot Verify Collection in this Instruction"),
+    (6021, "CollectionNotFound", "Collection Not Found on Metadata"),
+    (6022, "AlreadyVerified", "Collection item is already verified."),
+    (6023, "AlreadyUnverified", "Collection item is already unverified."),
+    (6024, "UpdateAuthorityIncorrect", "Incorrect leaf metadata update authority."),
+    (6025, "LeafAuthorityMustSign", "This transaction must be signed by either the leaf owner or leaf delegate"),
+    (6026, "CollectionMustBeSized", "Collection Not Compatable with Compression, Must be Sized"),
+    (6027, "MetadataMintMismatch", "Metadata mint does not match collection mint"),
+    (6028, "InvalidCollectionAuthority", "Invalid collection authority"),
+    (6029, "InvalidDelegateRecord", "Invalid delegate record pda derivation"),
+    (6030, "CollectionMasterEditionAccountInvalid", "Edition account doesnt match collection"),
+    (6031, "CollectionMustBeAUniqueMasterEdition", "Collection Must Be a Unique Master Edition v2"),
+    (6032, "UnknownExternalError", "Could not convert external error to BubblegumError"),
+    (6033, "DecompressionDisabled", "Decompression is disabled for this tree."),
+];
+
+/// `AccountCompressionError` of spl-account-compression 0.2.0.
+pub(super) const ACCOUNT_COMPRESSION_ERRORS: &[ErrorEntry] = &[
+    (6000, "IncorrectLeafLength", "Incorrect leaf length. Expected vec of 32 bytes"),
+    (6001, "ConcurrentMerkleTreeError", "Concurrent merkle tree error"),
+    (6002, "ZeroCopyError", "Issue zero copying concurrent merkle tree data"),
+    (6003, "ConcurrentMerkleTreeConstantsError", "An unsupported max depth or max buffer size constant was provided"),
+    (6004, "CanopyLengthMismatch", "Expected a different byte length for the merkle tree canopy"),
+    (6005, "IncorrectAuthority", "Provided authority does not match expected tree authority"),
+    (6006, "IncorrectAccountOwner", "Account is owned by a different program, expected it to be owned by this program"),
+    (6007, "IncorrectAccountType", "Account provided has incorrect account type"),
+    (6008, "LeafIndexOutOfBounds", "Leaf index of concurrent merkle tree is out of bounds"),
+];
+
+/// `ErrorCode` of anchor-lang 0.28.0.
+pub(super) const ANCHOR_ERRORS: &[ErrorEntry] = &[
+    (100, "InstructionMissing", "8 byte instruction identifier not provided"),
+    (101, "InstructionFallbackNotFound", "Fallback functions are not supported"),
+    (102, "InstructionDidNotDeserialize", "The program could not deserialize the given instruction"),
+    (103, "InstructionDidNotSerialize", "The program could not serialize the given instruction"),
+    (1000, "IdlInstructionStub", "The program was compiled without idl instructions"),
+    (1001, "IdlInstructionInvalidProgram", "Invalid program given to the IDL instruction"),
+    (1002, "IdlAccountNotEmpty", "IDL account must be empty in order to resize, try closing first"),
+    (1500, "EventInstructionStub", "The program was compiled without `event-cpi` feature"),
+    (2000, "ConstraintMut", "A mut constraint was violated"),
+    (2001, "ConstraintHasOne", "A has one constraint was violated"),
+    (2002, "ConstraintSigner", "A signer constraint was violated"),
+    (2003, "ConstraintRaw", "A raw constraint was violated"),
+    (2004, "ConstraintOwner", "An owner constraint was violated"),
+    (2005, "ConstraintRentExempt", "A rent exemption constraint was violated"),
+    (2006, "ConstraintSeeds", "A seeds constraint was violated"),
+    (2007, "ConstraintExecutable", "An executable constraint was violated"),
+    (2008, "ConstraintState", "Deprecated Error, feel free to replace with something else"),
+    (2009, "ConstraintAssociated", "An associated constraint was violated"),
+    (2010, "ConstraintAssociatedInit", "An associated init constraint was violated"),
+    (2011, "ConstraintClose", "A close constraint was violated"),
+    (2012, "ConstraintAddress", "An address constraint was violated"),
+    (2013, "ConstraintZero", "Expected zero account discriminant"),
+    (2014, "ConstraintTokenMint", "A token mint constraint was violated"),
+    (2015, "ConstraintTokenOwner", "A token owner constraint was violated"),
+    (2016, "ConstraintMintMintAuthority", "A mint mint authority constraint was violated"),
+    (2017, "ConstraintMintFreezeAuthority", "A mint freeze authority constraint was violated"),
+    (2018, "ConstraintMintDecimals", "A mint decimals constraint was violated"),
+    (2019, "ConstraintSpace", "A space constraint was violated"),
+    (2020, "ConstraintAccountIsNone", "A required account for the constraint is None"),
+    (2021, "ConstraintTokenTokenProgram", "A token account token program constraint was violated"),
+    (2022, "ConstraintMintTokenProgram", "A mint token program constraint was violated"),
+    (2023, "ConstraintAssociatedTokenTokenProgram", "An associated token account token program constraint was violated"),
+    (2500, "RequireViolated", "A require expression was violated"),
+    (2501, "RequireEqViolated", "A require_eq expression was violated"),
+    (2502, "RequireKeysEqViolated", "A require_keys_eq expression was violated"),
+    (2503, "RequireNeqViolated", "A require_neq expression was violated"),
+    (2504, "RequireKeysNeqViolated", "A require_keys_neq expression was violated"),
+    (2505, "RequireGtViolated", "A require_gt expression was violated"),
+    (2506, "RequireGteViolated", "A require_gte expression was violated"),
+    (3000, "AccountDiscriminatorAlreadySet", "The account discriminator was already set on this account"),
+    (3001, "AccountDiscriminatorNotFound", "No 8 byte discriminator was found on the account"),
+    (3002, "AccountDiscriminatorMismatch", "8 byte discriminator did not match what was expected"),
+    (3003, "AccountDidNotDeserialize", "Failed to deserialize the account"),
+    (3004, "AccountDidNotSerialize", "Failed to serialize the account"),
+    (3005, "AccountNotEnoughKeys", "Not enough account keys given to the instruction"),
+    (3006, "AccountNotMutable", "The given account is not mutable"),
+    (3007, "AccountOwnedByWrongProgram", "The given account is owned by a different program than expected"),
+    (3008, "InvalidProgramId", "Program ID was not as expected"),
+    (3009, "InvalidProgramExecutable", "Program account is not executable"),
+    (3010, "AccountNotSigner", "The given account did not sign"),
+    (3011, "AccountNotSystemOwned", "The given account is not owned by the system program"),
+    (3012, "AccountNotInitialized", "The program expected this account to be already initialized"),
+    (3013, "AccountNotProgramData", "The given account is not a program data account"),
+    (3014, "AccountNotAssociatedTokenAccount", "The given account is not the associated token account"),
+    (3015, "AccountSysvarMismatch", "The given public key does not match the required sysvar"),
+    (3016, "AccountReallocExceedsLimit", "The account reallocation exceeds the MAX_PERMITTED_DATA_INCREASE limit"),
+    (3017, "AccountDuplicateReallocs", "The account was duplicated for more than one reallocation"),
+    (4100, "DeclaredProgramIdMismatch", "The declared program id does not match the actual program id"),
+    (4101, "TryingToInitPayerAsProgramAccount", "You cannot/should not initialize the payer account as a program account"),
+    (5000, "Deprecated", "The API being used is deprecated and should no longer be used"),
+];
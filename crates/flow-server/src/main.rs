@@ -0,0 +1,281 @@
+//! Self-hosted flow server, runs flows with [`FlowRunner`] for local development.
+//!
+//! ```text
+//! flow-server [--bind <ADDR>] [--flows <DIR>] [--keypair <FILE>]... [--secrets <FILE>]
+//! ```
+//!
+//! - `--bind`: address to listen on, default `127.0.0.1:8080`.
+//! - `--flows`: directory of flow configs, flow `{id}` is read from `{DIR}/{id}.json` in the
+//!   front-end format of [`ClientConfig`][flow_lib::config::client::ClientConfig]. Files are read
+//!   when a flow is started, also by `interflow` nodes, default `flows`.
+//! - `--keypair`: sign for this keypair when a node requests a wallet signature, can be repeated.
+//! - `--secrets`: a JSON [`SecretsConfig`], the backend of secrets and which nodes can read them.
+//!   Flows cannot read secrets without it.
+//!
+//! Routes:
+//! - `POST /flow/start/{id}`: start a flow, request and response bodies are described in
+//!   `json-schemas/flow-server/flow/start`. Partial runs load outputs of previous runs of this
+//!   server process.
+//! - `GET /flow/events/{flow_run_id}`: [`FlowEvent`]s of a run as server-sent events.
+//! - `GET /flow/ws/{flow_run_id}`: [`FlowEvent`]s of a run as WebSocket text messages.
+//! - `GET /flow/output/{flow_run_id}`: wait for the run to finish and return its
+//!   [`FlowRunResult`][flow_lib::runner::FlowRunResult].
+//! - `POST /flow/stop/{flow_run_id}`: cancel a run.
+//!
+//! Event streams start with past events of the run and end after [`FlowEvent::FlowFinish`].
+
+use actix_web::{get, http::StatusCode, post, web, App, HttpRequest, HttpResponse, HttpServer};
+use bytes::Bytes;
+use cmds_solana as _;
+use flow_lib::{
+    config::{client::PartialConfig, SecretsConfig},
+    context::{get_flow, secrets, signer, Cancellation},
+    policy::Breakers,
+    runner::{FlowEvent, FlowRunner},
+    secret_store, FlowConfig, FlowId, FlowRunId, User, ValueSet,
+};
+use futures::{channel::mpsc, StreamExt};
+use runs::Runs;
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::read_keypair_file;
+use std::collections::HashMap;
+use tokio_util::sync::CancellationToken;
+use tracing_subscriber::prelude::*;
+
+mod runs;
+
+/// Request body of `/flow/start/{id}`.
+#[derive(Deserialize, Default)]
+struct StartFlowRequest {
+    #[serde(default)]
+    inputs: ValueSet,
+    #[serde(default)]
+    partial_config: Option<PartialConfig>,
+    #[serde(default)]
+    environment: HashMap<String, String>,
+}
+
+/// Response body of `/flow/start/{id}`.
+#[derive(Serialize)]
+struct StartFlowResponse {
+    flow_run_id: FlowRunId,
+}
+
+struct State {
+    get_flow: get_flow::Svc,
+    signer: signer::Svc,
+    secrets: secrets::Svc,
+    runs: Runs,
+    /// Circuit breakers of nodes, shared by all runs.
+    breakers: Breakers,
+}
+
+fn error(status: StatusCode, message: impl std::fmt::Display) -> HttpResponse {
+    HttpResponse::build(status).json(serde_json::json!({ "error": message.to_string() }))
+}
+
+fn run_not_found() -> HttpResponse {
+    error(StatusCode::NOT_FOUND, "flow run not found")
+}
+
+#[post("/flow/start/{id}")]
+async fn start_flow(state: web::Data<State>, id: web::Path<FlowId>, body: Bytes) -> HttpResponse {
+    let req = if body.is_empty() {
+        StartFlowRequest::default()
+    } else {
+        match serde_json::from_slice::<StartFlowRequest>(&body) {
+            Ok(req) => req,
+            Err(e) => return error(StatusCode::BAD_REQUEST, e),
+        }
+    };
+    let req_flow = get_flow::Request {
+        user_id: User::default().id,
+        flow_id: *id,
+    };
+    let mut flow = match state.get_flow.call_ref(req_flow).await {
+        Ok(get_flow::Response { config }) => FlowConfig::new(config),
+        Err(get_flow::Error::NotFound(_)) => return error(StatusCode::NOT_FOUND, "flow not found"),
+        Err(e) => {
+            let message = format!("invalid flow config {id}: {e}");
+            return error(StatusCode::INTERNAL_SERVER_ERROR, message);
+        }
+    };
+    flow.ctx.environment.extend(req.environment);
+    if req.partial_config.is_some() {
+        flow.partial_config = req.partial_config;
+    }
+
+    let flow_run_id = FlowRunId::new_v4();
+    let token = CancellationToken::new();
+    let (tx, rx) = mpsc::unbounded();
+    state.runs.insert(flow_run_id, token.clone(), rx);
+    let runner = FlowRunner::new(flow)
+        .signer(state.signer.clone())
+        .secrets(state.secrets.clone())
+        .get_flow(state.get_flow.clone())
+        .run_store(state.runs.store().svc(32))
+        .cancel(Cancellation::new(token, None))
+        .breakers(state.breakers.clone())
+        .events(tx);
+    actix_web::rt::spawn(runner.run(flow_run_id, req.inputs));
+
+    HttpResponse::Ok().json(StartFlowResponse { flow_run_id })
+}
+
+#[get("/flow/events/{flow_run_id}")]
+async fn flow_events(state: web::Data<State>, id: web::Path<FlowRunId>) -> HttpResponse {
+    let Some(rx) = state.runs.subscribe(&id) else {
+        return run_not_found();
+    };
+    let stream = rx.map(|event| {
+        let json = serde_json::to_string(&event).expect("FlowEvent is always serializable");
+        Ok::<_, actix_web::Error>(Bytes::from(format!("data: {json}\n\n")))
+    });
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream)
+}
+
+#[get("/flow/ws/{flow_run_id}")]
+async fn flow_ws(
+    state: web::Data<State>,
+    id: web::Path<FlowRunId>,
+    req: HttpRequest,
+    body: web::Payload,
+) -> actix_web::Result<HttpResponse> {
+    let Some(mut rx) = state.runs.subscribe(&id) else {
+        return Ok(run_not_found());
+    };
+    let (response, mut session, mut messages) = actix_ws::handle(&req, body)?;
+    actix_web::rt::spawn(async move {
+        loop {
+            tokio::select! {
+                event = rx.next() => match event {
+                    Some(event) => {
+                        let json =
+                            serde_json::to_string(&event).expect("FlowEvent is always serializable");
+                        if session.text(json).await.is_err() {
+                            return;
+                        }
+                    }
+                    None => break,
+                },
+                message = messages.next() => match message {
+                    Some(Ok(actix_ws::Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(actix_ws::Message::Close(_))) => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) | None => return,
+                },
+            }
+        }
+        session.close(None).await.ok();
+    });
+    Ok(response)
+}
+
+#[get("/flow/output/{flow_run_id}")]
+async fn flow_output(state: web::Data<State>, id: web::Path<FlowRunId>) -> HttpResponse {
+    let Some(rx) = state.runs.subscribe(&id) else {
+        return run_not_found();
+    };
+    let result = rx
+        .filter_map(|event| {
+            std::future::ready(match event {
+                FlowEvent::FlowFinish(result) => Some(result),
+                _ => None,
+            })
+        })
+        .next()
+        .await;
+    match result {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "flow run ended without result",
+        ),
+    }
+}
+
+#[post("/flow/stop/{flow_run_id}")]
+async fn stop_flow(state: web::Data<State>, id: web::Path<FlowRunId>) -> HttpResponse {
+    if state.runs.stop(&id) {
+        HttpResponse::Ok().json(serde_json::json!({}))
+    } else {
+        run_not_found()
+    }
+}
+
+#[actix_web::main]
+async fn main() -> anyhow::Result<()> {
+    let mut bind = "127.0.0.1:8080".to_owned();
+    let mut flows = std::path::PathBuf::from("flows");
+    let mut keypairs = Vec::new();
+    let mut secrets = SecretsConfig::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("missing value of {arg}"))
+        };
+        match arg.as_str() {
+            "--bind" => bind = value()?,
+            "--flows" => flows = value()?.into(),
+            "--keypair" => {
+                let path = value()?;
+                let keypair =
+                    read_keypair_file(&path).map_err(|e| anyhow::anyhow!("reading {path}: {e}"))?;
+                keypairs.push(keypair);
+            }
+            "--secrets" => {
+                let path = value()?;
+                let json = std::fs::read_to_string(&path)
+                    .map_err(|e| anyhow::anyhow!("reading {path}: {e}"))?;
+                secrets = serde_json::from_str(&json)
+                    .map_err(|e| anyhow::anyhow!("parsing {path}: {e}"))?;
+            }
+            _ => anyhow::bail!(
+                "unknown argument: {arg}\n\
+                usage: flow-server [--bind <ADDR>] [--flows <DIR>] [--keypair <FILE>]... \
+                [--secrets <FILE>]"
+            ),
+        }
+    }
+
+    // forward `tracing` events of nodes to their event streams
+    tracing_subscriber::registry()
+        .with(flow_lib::events::TracingLayer)
+        .init();
+
+    let signer = if keypairs.is_empty() {
+        signer::unimplemented_svc()
+    } else {
+        signer::keypair_svc(keypairs, 32)
+    };
+    let state = web::Data::new(State {
+        get_flow: get_flow::from_dir(flows, 32),
+        signer,
+        secrets: secret_store::from_config(&secrets),
+        runs: Runs::default(),
+        breakers: Breakers::default(),
+    });
+
+    println!("listening on http://{bind}");
+    HttpServer::new(move || {
+        App::new()
+            .app_data(state.clone())
+            .service(start_flow)
+            .service(flow_events)
+            .service(flow_ws)
+            .service(flow_output)
+            .service(stop_flow)
+    })
+    .bind(&bind)?
+    .run()
+    .await?;
+    Ok(())
+}
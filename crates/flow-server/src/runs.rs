@@ -0,0 +1,93 @@
+//! Keep events of flow runs and broadcast them to subscribers.
+
+use flow_lib::{context::run_store::MemoryStore, runner::FlowEvent, FlowRunId};
+use futures::{channel::mpsc, StreamExt};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Default)]
+struct Run {
+    events: Vec<FlowEvent>,
+    subscribers: Vec<mpsc::UnboundedSender<FlowEvent>>,
+    finished: bool,
+    cancel: CancellationToken,
+}
+
+/// Runs started by this server, kept in memory until the process exits.
+#[derive(Clone, Default)]
+pub struct Runs {
+    runs: Arc<Mutex<HashMap<FlowRunId, Run>>>,
+    store: MemoryStore,
+}
+
+impl Runs {
+    /// Node outputs of finished runs, for partial runs.
+    pub fn store(&self) -> &MemoryStore {
+        &self.store
+    }
+
+    /// Register a new run, events received from `rx` are stored and sent to subscribers.
+    pub fn insert(
+        &self,
+        id: FlowRunId,
+        cancel: CancellationToken,
+        mut rx: mpsc::UnboundedReceiver<FlowEvent>,
+    ) {
+        self.runs.lock().unwrap().insert(
+            id,
+            Run {
+                cancel,
+                ..Run::default()
+            },
+        );
+        let runs = self.runs.clone();
+        let store = self.store.clone();
+        actix_web::rt::spawn(async move {
+            while let Some(event) = rx.next().await {
+                if let FlowEvent::FlowFinish(result) = &event {
+                    store.insert(id, result.node_outputs.clone());
+                }
+                let mut runs = runs.lock().unwrap();
+                let Some(run) = runs.get_mut(&id) else {
+                    break;
+                };
+                run.subscribers
+                    .retain(|tx| tx.unbounded_send(event.clone()).is_ok());
+                if matches!(event, FlowEvent::FlowFinish(_)) {
+                    run.finished = true;
+                    run.subscribers.clear();
+                }
+                run.events.push(event);
+            }
+        });
+    }
+
+    /// Get past and future events of a run, the stream ends after [`FlowEvent::FlowFinish`].
+    /// Returns `None` if the run is not found.
+    pub fn subscribe(&self, id: &FlowRunId) -> Option<mpsc::UnboundedReceiver<FlowEvent>> {
+        let mut runs = self.runs.lock().unwrap();
+        let run = runs.get_mut(id)?;
+        let (tx, rx) = mpsc::unbounded();
+        for event in &run.events {
+            tx.unbounded_send(event.clone()).ok();
+        }
+        if !run.finished {
+            run.subscribers.push(tx);
+        }
+        Some(rx)
+    }
+
+    /// Cancel a run, returns `false` if the run is not found.
+    pub fn stop(&self, id: &FlowRunId) -> bool {
+        match self.runs.lock().unwrap().get(id) {
+            Some(run) => {
+                run.cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
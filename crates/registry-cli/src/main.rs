@@ -0,0 +1,329 @@
+//! List, describe and run registered commands without a flow server.
+//!
+//! ```text
+//! registry list
+//! registry describe <NAME>
+//! registry run <NAME> [OPTIONS]
+//! ```
+//!
+//! Options of `run`:
+//! - `--inputs <JSON|@FILE>`: inputs as a JSON object, values can be in [`Value`]'s tagged form
+//!   (`{"amount": {"U": "10"}}`) or plain JSON (`{"amount": 10}`).
+//! - `--form <JSON|@FILE>`: form data of the node, read with
+//!   [`read_form_data`][flow_lib::command::CommandTrait::read_form_data].
+//! - `--config <FILE>`: a JSON [`ContextConfig`], defaults to devnet.
+//! - `--keypair <FILE>`: sign for this keypair when a command requests a wallet signature, can be
+//!   repeated. Files are in the format of `solana-keygen`.
+//! - `--secrets <FILE>`: a JSON [`SecretsBackend`][flow_lib::config::SecretsBackend], the
+//!   command can read all secrets of the backend.
+//! - `--flows <DIR>`: directory of flow configs for `interflow` nodes, flow `{id}` is read from
+//!   `{DIR}/{id}.json`.
+//! - `--dry-run`: sign and simulate transactions instead of sending them, the `signature` output
+//!   will be `null`.
+//! - `--tagged`: print outputs in [`Value`]'s tagged form.
+//! - `--record <FILE>`: write requests and the result of the command to a
+//!   [`RunLog`][flow_lib::record::RunLog] JSON file, keypairs are replaced by their public keys.
+//! - `--replay <FILE>`: serve RPC, HTTP and signature requests from a recorded log instead of the
+//!   network, keypair inputs given as public keys are replaced by dummy keypairs.
+//!
+//! Errors of the command are printed to stderr as a [`FlowError`].
+
+use anyhow::{anyhow, bail, Context as _};
+use cmds_solana as _;
+use flow_lib::{
+    command::{CommandDescription, CommandTrait},
+    config::{
+        client::{Extra, NodeData, TargetsForm},
+        SecretsBackend,
+    },
+    context::{execute, get_flow, get_jwt, signer, stream, CommandContext},
+    policy,
+    record::{self, Entry, Recorder, Replay, RunLog},
+    secret_store, CommandType, Context, ContextConfig, FlowError, User, Value, ValueSet,
+};
+use futures::StreamExt;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use std::{process::ExitCode, sync::Arc};
+use tower::ServiceExt;
+
+const USAGE: &str = "\
+usage:
+    registry list
+    registry describe <NAME>
+    registry run <NAME> [--inputs <JSON|@FILE>] [--form <JSON|@FILE>] [--config <FILE>]
+                        [--keypair <FILE>]... [--secrets <FILE>] [--flows <DIR>]
+                        [--dry-run] [--tagged] [--record <FILE>] [--replay <FILE>]";
+
+#[derive(Default)]
+struct RunArgs {
+    inputs: Option<String>,
+    form: Option<String>,
+    config: Option<String>,
+    keypairs: Vec<String>,
+    secrets: Option<String>,
+    flows: Option<String>,
+    dry_run: bool,
+    tagged: bool,
+    record: Option<String>,
+    replay: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let result = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["list"] => list(),
+        ["describe", name] => describe(name),
+        ["run", name, ref rest @ ..] => match parse_run_args(rest) {
+            Ok(args) => run(name, args).await,
+            Err(error) => Err(error),
+        },
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            match error.downcast_ref::<FlowError>() {
+                Some(error) => eprintln!("{}", serde_json::to_string_pretty(error).unwrap()),
+                None => eprintln!("error: {error:#}"),
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_run_args(args: &[&str]) -> anyhow::Result<RunArgs> {
+    let mut result = RunArgs::default();
+    let mut iter = args.iter();
+    while let Some(&arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .map(|s| s.to_string())
+                .ok_or_else(|| anyhow!("missing value of {arg}"))
+        };
+        match arg {
+            "--inputs" => result.inputs = Some(value()?),
+            "--form" => result.form = Some(value()?),
+            "--config" => result.config = Some(value()?),
+            "--keypair" => result.keypairs.push(value()?),
+            "--secrets" => result.secrets = Some(value()?),
+            "--flows" => result.flows = Some(value()?),
+            "--dry-run" => result.dry_run = true,
+            "--tagged" => result.tagged = true,
+            "--record" => result.record = Some(value()?),
+            "--replay" => result.replay = Some(value()?),
+            _ => bail!("unknown argument: {arg}\n{USAGE}"),
+        }
+    }
+    Ok(result)
+}
+
+fn descriptions() -> Vec<&'static CommandDescription> {
+    let mut result = inventory::iter::<CommandDescription>().collect::<Vec<_>>();
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    result
+}
+
+fn build(name: &str, form_data: serde_json::Value) -> anyhow::Result<Box<dyn CommandTrait>> {
+    let d = descriptions()
+        .into_iter()
+        .find(|d| d.name == name)
+        .ok_or_else(|| anyhow!("command not found: {name}"))?;
+    let data = NodeData {
+        r#type: CommandType::Native,
+        node_id: name.to_owned(),
+        sources: Vec::new(),
+        targets: Vec::new(),
+        targets_form: TargetsForm {
+            form_data,
+            extra: Extra::default(),
+            wasm_bytes: None,
+        },
+        policy: None,
+    };
+    (d.fn_new)(&data).with_context(|| format!("failed to build {name}"))
+}
+
+fn list() -> anyhow::Result<()> {
+    for d in descriptions() {
+        println!("{}", d.name);
+    }
+    Ok(())
+}
+
+fn describe(name: &str) -> anyhow::Result<()> {
+    let cmd = build(name, serde_json::json!({}))?;
+    let json = serde_json::json!({
+        "name": cmd.name(),
+        "inputs": cmd.inputs(),
+        "outputs": cmd.outputs(),
+        "permissions": cmd.permissions(),
+        "instruction_info": cmd.instruction_info(),
+        "policy": cmd.policy(),
+    });
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+/// Read `arg` as JSON, or the content of a file if it starts with `@`.
+fn read_json(arg: &str) -> anyhow::Result<serde_json::Value> {
+    let text = match arg.strip_prefix('@') {
+        Some(path) => std::fs::read_to_string(path).with_context(|| format!("reading {path}"))?,
+        None => arg.to_owned(),
+    };
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// Parse inputs in the tagged form, or as plain JSON if that fails.
+fn parse_inputs(json: serde_json::Value) -> anyhow::Result<ValueSet> {
+    if !json.is_object() {
+        bail!("inputs must be a JSON object");
+    }
+    match serde_json::from_value::<ValueSet>(json.clone()) {
+        Ok(inputs) => Ok(inputs),
+        Err(_) => match Value::from(json) {
+            Value::Map(inputs) => Ok(inputs),
+            _ => unreachable!(),
+        },
+    }
+}
+
+fn print_outputs(outputs: ValueSet, tagged: bool) -> anyhow::Result<()> {
+    let json = if tagged {
+        serde_json::to_value(&outputs)?
+    } else {
+        serde_json::Value::from(Value::Map(outputs))
+    };
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+/// Sign and simulate instructions, print simulation logs to stderr.
+fn dry_run_svc(ctx: &Context) -> execute::Svc {
+    let rpc = ctx.solana_client.clone();
+    let signer = ctx.signer.clone();
+    let user_id = ctx.user.id;
+    let handle = move |req: execute::Request| {
+        let rpc = rpc.clone();
+        let signer = signer.clone();
+        async move {
+            let result = req.instructions.simulate(&rpc, signer, user_id).await?;
+            for log in result.logs.unwrap_or_default() {
+                eprintln!("simulation: {log}");
+            }
+            if let Some(units) = result.units_consumed {
+                eprintln!("simulation: {units} compute units consumed");
+            }
+            Ok::<_, execute::Error>(execute::Response { signature: None })
+        }
+    };
+    execute::Svc::from_service(tower::service_fn(handle), execute::Error::worker, 1)
+}
+
+async fn run(name: &str, args: RunArgs) -> anyhow::Result<()> {
+    let form = match &args.form {
+        Some(form) => read_json(form)?,
+        None => serde_json::json!({}),
+    };
+    let cmd = build(name, form.clone())?;
+    let mut inputs = cmd.read_form_data(form);
+    if let Some(json) = &args.inputs {
+        inputs.extend(parse_inputs(read_json(json)?)?);
+    }
+
+    let cfg = match &args.config {
+        Some(path) => serde_json::from_str::<ContextConfig>(
+            &std::fs::read_to_string(path).with_context(|| format!("reading {path}"))?,
+        )
+        .with_context(|| format!("parsing {path}"))?,
+        None => ContextConfig::default(),
+    };
+    let keypairs = args
+        .keypairs
+        .iter()
+        .map(|path| read_keypair_file(path).map_err(|e| anyhow!("reading {path}: {e}")))
+        .collect::<Result<Vec<Keypair>, _>>()?;
+    let signer = if keypairs.is_empty() {
+        signer::unimplemented_svc()
+    } else {
+        signer::keypair_svc(keypairs, 1)
+    };
+
+    let mut ctx = Context::from_cfg(
+        &cfg,
+        User::default(),
+        signer,
+        get_jwt::unimplemented_svc(),
+        <_>::default(),
+    );
+    if let Some(path) = &args.secrets {
+        let backend = serde_json::from_str::<SecretsBackend>(
+            &std::fs::read_to_string(path).with_context(|| format!("reading {path}"))?,
+        )
+        .with_context(|| format!("parsing {path}"))?;
+        ctx.secrets = secret_store::from_backend(&backend);
+    }
+    if let Some(dir) = &args.flows {
+        ctx.get_flow = get_flow::from_dir(dir.into(), 1);
+    }
+    let recorder = Recorder::default();
+    let keypairs = record::Keypairs::new(&*cmd);
+    if let Some(path) = &args.replay {
+        let log = serde_json::from_str::<RunLog>(
+            &std::fs::read_to_string(path).with_context(|| format!("reading {path}"))?,
+        )
+        .with_context(|| format!("parsing {path}"))?;
+        let replay = Replay::new(log);
+        inputs = replay.restore_keypairs(&keypairs, inputs);
+        record::Mode::Replay(replay).install(&mut ctx);
+    } else if args.record.is_some() {
+        record::Mode::Record(recorder.clone()).install(&mut ctx);
+    }
+    let (stream, mut emitted) = stream::channel(16);
+    tokio::spawn(async move {
+        while let Some(output) = emitted.next().await {
+            let json = serde_json::Value::from(Value::Map(output));
+            eprintln!("emit: {json}");
+        }
+    });
+    ctx.command = Some(CommandContext {
+        svc: if args.dry_run {
+            dry_run_svc(&ctx)
+        } else {
+            execute::simple(&ctx, 1)
+        },
+        stream,
+        flow_run_id: uuid::Uuid::new_v4(),
+        node_id: uuid::Uuid::nil(),
+        times: 0,
+        call_depth: 0,
+    });
+
+    let policy = cmd.policy();
+    let svc = policy::service(Arc::from(cmd), &policy);
+    let result = svc
+        .oneshot(policy::Request {
+            ctx,
+            inputs: inputs.clone(),
+        })
+        .await
+        .map_err(|error| FlowError::from_command_error(&error));
+    if let Some(path) = &args.record {
+        recorder.push(Entry::Command {
+            node_id: uuid::Uuid::nil(),
+            times: 0,
+            name: name.to_owned(),
+            inputs: keypairs.redact_inputs(&inputs),
+            result: result
+                .as_ref()
+                .map(|outputs| keypairs.redact_outputs(outputs))
+                .map_err(Clone::clone),
+        });
+        let json = serde_json::to_string_pretty(&recorder.log())?;
+        std::fs::write(path, json).with_context(|| format!("writing {path}"))?;
+    }
+    print_outputs(result?, args.tagged)
+}
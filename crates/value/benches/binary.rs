@@ -0,0 +1,61 @@
+//! Compare size and speed of `value::binary`, `value::binary::cbor` and the JSON form.
+//!
+//! ```sh
+//! cargo bench -p value --bench binary
+//! ```
+
+use std::time::{Duration, Instant};
+use value::Value;
+
+/// Something like the output of a large holder snapshot.
+fn sample() -> Value {
+    let rows = (0..10_000u64)
+        .map(|i| {
+            let mut owner = [0u8; 32];
+            owner[..8].copy_from_slice(&i.to_le_bytes());
+            Value::Map(value::map! {
+                "owner" => owner,
+                "amount" => i * 1_000_000_007,
+                "ui_amount" => rust_decimal::Decimal::new(i as i64 * 1_000_000_007, 9),
+                "name" => format!("holder #{i}"),
+                "frozen" => i % 7 == 0,
+            })
+        })
+        .collect();
+    Value::Array(rows)
+}
+
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    const ITERATIONS: u32 = 20;
+    f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(f());
+    }
+    let time: Duration = start.elapsed() / ITERATIONS;
+    println!("{name:<16} {time:>12.3?}");
+}
+
+fn main() {
+    let value = sample();
+
+    let json = serde_json::to_vec(&value).unwrap();
+    let binary = value::binary::to_vec(&value);
+    let cbor = value::binary::cbor::to_vec(&value);
+    println!("{:<16} {:>12}", "json bytes", json.len());
+    println!("{:<16} {:>12}", "binary bytes", binary.len());
+    println!("{:<16} {:>12}", "cbor bytes", cbor.len());
+
+    bench("json encode", || serde_json::to_vec(&value).unwrap());
+    bench("binary encode", || value::binary::to_vec(&value));
+    bench("cbor encode", || value::binary::cbor::to_vec(&value));
+    bench("json decode", || {
+        serde_json::from_slice::<Value>(&json).unwrap()
+    });
+    bench("binary decode", || {
+        value::binary::from_slice(&binary).unwrap()
+    });
+    bench("cbor decode", || {
+        value::binary::cbor::from_slice(&cbor).unwrap()
+    });
+}
@@ -0,0 +1,345 @@
+//! Compact binary encoding of [`Value`], for storing large values and passing them between
+//! processes. See [`cbor`] for interoperability with other languages.
+//!
+//! Layout, all integers are little-endian:
+//!
+//! ```text
+//! data  := VERSION value
+//! value := tag:u8 payload
+//! ```
+//!
+//! | tag | variant   | payload                                             |
+//! |-----|-----------|-----------------------------------------------------|
+//! | 0   | `Null`    |                                                     |
+//! | 1   | `String`  | varint length, UTF-8 bytes                          |
+//! | 2   | `Bool`    | 0 or 1                                              |
+//! | 3   | `U64`     | varint                                              |
+//! | 4   | `I64`     | zigzag varint                                       |
+//! | 5   | `F64`     | 8 bytes                                             |
+//! | 6   | `Decimal` | 16 bytes of [`Decimal::serialize`]                  |
+//! | 7   | `I128`    | zigzag varint                                       |
+//! | 8   | `U128`    | varint                                              |
+//! | 9   | `B32`     | 32 bytes                                            |
+//! | 10  | `B64`     | 64 bytes                                            |
+//! | 11  | `Bytes`   | varint length, bytes                                |
+//! | 12  | `Array`   | varint length, values                               |
+//! | 13  | `Map`     | varint length, (varint length, UTF-8 key, value)... |
+//! | 14  | `EvmAddress` | 20 bytes                                         |
+//! | 15  | `U256`    | 32 bytes                                            |
+//! | 16  | `I256`    | 32 bytes, two's complement                          |
+//! | 17  | `Timestamp` | 8 bytes Unix seconds, 4 bytes nanoseconds         |
+//! | 18  | `Duration` | 8 bytes seconds, 4 bytes signed nanoseconds        |
+//!
+//! Tags are the indexes of [`Variant`]. Varints are unsigned LEB128.
+
+use crate::{value_type::Variant, Decimal, Map, Value};
+use thiserror::Error as ThisError;
+
+pub mod cbor;
+
+/// Version of the format, the first byte of encoded data.
+pub const VERSION: u8 = 1;
+
+/// Maximum nesting of arrays and maps when decoding.
+pub(crate) const MAX_DEPTH: usize = 128;
+
+#[derive(ThisError, Debug)]
+pub enum Error {
+    #[error("unexpected end of data")]
+    UnexpectedEnd,
+    #[error("unsupported version {0}")]
+    UnsupportedVersion(u8),
+    #[error("invalid tag {0}")]
+    InvalidTag(u64),
+    #[error("invalid {0}")]
+    Invalid(&'static str),
+    #[error("integer overflow")]
+    Overflow,
+    #[error("invalid UTF-8: {0}")]
+    Utf8(#[from] std::str::Utf8Error),
+    #[error("nesting is deeper than {MAX_DEPTH}")]
+    TooDeep,
+    #[error("{0} bytes left after decoding")]
+    TrailingBytes(usize),
+}
+
+/// Encode a [`Value`].
+pub fn to_vec(value: &Value) -> Vec<u8> {
+    let mut data = vec![VERSION];
+    write(&mut data, value);
+    data
+}
+
+/// Decode a [`Value`] encoded by [`to_vec`].
+pub fn from_slice(data: &[u8]) -> Result<Value, Error> {
+    let mut reader = Reader { data, depth: 0 };
+    match reader.byte()? {
+        VERSION => {}
+        version => return Err(Error::UnsupportedVersion(version)),
+    }
+    let value = reader.value()?;
+    if !reader.data.is_empty() {
+        return Err(Error::TrailingBytes(reader.data.len()));
+    }
+    Ok(value)
+}
+
+fn write_varint(data: &mut Vec<u8>, mut n: u128) {
+    while n >= 0x80 {
+        data.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    data.push(n as u8);
+}
+
+fn zigzag(n: i128) -> u128 {
+    ((n << 1) ^ (n >> 127)) as u128
+}
+
+fn unzigzag(n: u128) -> i128 {
+    (n >> 1) as i128 ^ -((n & 1) as i128)
+}
+
+fn write_bytes(data: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(data, bytes.len() as u128);
+    data.extend_from_slice(bytes);
+}
+
+fn write(data: &mut Vec<u8>, value: &Value) {
+    data.push(value.kind() as u8);
+    match value {
+        Value::Null => {}
+        Value::String(s) => write_bytes(data, s.as_bytes()),
+        Value::Bool(b) => data.push(*b as u8),
+        Value::U64(n) => write_varint(data, (*n).into()),
+        Value::I64(n) => write_varint(data, zigzag((*n).into())),
+        Value::F64(n) => data.extend(n.to_le_bytes()),
+        Value::Decimal(d) => data.extend(d.serialize()),
+        Value::I128(n) => write_varint(data, zigzag(*n)),
+        Value::U128(n) => write_varint(data, *n),
+        Value::B32(b) => data.extend(b),
+        Value::B64(b) => data.extend(b),
+        Value::Bytes(b) => write_bytes(data, b),
+        Value::EvmAddress(a) => data.extend(a),
+        Value::U256(n) => data.extend(n.to_le_bytes()),
+        Value::I256(n) => data.extend(n.to_le_bytes()),
+        Value::Timestamp(t) => data.extend(crate::timestamp::to_bytes(t)),
+        Value::Duration(d) => data.extend(crate::duration::to_bytes(d)),
+        Value::Array(a) => {
+            write_varint(data, a.len() as u128);
+            for v in a {
+                write(data, v);
+            }
+        }
+        Value::Map(m) => {
+            write_varint(data, m.len() as u128);
+            for (k, v) in m {
+                write_bytes(data, k.as_bytes());
+                write(data, v);
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    /// Remaining data.
+    data: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err(Error::UnexpectedEnd);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn varint(&mut self) -> Result<u128, Error> {
+        let mut n = 0u128;
+        for shift in (0..128).step_by(7) {
+            let byte = self.byte()?;
+            let bits = u128::from(byte & 0x7f);
+            if shift > 0 && bits >> (128 - shift) != 0 {
+                return Err(Error::Overflow);
+            }
+            n |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(Error::Overflow)
+    }
+
+    fn len(&mut self) -> Result<usize, Error> {
+        usize::try_from(self.varint()?).map_err(|_| Error::Overflow)
+    }
+
+    fn str(&mut self) -> Result<&'a str, Error> {
+        let len = self.len()?;
+        Ok(std::str::from_utf8(self.take(len)?)?)
+    }
+
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error::TooDeep);
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        let tag = self.byte()?;
+        let variant = Variant::from_u32(tag.into()).ok_or(Error::InvalidTag(tag.into()))?;
+        Ok(match variant {
+            Variant::Null => Value::Null,
+            Variant::String => Value::String(self.str()?.to_owned()),
+            Variant::Bool => match self.byte()? {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                _ => return Err(Error::Invalid("boolean")),
+            },
+            Variant::U64 => Value::U64(self.varint()?.try_into().map_err(|_| Error::Overflow)?),
+            Variant::I64 => Value::I64(
+                unzigzag(self.varint()?)
+                    .try_into()
+                    .map_err(|_| Error::Overflow)?,
+            ),
+            Variant::F64 => Value::F64(f64::from_le_bytes(self.array()?)),
+            Variant::Decimal => Value::Decimal(decimal(self.array()?)?),
+            Variant::I128 => Value::I128(unzigzag(self.varint()?)),
+            Variant::U128 => Value::U128(self.varint()?),
+            Variant::B32 => Value::B32(self.array()?),
+            Variant::B64 => Value::B64(self.array()?),
+            Variant::EvmAddress => Value::EvmAddress(self.array()?),
+            Variant::U256 => Value::U256(ethnum::U256::from_le_bytes(self.array()?)),
+            Variant::I256 => Value::I256(ethnum::I256::from_le_bytes(self.array()?)),
+            Variant::Timestamp => Value::Timestamp(
+                crate::timestamp::from_bytes(&self.array()?)
+                    .map_err(|_| Error::Invalid("timestamp"))?,
+            ),
+            Variant::Duration => Value::Duration(
+                crate::duration::from_bytes(&self.array()?)
+                    .map_err(|_| Error::Invalid("duration"))?,
+            ),
+            Variant::Bytes => {
+                let len = self.len()?;
+                Value::Bytes(bytes::Bytes::copy_from_slice(self.take(len)?))
+            }
+            Variant::Array => self.nested(|r| {
+                let len = r.len()?;
+                let mut array = Vec::with_capacity(len.min(r.data.len()));
+                for _ in 0..len {
+                    array.push(r.value()?);
+                }
+                Ok(Value::Array(array))
+            })?,
+            Variant::Map => self.nested(|r| {
+                let len = r.len()?;
+                let mut map = Map::with_capacity(len.min(r.data.len()));
+                for _ in 0..len {
+                    let key = r.str()?.to_owned();
+                    map.insert(key, r.value()?);
+                }
+                Ok(Value::Map(map))
+            })?,
+        })
+    }
+}
+
+/// Check flags of [`Decimal::serialize`] before deserializing.
+fn decimal(bytes: [u8; 16]) -> Result<Decimal, Error> {
+    if bytes[0] != 0 || bytes[1] != 0 || bytes[2] > 28 || bytes[3] & 0x7f != 0 {
+        return Err(Error::Invalid("decimal"));
+    }
+    Ok(Decimal::deserialize(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    pub(crate) fn sample() -> Value {
+        Value::Map(crate::map! {
+            "null" => Value::Null,
+            "string" => "hello",
+            "bool" => true,
+            "u64" => u64::MAX,
+            "i64" => i64::MIN,
+            "f64" => -1.5f64,
+            "decimal" => dec!(-3.1415926535897932384626433832),
+            "i128" => i128::MIN,
+            "u128" => u128::MAX,
+            "b32" => [1u8; 32],
+            "b64" => [2u8; 64],
+            "bytes" => Value::Bytes(vec![1, 2, 3].into()),
+            "array" => Value::Array(vec![0u64.into(), (-1i64).into(), Value::Array(vec![])]),
+            "map" => crate::map! { "" => Value::Map(Map::new()) },
+            "evm_address" => [3u8; 20],
+            "u256" => ethnum::U256::MAX,
+            "i256" => ethnum::I256::MIN,
+            "timestamp" => crate::timestamp::from_unix_nanos(-1, 500).unwrap(),
+            "duration" => chrono::Duration::nanoseconds(-1_500_000_001),
+        })
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let value = sample();
+        let data = to_vec(&value);
+        assert_eq!(from_slice(&data).unwrap(), value);
+
+        assert_eq!(to_vec(&Value::U64(300)), [VERSION, 3, 0xac, 0x02]);
+        assert_eq!(to_vec(&Value::I64(-1)), [VERSION, 4, 1]);
+        assert_eq!(to_vec(&Value::String("a".into())), [VERSION, 1, 1, b'a']);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(matches!(
+            from_slice(&[2, 0]),
+            Err(Error::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            from_slice(&[VERSION, 19]),
+            Err(Error::InvalidTag(19))
+        ));
+        // 2e9 nanoseconds
+        assert!(matches!(
+            from_slice(&[VERSION, 17, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x94, 0x35, 0x77]),
+            Err(Error::Invalid("timestamp"))
+        ));
+        assert!(matches!(
+            from_slice(&[VERSION, 1, 5, b'a']),
+            Err(Error::UnexpectedEnd)
+        ));
+        assert!(matches!(
+            from_slice(&[VERSION, 0, 0]),
+            Err(Error::TrailingBytes(1))
+        ));
+        let mut u64_overflow = vec![VERSION, 3];
+        u64_overflow.extend([0xff; 9]);
+        u64_overflow.push(0x02);
+        assert!(matches!(from_slice(&u64_overflow), Err(Error::Overflow)));
+
+        let mut deep = vec![VERSION];
+        for _ in 0..=MAX_DEPTH {
+            deep.extend([Variant::Array as u8, 1]);
+        }
+        deep.push(0);
+        assert!(matches!(from_slice(&deep), Err(Error::TooDeep)));
+    }
+}
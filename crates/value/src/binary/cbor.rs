@@ -0,0 +1,671 @@
+//! Lossless mapping between [`Value`] and [CBOR](https://www.rfc-editor.org/rfc/rfc8949).
+//!
+//! | variant   | CBOR                                                          |
+//! |-----------|---------------------------------------------------------------|
+//! | `Null`    | `null`, `undefined` is also decoded as `Null`                 |
+//! | `String`  | text string                                                   |
+//! | `Bool`    | `true` or `false`                                             |
+//! | `U64`     | unsigned integer                                              |
+//! | `I64`     | negative integer, or [`TAG_I64`] + unsigned integer           |
+//! | `F64`     | double-precision float, half and single are also decoded      |
+//! | `Decimal` | tag 4 (decimal fraction): `[-scale, mantissa]`                |
+//! | `I128`    | tag 3 (negative bignum), or [`TAG_I128`] + tag 2              |
+//! | `U128`    | tag 2 (unsigned bignum)                                       |
+//! | `B32`     | [`TAG_B32`] + byte string                                     |
+//! | `B64`     | [`TAG_B64`] + byte string                                     |
+//! | `Bytes`   | byte string                                                   |
+//! | `Array`   | array                                                         |
+//! | `Map`     | map with text string keys                                     |
+//! | `EvmAddress` | [`TAG_EVM_ADDRESS`] + byte string                          |
+//! | `U256`    | [`TAG_U256`] + tag 2                                          |
+//! | `I256`    | [`TAG_I256`] + tag 2 or tag 3                                 |
+//! | `Timestamp` | tag 0 (RFC 3339 string), tag 1 (epoch seconds) is also decoded |
+//! | `Duration` | [`TAG_DURATION`] + `[seconds, nanoseconds]`                  |
+//!
+//! Private tags are used where CBOR has no type to keep the [`Value`] variant, decoders that do
+//! not know them will see the untagged data. Unknown tags are ignored when decoding, and
+//! negative integers that do not fit in `i64` are decoded as `I128`.
+
+use super::{Error, MAX_DEPTH};
+use crate::{value_type::Variant, Decimal, Map, Value};
+
+const TAG_BASE: u64 = 0x5356_0000;
+
+/// Non-negative [`Value::I64`].
+pub const TAG_I64: u64 = TAG_BASE + Variant::I64 as u64;
+/// Non-negative [`Value::I128`], wrapping an unsigned bignum.
+pub const TAG_I128: u64 = TAG_BASE + Variant::I128 as u64;
+/// [`Value::B32`].
+pub const TAG_B32: u64 = TAG_BASE + Variant::B32 as u64;
+/// [`Value::B64`].
+pub const TAG_B64: u64 = TAG_BASE + Variant::B64 as u64;
+/// [`Value::EvmAddress`].
+pub const TAG_EVM_ADDRESS: u64 = TAG_BASE + Variant::EvmAddress as u64;
+/// [`Value::U256`], wrapping an unsigned bignum.
+pub const TAG_U256: u64 = TAG_BASE + Variant::U256 as u64;
+/// [`Value::I256`], wrapping a bignum.
+pub const TAG_I256: u64 = TAG_BASE + Variant::I256 as u64;
+/// [`Value::Duration`], wrapping an array of seconds and nanoseconds with the same sign.
+pub const TAG_DURATION: u64 = TAG_BASE + Variant::Duration as u64;
+
+const TAG_DATE_TIME: u64 = 0;
+const TAG_EPOCH: u64 = 1;
+const TAG_POSITIVE_BIGNUM: u64 = 2;
+const TAG_NEGATIVE_BIGNUM: u64 = 3;
+const TAG_DECIMAL_FRACTION: u64 = 4;
+
+const UINT: u8 = 0;
+const NINT: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const SIMPLE: u8 = 7;
+
+const FALSE: u8 = 0xf4;
+const TRUE: u8 = 0xf5;
+const NULL: u8 = 0xf6;
+const UNDEFINED: u8 = 0xf7;
+const F16: u8 = 0xf9;
+const F32: u8 = 0xfa;
+const F64: u8 = 0xfb;
+const BREAK: u8 = 0xff;
+const INDEFINITE: u8 = 31;
+
+/// Encode a [`Value`] to CBOR.
+pub fn to_vec(value: &Value) -> Vec<u8> {
+    let mut data = Vec::new();
+    write(&mut data, value);
+    data
+}
+
+/// Decode a CBOR data item to [`Value`].
+pub fn from_slice(data: &[u8]) -> Result<Value, Error> {
+    let mut reader = Reader { data, depth: 0 };
+    let value = reader.value()?;
+    if !reader.data.is_empty() {
+        return Err(Error::TrailingBytes(reader.data.len()));
+    }
+    Ok(value)
+}
+
+fn head(data: &mut Vec<u8>, major: u8, n: u64) {
+    let major = major << 5;
+    if n < 24 {
+        data.push(major | n as u8);
+    } else if let Ok(n) = u8::try_from(n) {
+        data.extend([major | 24, n]);
+    } else if let Ok(n) = u16::try_from(n) {
+        data.push(major | 25);
+        data.extend(n.to_be_bytes());
+    } else if let Ok(n) = u32::try_from(n) {
+        data.push(major | 26);
+        data.extend(n.to_be_bytes());
+    } else {
+        data.push(major | 27);
+        data.extend(n.to_be_bytes());
+    }
+}
+
+fn bytes(data: &mut Vec<u8>, major: u8, bytes: &[u8]) {
+    head(data, major, bytes.len() as u64);
+    data.extend_from_slice(bytes);
+}
+
+/// Bignum of big-endian bytes `be`.
+fn bignum(data: &mut Vec<u8>, tag: u64, be: &[u8]) {
+    head(data, TAG, tag);
+    let zeros = be.iter().take_while(|b| **b == 0).count();
+    bytes(data, BYTES, &be[zeros..]);
+}
+
+/// Integer or bignum, used for mantissas of decimals.
+fn integer(data: &mut Vec<u8>, n: i128) {
+    match (u64::try_from(n), n < 0) {
+        (Ok(n), _) => head(data, UINT, n),
+        (Err(_), false) => bignum(data, TAG_POSITIVE_BIGNUM, &n.to_be_bytes()),
+        (Err(_), true) => {
+            let n = (-1 - n) as u128;
+            match u64::try_from(n) {
+                Ok(n) => head(data, NINT, n),
+                Err(_) => bignum(data, TAG_NEGATIVE_BIGNUM, &n.to_be_bytes()),
+            }
+        }
+    }
+}
+
+fn write(data: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => data.push(NULL),
+        Value::String(s) => bytes(data, TEXT, s.as_bytes()),
+        Value::Bool(b) => data.push(if *b { TRUE } else { FALSE }),
+        Value::U64(n) => head(data, UINT, *n),
+        Value::I64(n) => {
+            if *n < 0 {
+                head(data, NINT, (-1 - *n) as u64);
+            } else {
+                head(data, TAG, TAG_I64);
+                head(data, UINT, *n as u64);
+            }
+        }
+        Value::F64(n) => {
+            data.push(F64);
+            data.extend(n.to_be_bytes());
+        }
+        Value::Decimal(d) => {
+            head(data, TAG, TAG_DECIMAL_FRACTION);
+            head(data, ARRAY, 2);
+            integer(data, -i128::from(d.scale()));
+            integer(data, d.mantissa());
+        }
+        Value::I128(n) => {
+            if *n < 0 {
+                bignum(
+                    data,
+                    TAG_NEGATIVE_BIGNUM,
+                    &((-1 - *n) as u128).to_be_bytes(),
+                );
+            } else {
+                head(data, TAG, TAG_I128);
+                bignum(data, TAG_POSITIVE_BIGNUM, &n.to_be_bytes());
+            }
+        }
+        Value::U128(n) => bignum(data, TAG_POSITIVE_BIGNUM, &n.to_be_bytes()),
+        Value::B32(b) => {
+            head(data, TAG, TAG_B32);
+            bytes(data, BYTES, b);
+        }
+        Value::B64(b) => {
+            head(data, TAG, TAG_B64);
+            bytes(data, BYTES, b);
+        }
+        Value::Bytes(b) => bytes(data, BYTES, b),
+        Value::EvmAddress(a) => {
+            head(data, TAG, TAG_EVM_ADDRESS);
+            bytes(data, BYTES, a);
+        }
+        Value::U256(n) => {
+            head(data, TAG, TAG_U256);
+            bignum(data, TAG_POSITIVE_BIGNUM, &n.to_be_bytes());
+        }
+        Value::I256(n) => {
+            head(data, TAG, TAG_I256);
+            if n.is_negative() {
+                bignum(data, TAG_NEGATIVE_BIGNUM, &(-1 - *n).to_be_bytes());
+            } else {
+                bignum(data, TAG_POSITIVE_BIGNUM, &n.to_be_bytes());
+            }
+        }
+        Value::Timestamp(t) => {
+            head(data, TAG, TAG_DATE_TIME);
+            bytes(data, TEXT, crate::timestamp::format(t).as_bytes());
+        }
+        Value::Duration(d) => {
+            let (secs, nanos) = crate::duration::to_secs_nanos(d);
+            head(data, TAG, TAG_DURATION);
+            head(data, ARRAY, 2);
+            integer(data, secs.into());
+            integer(data, nanos.into());
+        }
+        Value::Array(a) => {
+            head(data, ARRAY, a.len() as u64);
+            for v in a {
+                write(data, v);
+            }
+        }
+        Value::Map(m) => {
+            head(data, MAP, m.len() as u64);
+            for (k, v) in m {
+                bytes(data, TEXT, k.as_bytes());
+                write(data, v);
+            }
+        }
+    }
+}
+
+/// Integer read from CBOR, before it is converted to a [`Value`] variant.
+enum Integer {
+    Unsigned(u128),
+    /// `-1 - n`
+    Negative(u128),
+}
+
+impl Integer {
+    fn to_i128(&self) -> Result<i128, Error> {
+        match self {
+            Integer::Unsigned(n) => i128::try_from(*n).map_err(|_| Error::Overflow),
+            Integer::Negative(n) => i128::try_from(*n)
+                .map(|n| -1 - n)
+                .map_err(|_| Error::Overflow),
+        }
+    }
+}
+
+struct Reader<'a> {
+    /// Remaining data.
+    data: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err(Error::UnexpectedEnd);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn peek(&self) -> Result<u8, Error> {
+        self.data.first().copied().ok_or(Error::UnexpectedEnd)
+    }
+
+    /// Read the argument of a data item, `None` for indefinite length.
+    fn argument(&mut self, info: u8) -> Result<Option<u64>, Error> {
+        Ok(Some(match info {
+            0..=23 => info.into(),
+            24 => self.byte()?.into(),
+            25 => u16::from_be_bytes(self.array()?).into(),
+            26 => u32::from_be_bytes(self.array()?).into(),
+            27 => u64::from_be_bytes(self.array()?),
+            INDEFINITE => return Ok(None),
+            _ => return Err(Error::Invalid("additional information")),
+        }))
+    }
+
+    fn len(&mut self, info: u8) -> Result<Option<usize>, Error> {
+        self.argument(info)?
+            .map(|n| usize::try_from(n).map_err(|_| Error::Overflow))
+            .transpose()
+    }
+
+    fn definite(&mut self, info: u8) -> Result<u64, Error> {
+        self.argument(info)?
+            .ok_or(Error::Invalid("indefinite length"))
+    }
+
+    /// Byte or text string, indefinite length strings are concatenated.
+    fn string(&mut self, major: u8, info: u8) -> Result<Vec<u8>, Error> {
+        match self.len(info)? {
+            Some(len) => Ok(self.take(len)?.to_vec()),
+            None => {
+                let mut data = Vec::new();
+                loop {
+                    let initial = self.byte()?;
+                    if initial == BREAK {
+                        break Ok(data);
+                    }
+                    if initial >> 5 != major {
+                        break Err(Error::Invalid("chunk of indefinite length string"));
+                    }
+                    let len = self.len(initial & 0x1f)?;
+                    let len = len.ok_or(Error::Invalid("chunk of indefinite length string"))?;
+                    data.extend_from_slice(self.take(len)?);
+                }
+            }
+        }
+    }
+
+    fn text(&mut self, info: u8) -> Result<String, Error> {
+        let bytes = self.string(TEXT, info)?;
+        String::from_utf8(bytes).map_err(|e| Error::Utf8(e.utf8_error()))
+    }
+
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error::TooDeep);
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Call `f` for each element of an array or a map, until `len` or a break.
+    fn elements(
+        &mut self,
+        len: Option<usize>,
+        mut f: impl FnMut(&mut Self) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        self.nested(|r| match len {
+            Some(len) => (0..len).try_for_each(|_| f(r)),
+            None => loop {
+                if r.peek()? == BREAK {
+                    r.data = &r.data[1..];
+                    break Ok(());
+                }
+                f(r)?;
+            },
+        })
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        let initial = self.byte()?;
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        Ok(match major {
+            UINT => Value::U64(self.definite(info)?),
+            NINT => {
+                let n = self.definite(info)?;
+                match i64::try_from(n) {
+                    Ok(n) => Value::I64(-1 - n),
+                    Err(_) => Value::I128(-1 - i128::from(n)),
+                }
+            }
+            BYTES => Value::Bytes(self.string(BYTES, info)?.into()),
+            TEXT => Value::String(self.text(info)?),
+            ARRAY => {
+                let len = self.len(info)?;
+                let mut array = Vec::with_capacity(len.unwrap_or(0).min(self.data.len()));
+                self.elements(len, |r| {
+                    array.push(r.value()?);
+                    Ok(())
+                })?;
+                Value::Array(array)
+            }
+            MAP => {
+                let len = self.len(info)?;
+                let mut map = Map::with_capacity(len.unwrap_or(0).min(self.data.len()));
+                self.elements(len, |r| {
+                    let initial = r.byte()?;
+                    if initial >> 5 != TEXT {
+                        return Err(Error::Invalid("map key, keys must be text strings"));
+                    }
+                    let key = r.text(initial & 0x1f)?;
+                    map.insert(key, r.value()?);
+                    Ok(())
+                })?;
+                Value::Map(map)
+            }
+            TAG => {
+                let tag = self.definite(info)?;
+                self.tagged(tag)?
+            }
+            SIMPLE => match initial {
+                FALSE => Value::Bool(false),
+                TRUE => Value::Bool(true),
+                NULL | UNDEFINED => Value::Null,
+                F16 => Value::F64(f16_to_f64(u16::from_be_bytes(self.array()?))),
+                F32 => Value::F64(f32::from_be_bytes(self.array()?).into()),
+                F64 => Value::F64(f64::from_be_bytes(self.array()?)),
+                _ => return Err(Error::Invalid("simple value")),
+            },
+            _ => unreachable!("major type has 3 bits"),
+        })
+    }
+
+    /// Integer or bignum.
+    fn integer(&mut self) -> Result<Integer, Error> {
+        let initial = self.byte()?;
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        match major {
+            UINT => Ok(Integer::Unsigned(self.definite(info)?.into())),
+            NINT => Ok(Integer::Negative(self.definite(info)?.into())),
+            TAG => match self.definite(info)? {
+                tag @ (TAG_POSITIVE_BIGNUM | TAG_NEGATIVE_BIGNUM) => self.bignum(tag),
+                tag => Err(Error::InvalidTag(tag)),
+            },
+            _ => Err(Error::Invalid("integer")),
+        }
+    }
+
+    fn byte_string(&mut self, what: &'static str) -> Result<Vec<u8>, Error> {
+        let initial = self.byte()?;
+        if initial >> 5 != BYTES {
+            return Err(Error::Invalid(what));
+        }
+        self.string(BYTES, initial & 0x1f)
+    }
+
+    /// Content of a bignum as `N` big-endian bytes.
+    fn bignum_bytes<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let bytes = self.byte_string("bignum")?;
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+        let bytes = &bytes[start..];
+        if bytes.len() > N {
+            return Err(Error::Overflow);
+        }
+        let mut be = [0u8; N];
+        be[N - bytes.len()..].copy_from_slice(bytes);
+        Ok(be)
+    }
+
+    /// Content of a bignum with its tag.
+    fn bignum(&mut self, tag: u64) -> Result<Integer, Error> {
+        let n = u128::from_be_bytes(self.bignum_bytes()?);
+        Ok(if tag == TAG_POSITIVE_BIGNUM {
+            Integer::Unsigned(n)
+        } else {
+            Integer::Negative(n)
+        })
+    }
+
+    fn tagged(&mut self, tag: u64) -> Result<Value, Error> {
+        Ok(match tag {
+            TAG_POSITIVE_BIGNUM | TAG_NEGATIVE_BIGNUM => match self.bignum(tag)? {
+                Integer::Unsigned(n) => Value::U128(n),
+                n => Value::I128(n.to_i128()?),
+            },
+            TAG_DECIMAL_FRACTION => {
+                let initial = self.byte()?;
+                if initial != (ARRAY << 5 | 2) {
+                    return Err(Error::Invalid("decimal fraction"));
+                }
+                let exponent = self.integer()?.to_i128()?;
+                let mantissa = self.integer()?.to_i128()?;
+                let decimal = if exponent <= 0 {
+                    u32::try_from(-exponent)
+                        .ok()
+                        .and_then(|scale| Decimal::try_from_i128_with_scale(mantissa, scale).ok())
+                } else {
+                    u32::try_from(exponent)
+                        .ok()
+                        .and_then(|e| 10i128.checked_pow(e))
+                        .and_then(|m| mantissa.checked_mul(m))
+                        .and_then(|n| Decimal::try_from_i128_with_scale(n, 0).ok())
+                };
+                Value::Decimal(decimal.ok_or(Error::Overflow)?)
+            }
+            TAG_I64 => {
+                Value::I64(i64::try_from(self.integer()?.to_i128()?).map_err(|_| Error::Overflow)?)
+            }
+            TAG_I128 => Value::I128(self.integer()?.to_i128()?),
+            TAG_B32 | TAG_B64 | TAG_EVM_ADDRESS => {
+                let bytes = self.byte_string("byte string")?;
+                match tag {
+                    TAG_B32 => Value::B32(bytes.try_into().map_err(|_| Error::Invalid("B32"))?),
+                    TAG_B64 => Value::B64(bytes.try_into().map_err(|_| Error::Invalid("B64"))?),
+                    _ => Value::EvmAddress(
+                        bytes
+                            .try_into()
+                            .map_err(|_| Error::Invalid("EVM address"))?,
+                    ),
+                }
+            }
+            TAG_U256 | TAG_I256 => {
+                let initial = self.byte()?;
+                let bignum = match (initial >> 5, self.definite(initial & 0x1f)?) {
+                    (TAG, tag @ (TAG_POSITIVE_BIGNUM | TAG_NEGATIVE_BIGNUM)) => tag,
+                    _ => return Err(Error::Invalid("bignum")),
+                };
+                let n = ethnum::U256::from_be_bytes(self.bignum_bytes()?);
+                match (tag, bignum) {
+                    (TAG_U256, TAG_POSITIVE_BIGNUM) => Value::U256(n),
+                    (TAG_I256, TAG_POSITIVE_BIGNUM) => {
+                        Value::I256(n.try_into().map_err(|_| Error::Overflow)?)
+                    }
+                    (TAG_I256, _) => Value::I256(
+                        ethnum::I256::try_from(n)
+                            .map(|n| -1 - n)
+                            .map_err(|_| Error::Overflow)?,
+                    ),
+                    _ => return Err(Error::Invalid("U256")),
+                }
+            }
+            TAG_DATE_TIME => {
+                let initial = self.byte()?;
+                if initial >> 5 != TEXT {
+                    return Err(Error::Invalid("date/time string"));
+                }
+                let text = self.text(initial & 0x1f)?;
+                let t = chrono::DateTime::parse_from_rfc3339(&text)
+                    .map_err(|_| Error::Invalid("date/time string"))?;
+                Value::Timestamp(t.with_timezone(&chrono::Utc))
+            }
+            TAG_EPOCH => {
+                let t = match self.nested(Self::value)? {
+                    Value::U64(n) => i64::try_from(n)
+                        .map_err(|_| crate::timestamp::ParseError::OutOfRange)
+                        .and_then(crate::timestamp::from_unix),
+                    Value::I64(n) => crate::timestamp::from_unix(n),
+                    Value::F64(n) => crate::timestamp::from_unix_f64(n),
+                    _ => return Err(Error::Invalid("epoch-based date/time")),
+                };
+                Value::Timestamp(t.map_err(|_| Error::Overflow)?)
+            }
+            TAG_DURATION => {
+                let initial = self.byte()?;
+                if initial != (ARRAY << 5 | 2) {
+                    return Err(Error::Invalid("duration"));
+                }
+                let secs = self.integer()?.to_i128()?;
+                let nanos = self.integer()?.to_i128()?;
+                let d = i64::try_from(secs)
+                    .ok()
+                    .zip(i32::try_from(nanos).ok())
+                    .and_then(|(secs, nanos)| crate::duration::from_secs_nanos(secs, nanos).ok());
+                Value::Duration(d.ok_or(Error::Overflow)?)
+            }
+            _ => self.nested(Self::value)?,
+        })
+    }
+}
+
+fn f16_to_f64(half: u16) -> f64 {
+    let exponent = (half >> 10) & 0x1f;
+    let mantissa = f64::from(half & 0x3ff);
+    let value = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        e => (mantissa + 1024.0) * 2f64.powi(i32::from(e) - 25),
+    };
+    if half & 0x8000 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let value = super::super::tests::sample();
+        let data = to_vec(&value);
+        assert_eq!(from_slice(&data).unwrap(), value);
+
+        for value in [
+            Value::I64(0),
+            Value::I64(i64::MAX),
+            Value::I128(0),
+            Value::I128(i128::MAX),
+            Value::I128(-1),
+            Value::U128(0),
+            Value::Decimal(Decimal::MAX),
+            Value::Decimal(Decimal::new(-5, 28)),
+            Value::U256(ethnum::U256::ZERO),
+            Value::I256(ethnum::I256::MAX),
+            Value::I256(ethnum::I256::new(-1)),
+            Value::Duration(chrono::Duration::max_value()),
+            Value::Duration(chrono::Duration::min_value()),
+        ] {
+            assert_eq!(from_slice(&to_vec(&value)).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_interop() {
+        // examples from RFC 8949, Appendix A
+        let cases: &[(&str, Value)] = &[
+            ("00", Value::U64(0)),
+            ("1903e8", Value::U64(1000)),
+            ("3903e7", Value::I64(-1000)),
+            ("3bffffffffffffffff", Value::I128(-18446744073709551616)),
+            ("c249010000000000000000", Value::U128(18446744073709551616)),
+            ("c349010000000000000000", Value::I128(-18446744073709551617)),
+            ("f90000", Value::F64(0.0)),
+            ("f93c00", Value::F64(1.0)),
+            ("f9c400", Value::F64(-4.0)),
+            ("f97bff", Value::F64(65504.0)),
+            ("fa47c35000", Value::F64(100000.0)),
+            ("fb3ff199999999999a", Value::F64(1.1)),
+            ("f4", Value::Bool(false)),
+            ("f7", Value::Null),
+            ("c48221196ab3", Value::Decimal(Decimal::new(27315, 2))),
+            ("4401020304", Value::Bytes(vec![1, 2, 3, 4].into())),
+            ("62c3bc", Value::String("ü".into())),
+            (
+                "7f657374726561646d696e67ff",
+                Value::String("streaming".into()),
+            ),
+            (
+                "9f018202039f0405ffff",
+                Value::Array(vec![
+                    1u64.into(),
+                    Value::Array(vec![2u64.into(), 3u64.into()]),
+                    Value::Array(vec![4u64.into(), 5u64.into()]),
+                ]),
+            ),
+            (
+                "bf61610161629f0203ffff",
+                Value::Map(crate::map! {
+                    "a" => 1u64,
+                    "b" => Value::Array(vec![2u64.into(), 3u64.into()]),
+                }),
+            ),
+            (
+                "c074323031332d30332d32315432303a30343a30305a",
+                Value::Timestamp(crate::timestamp::from_unix(1363896240).unwrap()),
+            ),
+            (
+                "c11a514b67b0",
+                Value::Timestamp(crate::timestamp::from_unix(1363896240).unwrap()),
+            ),
+            (
+                "c1fb41d452d9ec200000",
+                Value::Timestamp(
+                    crate::timestamp::from_unix_nanos(1363896240, 500_000_000).unwrap(),
+                ),
+            ),
+            // self-described CBOR
+            ("d9d9f700", Value::U64(0)),
+        ];
+        for (hex, value) in cases {
+            let data = (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                .collect::<Vec<u8>>();
+            assert_eq!(from_slice(&data).unwrap(), *value, "{hex}");
+        }
+
+        assert!(matches!(
+            from_slice(&[0xa1, 0x01, 0x02]),
+            Err(Error::Invalid(_))
+        ));
+        let mut deep = [0xd9, 0xd9, 0xf7].repeat(MAX_DEPTH + 1);
+        deep.push(0);
+        assert!(matches!(from_slice(&deep), Err(Error::TooDeep)));
+    }
+}
@@ -0,0 +1,150 @@
+//! Canonical form and content hashing of [`Value`].
+//!
+//! Values that are equal as data have the same canonical form, and therefore the same
+//! canonical bytes and hashes:
+//! - Keys of maps are sorted by their UTF-8 bytes.
+//! - Numbers are normalized like [`Value::normalize`]: decimals without a fractional part
+//!   become integers and `1.0` and `1.00` are the same decimal. Non-negative integers are
+//!   `U64`, or `U128` and `U256` if they don't fit, negative integers are `I64`, `I128` or
+//!   `I256`.
+//! - `F64` is not converted, `-0.0` becomes `0.0` and every NaN becomes the same NaN.
+//!
+//! Canonical bytes are the [`binary`][crate::binary] encoding of the canonical form, they are
+//! stable across versions of this crate as long as [`binary::VERSION`][crate::binary::VERSION]
+//! does not change.
+
+use crate::{binary, Value};
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+
+/// Hash algorithms supported by [`hash`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Algorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+/// Convert `value` to its canonical form.
+pub fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Decimal(_) | Value::I128(_) | Value::U128(_) | Value::I256(_) | Value::U256(_) => {
+            match value.normalize() {
+                Value::I128(n) if n >= 0 => Value::U128(n as u128),
+                Value::I256(n) if n >= 0 => Value::U256(n.as_u256()),
+                value => value,
+            }
+        }
+        Value::I64(n) if n >= 0 => Value::U64(n as u64),
+        // also matches -0.0
+        Value::F64(0.0) => Value::F64(0.0),
+        Value::F64(x) if x.is_nan() => Value::F64(f64::NAN),
+        Value::Array(mut a) => {
+            for v in &mut a {
+                *v = canonicalize(std::mem::take(v));
+            }
+            Value::Array(a)
+        }
+        Value::Map(mut m) => {
+            for v in m.values_mut() {
+                *v = canonicalize(std::mem::take(v));
+            }
+            m.sort_unstable_keys();
+            Value::Map(m)
+        }
+        value => value,
+    }
+}
+
+/// Canonical bytes of `value`.
+pub fn to_vec(value: &Value) -> Vec<u8> {
+    binary::to_vec(&canonicalize(value.clone()))
+}
+
+/// Hash the canonical bytes of `value`.
+pub fn hash(algorithm: Algorithm, value: &Value) -> [u8; 32] {
+    let data = to_vec(value);
+    match algorithm {
+        Algorithm::Sha256 => sha2::Sha256::digest(&data).into(),
+        Algorithm::Blake3 => blake3::hash(&data).into(),
+    }
+}
+
+/// SHA-256 of the canonical bytes of `value`.
+pub fn sha256(value: &Value) -> [u8; 32] {
+    hash(Algorithm::Sha256, value)
+}
+
+/// BLAKE3 of the canonical bytes of `value`.
+pub fn blake3(value: &Value) -> [u8; 32] {
+    hash(Algorithm::Blake3, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Map;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_canonicalize() {
+        let a = Value::Map(crate::map! {
+            "b" => dec!(1.0),
+            "a" => Value::Array(vec![Value::I128(1 << 100), Value::F64(-0.0)]),
+            "c" => Value::U128(7),
+            "d" => Value::I256(ethnum::I256::ONE << 200),
+            "e" => Value::U256(ethnum::U256::new(9)),
+        });
+        let b = Value::Map(crate::map! {
+            "a" => Value::Array(vec![Value::U128(1 << 100), Value::F64(0.0)]),
+            "c" => Value::I64(7),
+            "b" => 1u64,
+            "e" => Value::I64(9),
+            "d" => Value::U256(ethnum::U256::ONE << 200),
+        });
+        assert_ne!(a, b);
+        assert_eq!(canonicalize(a.clone()), canonicalize(b.clone()));
+        assert_eq!(to_vec(&a), to_vec(&b));
+        assert_eq!(sha256(&a), sha256(&b));
+        assert_eq!(blake3(&a), blake3(&b));
+
+        let keys = match canonicalize(a) {
+            Value::Map(m) => m.into_keys().collect::<Vec<_>>(),
+            _ => unreachable!(),
+        };
+        assert_eq!(keys, ["a", "b", "c", "d", "e"]);
+
+        assert_eq!(
+            canonicalize(Value::Decimal(dec!(-2.50))),
+            Value::Decimal(dec!(-2.5))
+        );
+        assert_ne!(sha256(&Value::F64(1.0)), sha256(&Value::U64(1)));
+        assert_ne!(
+            sha256(&Value::Map(Map::new())),
+            blake3(&Value::Map(Map::new()))
+        );
+    }
+
+    #[test]
+    fn test_stable() {
+        // changing these breaks stored cache keys
+        let value = Value::Map(crate::map! { "x" => 1u64, "a" => "b" });
+        assert_eq!(
+            to_vec(&value),
+            [binary::VERSION, 13, 2, 1, b'a', 1, 1, b'b', 1, b'x', 3, 1]
+        );
+        assert_eq!(
+            hex(&sha256(&value)),
+            "1e930bbc4c71b8fc95c4c12e55e6463b90553941f4af9195aea7e8dd2f9e5078"
+        );
+        assert_eq!(
+            hex(&blake3(&value)),
+            "662db83339122d70dd9bc1f34a56c72b265a708687879d719419375758a9f51f"
+        );
+    }
+
+    fn hex(bytes: &[u8; 32]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
@@ -0,0 +1,692 @@
+//! Convert values received from forms and other nodes to the types declared in `type_bounds`.
+//!
+//! Conversions never lose information:
+//! - Numeric strings are parsed to numbers, `"true"` and `"false"` to booleans.
+//! - Integers are accepted by wider integer types, [`Decimal`] and floats if they are exactly
+//!   representable. Decimals and floats are accepted by integer types only if they have no
+//!   fractional part, floats only up to 2<sup>53</sup>. `F32` only accepts numbers whose
+//!   shortest representation is the same as an `f32`.
+//! - Numbers are converted to their decimal representation for `String`, EVM addresses to
+//!   their checksummed hex, timestamps and durations to the strings of
+//!   [`timestamp`][crate::timestamp] and [`duration`][crate::duration].
+//! - `U256` and `I256` are accepted by smaller number types if they are in range.
+//! - Base-58 strings are decoded to `B32` for public keys and `B64` for keypairs and
+//!   signatures. Keypairs are accepted as public keys if their secret key derives the public
+//!   key, other 64-byte values such as signatures are not.
+//! - Base-64 strings and arrays of bytes are decoded to `Bytes`.
+//! - `0x`-prefixed hex strings of 20 bytes are decoded to `EvmAddress` for addresses, see
+//!   [`evm_address`][crate::evm_address]. Other addresses are not converted.
+//!
+//! `Null` is accepted by all types, required inputs are checked elsewhere.
+
+use crate::{Decimal, Value, ValueType};
+use rust_decimal::prelude::ToPrimitive;
+use thiserror::Error as ThisError;
+
+/// Largest integer `n` such that all integers in `[-n, n]` are exactly representable as `f64`.
+const MAX_SAFE_INTEGER: f64 = 9007199254740992.0;
+
+#[derive(ThisError, Debug, Clone, PartialEq)]
+pub enum CoerceError {
+    #[error("expected {expected}, found {found}")]
+    Expected {
+        expected: String,
+        found: &'static str,
+    },
+    #[error("{value} is out of range of {ty}")]
+    OutOfRange { ty: ValueType, value: String },
+    #[error("{value} can not be converted to {ty} without losing precision")]
+    Lossy { ty: ValueType, value: String },
+    #[error("invalid {ty}: {message}")]
+    Invalid { ty: ValueType, message: String },
+}
+
+/// Convert `value` to type `ty`, see the [module documentation][self] for the rules.
+pub fn coerce(value: Value, ty: &ValueType) -> Result<Value, CoerceError> {
+    if matches(&value, ty) {
+        return Ok(value);
+    }
+    match ty {
+        ValueType::Bool => match &value {
+            Value::String(s) if s.trim() == "true" => Ok(Value::Bool(true)),
+            Value::String(s) if s.trim() == "false" => Ok(Value::Bool(false)),
+            _ => Err(expected(ty, &value)),
+        },
+        ValueType::U8
+        | ValueType::U16
+        | ValueType::U32
+        | ValueType::U64
+        | ValueType::U128
+        | ValueType::I8
+        | ValueType::I16
+        | ValueType::I32
+        | ValueType::I64
+        | ValueType::I128 => integer(&value, ty),
+        ValueType::F32 | ValueType::F64 => float(&value, ty),
+        ValueType::Decimal => decimal(&value, ty).map(Value::Decimal),
+        ValueType::Pubkey => match &value {
+            Value::B64(b) => keypair_pubkey(b)
+                .map(Value::B32)
+                .ok_or_else(|| invalid(ty, "64 bytes are not a keypair")),
+            _ => fixed_bytes::<32>(&value, ty).map(Value::B32),
+        },
+        ValueType::Keypair | ValueType::Signature => fixed_bytes::<64>(&value, ty).map(Value::B64),
+        ValueType::Bytes => match &value {
+            Value::String(s) => base64::decode(s.trim())
+                .map(|b| Value::Bytes(b.into()))
+                .map_err(|e| invalid(ty, e)),
+            Value::Array(_) => byte_array(&value, ty).map(|b| Value::Bytes(b.into())),
+            _ => Err(expected(ty, &value)),
+        },
+        ValueType::String => match &value {
+            Value::U64(n) => Ok(Value::String(n.to_string())),
+            Value::I64(n) => Ok(Value::String(n.to_string())),
+            Value::U128(n) => Ok(Value::String(n.to_string())),
+            Value::I128(n) => Ok(Value::String(n.to_string())),
+            Value::U256(n) => Ok(Value::String(n.to_string())),
+            Value::I256(n) => Ok(Value::String(n.to_string())),
+            Value::EvmAddress(a) => Ok(Value::String(crate::evm_address::to_checksum(a))),
+            Value::Timestamp(t) => Ok(Value::String(crate::timestamp::format(t))),
+            Value::Duration(d) => Ok(Value::String(crate::duration::format(d))),
+            Value::Decimal(d) => Ok(Value::String(d.to_string())),
+            // `Display` of f64 is the shortest representation that parses to the same number
+            Value::F64(x) => Ok(Value::String(x.to_string())),
+            _ => Err(expected(ty, &value)),
+        },
+        ValueType::Array | ValueType::Map => Err(expected(ty, &value)),
+        ValueType::Address => address(value, ty),
+        ValueType::Json | ValueType::Free | ValueType::Other => Ok(value),
+    }
+}
+
+/// Convert `value` to the first of `types` that it can be converted to, a value that already
+/// has one of the types is returned unchanged.
+pub fn coerce_any(value: Value, types: &[ValueType]) -> Result<Value, CoerceError> {
+    if types.is_empty() || types.iter().any(|ty| matches(&value, ty)) {
+        return Ok(value);
+    }
+    let mut errors = Vec::new();
+    for ty in types {
+        match coerce(value.clone(), ty) {
+            Ok(value) => return Ok(value),
+            Err(error) => errors.push(error),
+        }
+    }
+    // report the first error about the content of the value, not its kind
+    match errors
+        .iter()
+        .position(|e| !matches!(e, CoerceError::Expected { .. }))
+    {
+        Some(i) => Err(errors.swap_remove(i)),
+        None => Err(CoerceError::Expected {
+            expected: types
+                .iter()
+                .map(ValueType::name)
+                .collect::<Vec<_>>()
+                .join(" or "),
+            found: kind(&value),
+        }),
+    }
+}
+
+/// `value` has type `ty` without conversion.
+fn matches(value: &Value, ty: &ValueType) -> bool {
+    match (ty, value) {
+        (ValueType::Address, Value::String(s)) => !is_evm_hex(s),
+        (ValueType::Address | ValueType::Json | ValueType::Free | ValueType::Other, _) => true,
+        (_, Value::Null) => true,
+        (ValueType::Bool, Value::Bool(_)) => true,
+        (
+            ValueType::U8
+            | ValueType::U16
+            | ValueType::U32
+            | ValueType::U64
+            | ValueType::U128
+            | ValueType::I8
+            | ValueType::I16
+            | ValueType::I32
+            | ValueType::I64
+            | ValueType::I128,
+            Value::U64(_)
+            | Value::I64(_)
+            | Value::U128(_)
+            | Value::I128(_)
+            | Value::U256(_)
+            | Value::I256(_),
+        ) => integer(value, ty).is_ok(),
+        (ValueType::F32, Value::F64(x)) => f32_exact(*x, ty).is_ok(),
+        (ValueType::F64, Value::F64(_)) => true,
+        (ValueType::Decimal, Value::Decimal(_)) => true,
+        (ValueType::Pubkey, Value::B32(_)) => true,
+        (ValueType::Keypair | ValueType::Signature, Value::B64(_)) => true,
+        (ValueType::String, Value::String(_)) => true,
+        (
+            ValueType::Bytes,
+            Value::Bytes(_) | Value::B32(_) | Value::B64(_) | Value::EvmAddress(_),
+        ) => true,
+        (ValueType::Array, Value::Array(_)) => true,
+        (ValueType::Map, Value::Map(_)) => true,
+        _ => false,
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::String(_) => "string",
+        Value::Bool(_) => "boolean",
+        Value::U64(_)
+        | Value::I64(_)
+        | Value::U128(_)
+        | Value::I128(_)
+        | Value::U256(_)
+        | Value::I256(_)
+        | Value::F64(_)
+        | Value::Decimal(_) => "number",
+        Value::B32(_) => "public key",
+        Value::B64(_) => "keypair",
+        Value::Bytes(_) => "bytes",
+        Value::EvmAddress(_) => "EVM address",
+        Value::Timestamp(_) => "timestamp",
+        Value::Duration(_) => "duration",
+        Value::Array(_) => "array",
+        Value::Map(_) => "map",
+    }
+}
+
+fn expected(ty: &ValueType, value: &Value) -> CoerceError {
+    CoerceError::Expected {
+        expected: ty.name().to_owned(),
+        found: kind(value),
+    }
+}
+
+fn is_evm_hex(s: &str) -> bool {
+    s.len() == 42 && s.starts_with("0x")
+}
+
+fn address(value: Value, ty: &ValueType) -> Result<Value, CoerceError> {
+    match &value {
+        Value::String(s) if is_evm_hex(s) => crate::evm_address::parse(s)
+            .map(Value::EvmAddress)
+            .map_err(|e| invalid(ty, e)),
+        _ => Ok(value),
+    }
+}
+
+fn invalid(ty: &ValueType, message: impl ToString) -> CoerceError {
+    CoerceError::Invalid {
+        ty: ty.clone(),
+        message: message.to_string(),
+    }
+}
+
+fn lossy(ty: &ValueType, value: impl ToString) -> CoerceError {
+    CoerceError::Lossy {
+        ty: ty.clone(),
+        value: value.to_string(),
+    }
+}
+
+/// An integer of any size.
+#[derive(Clone, Copy)]
+enum Int {
+    Unsigned(u128),
+    Negative(i128),
+}
+
+impl std::fmt::Display for Int {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Int::Unsigned(n) => n.fmt(f),
+            Int::Negative(n) => n.fmt(f),
+        }
+    }
+}
+
+impl From<i128> for Int {
+    fn from(n: i128) -> Self {
+        if n < 0 {
+            Int::Negative(n)
+        } else {
+            Int::Unsigned(n as u128)
+        }
+    }
+}
+
+fn int(value: &Value, ty: &ValueType) -> Result<Int, CoerceError> {
+    let out_of_range = |value: String| CoerceError::OutOfRange {
+        ty: ty.clone(),
+        value,
+    };
+    Ok(match value {
+        Value::U64(n) => Int::Unsigned((*n).into()),
+        Value::I64(n) => Int::from(i128::from(*n)),
+        Value::U128(n) => Int::Unsigned(*n),
+        Value::I128(n) => Int::from(*n),
+        Value::U256(n) => {
+            Int::Unsigned(u128::try_from(*n).map_err(|_| out_of_range(n.to_string()))?)
+        }
+        Value::I256(n) => Int::from(i128::try_from(*n).map_err(|_| out_of_range(n.to_string()))?),
+        Value::Decimal(d) => decimal_to_int(*d, ty)?,
+        Value::F64(x) => {
+            if x.fract() != 0.0 || x.abs() > MAX_SAFE_INTEGER {
+                return Err(lossy(ty, x));
+            }
+            Int::from(*x as i128)
+        }
+        Value::String(s) => {
+            let s = s.trim();
+            match s.parse::<u128>() {
+                Ok(n) => Int::Unsigned(n),
+                Err(_) => match s.parse::<i128>() {
+                    Ok(n) => Int::from(n),
+                    Err(_) => decimal_to_int(decimal(value, ty)?, ty)?,
+                },
+            }
+        }
+        _ => return Err(expected(ty, value)),
+    })
+}
+
+fn integer(value: &Value, ty: &ValueType) -> Result<Value, CoerceError> {
+    let n = int(value, ty)?;
+    let out_of_range = || CoerceError::OutOfRange {
+        ty: ty.clone(),
+        value: n.to_string(),
+    };
+    macro_rules! convert {
+        ($t:ty, $variant:ident) => {
+            match n {
+                Int::Unsigned(n) => <$t>::try_from(n).map_err(|_| out_of_range())?,
+                Int::Negative(n) => <$t>::try_from(n).map_err(|_| out_of_range())?,
+            }
+            .try_into()
+            .map(Value::$variant)
+            .map_err(|_| out_of_range())
+        };
+    }
+    let keep = |result: Result<Value, CoerceError>| {
+        // integers in range keep their variant
+        result.map(|converted| match value {
+            Value::U64(_)
+            | Value::I64(_)
+            | Value::U128(_)
+            | Value::I128(_)
+            | Value::U256(_)
+            | Value::I256(_) => value.clone(),
+            _ => converted,
+        })
+    };
+    keep(match ty {
+        ValueType::U8 => convert!(u8, U64),
+        ValueType::U16 => convert!(u16, U64),
+        ValueType::U32 => convert!(u32, U64),
+        ValueType::U64 => convert!(u64, U64),
+        ValueType::U128 => convert!(u128, U128),
+        ValueType::I8 => convert!(i8, I64),
+        ValueType::I16 => convert!(i16, I64),
+        ValueType::I32 => convert!(i32, I64),
+        ValueType::I64 => convert!(i64, I64),
+        ValueType::I128 => convert!(i128, I128),
+        _ => unreachable!("not an integer type"),
+    })
+}
+
+fn decimal_to_int(d: Decimal, ty: &ValueType) -> Result<Int, CoerceError> {
+    let d = d.normalize();
+    if d.scale() != 0 {
+        return Err(lossy(ty, d));
+    }
+    Ok(Int::from(d.mantissa()))
+}
+
+fn float(value: &Value, ty: &ValueType) -> Result<Value, CoerceError> {
+    let x = match value {
+        Value::F64(x) => *x,
+        Value::U64(_)
+        | Value::I64(_)
+        | Value::U128(_)
+        | Value::I128(_)
+        | Value::U256(_)
+        | Value::I256(_) => {
+            let n = match value {
+                Value::U64(n) => i128::from(*n),
+                Value::I64(n) => i128::from(*n),
+                Value::U128(n) => i128::try_from(*n).map_err(|_| lossy(ty, n))?,
+                Value::I128(n) => *n,
+                Value::U256(n) => i128::try_from(*n).map_err(|_| lossy(ty, n))?,
+                Value::I256(n) => i128::try_from(*n).map_err(|_| lossy(ty, n))?,
+                _ => unreachable!(),
+            };
+            let x = n as f64;
+            if x.abs() >= 2f64.powi(127) || x as i128 != n {
+                return Err(lossy(ty, n));
+            }
+            x
+        }
+        Value::Decimal(d) => {
+            let x = d.to_f64().ok_or_else(|| lossy(ty, d))?;
+            // shortest representation of `x` must be the same number
+            if x.to_string().parse::<Decimal>().ok() != Some(d.normalize()) {
+                return Err(lossy(ty, d));
+            }
+            x
+        }
+        Value::String(s) => s.trim().parse().map_err(|e| invalid(ty, e))?,
+        _ => return Err(expected(ty, value)),
+    };
+    if *ty == ValueType::F32 {
+        f32_exact(x, ty)?;
+    }
+    Ok(Value::F64(x))
+}
+
+/// Check that `x` is in range of `f32` and has the same shortest representation.
+fn f32_exact(x: f64, ty: &ValueType) -> Result<(), CoerceError> {
+    let y = x as f32;
+    if x.is_finite() && y.is_infinite() {
+        return Err(CoerceError::OutOfRange {
+            ty: ty.clone(),
+            value: x.to_string(),
+        });
+    }
+    if !x.is_nan() && y.to_string().parse::<f64>() != Ok(x) {
+        return Err(lossy(ty, x));
+    }
+    Ok(())
+}
+
+/// Public key of a keypair, if the secret key in the first 32 bytes derives the last 32 bytes.
+#[cfg(feature = "solana")]
+fn keypair_pubkey(bytes: &[u8; 64]) -> Option<[u8; 32]> {
+    use solana_sdk::signer::{keypair::keypair_from_seed, Signer};
+    let pubkey = keypair_from_seed(&bytes[..32]).ok()?.pubkey().to_bytes();
+    (pubkey[..] == bytes[32..]).then_some(pubkey)
+}
+
+#[cfg(not(feature = "solana"))]
+fn keypair_pubkey(_: &[u8; 64]) -> Option<[u8; 32]> {
+    None
+}
+
+fn decimal(value: &Value, ty: &ValueType) -> Result<Decimal, CoerceError> {
+    let out_of_range = |value: String| CoerceError::OutOfRange {
+        ty: ty.clone(),
+        value,
+    };
+    match value {
+        Value::Decimal(d) => Ok(*d),
+        Value::U64(n) => Ok((*n).into()),
+        Value::I64(n) => Ok((*n).into()),
+        Value::U128(n) => i128::try_from(*n)
+            .ok()
+            .and_then(|n| Decimal::try_from_i128_with_scale(n, 0).ok())
+            .ok_or_else(|| out_of_range(n.to_string())),
+        Value::I128(n) => {
+            Decimal::try_from_i128_with_scale(*n, 0).map_err(|_| out_of_range(n.to_string()))
+        }
+        Value::U256(n) => i128::try_from(*n)
+            .ok()
+            .and_then(|n| Decimal::try_from_i128_with_scale(n, 0).ok())
+            .ok_or_else(|| out_of_range(n.to_string())),
+        Value::I256(n) => i128::try_from(*n)
+            .ok()
+            .and_then(|n| Decimal::try_from_i128_with_scale(n, 0).ok())
+            .ok_or_else(|| out_of_range(n.to_string())),
+        Value::F64(x) => {
+            if !x.is_finite() {
+                return Err(invalid(ty, format!("{x} is not a number")));
+            }
+            // `Display` of f64 is the shortest representation that parses to the same number
+            Decimal::from_str_exact(&x.to_string()).map_err(|_| lossy(ty, x))
+        }
+        Value::String(s) => {
+            let s = s.trim();
+            if s.bytes().any(|c| c == b'e' || c == b'E') {
+                Decimal::from_scientific(s).map_err(|e| invalid(ty, e))
+            } else {
+                Decimal::from_str_exact(s).map_err(|e| invalid(ty, e))
+            }
+        }
+        _ => Err(expected(ty, value)),
+    }
+}
+
+fn byte_array(value: &Value, ty: &ValueType) -> Result<Vec<u8>, CoerceError> {
+    match value {
+        Value::Array(array) => array
+            .iter()
+            .map(|v| match int(v, &ValueType::U8)? {
+                Int::Unsigned(n) if n <= u8::MAX as u128 => Ok(n as u8),
+                n => Err(CoerceError::OutOfRange {
+                    ty: ValueType::U8,
+                    value: n.to_string(),
+                }),
+            })
+            .collect::<Result<_, _>>()
+            .map_err(|e| invalid(ty, e)),
+        _ => Err(expected(ty, value)),
+    }
+}
+
+fn fixed_bytes<const N: usize>(value: &Value, ty: &ValueType) -> Result<[u8; N], CoerceError> {
+    let wrong_length = |len: usize| invalid(ty, format!("need length {N}, got {len}"));
+    match value {
+        Value::String(s) => {
+            let mut buf = [0u8; N];
+            let len = bs58::decode(s.trim())
+                .into(&mut buf)
+                .map_err(|e| invalid(ty, e))?;
+            if len != N {
+                return Err(wrong_length(len));
+            }
+            Ok(buf)
+        }
+        Value::Bytes(b) => b[..].try_into().map_err(|_| wrong_length(b.len())),
+        Value::Array(_) => {
+            let bytes = byte_array(value, ty)?;
+            let len = bytes.len();
+            bytes.try_into().map_err(|_| wrong_length(len))
+        }
+        _ => Err(expected(ty, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_coerce() {
+        let ok = |value: Value, ty: ValueType| coerce(value, &ty).unwrap();
+        let err = |value: Value, ty: ValueType| coerce(value, &ty).unwrap_err().to_string();
+
+        assert_eq!(ok("true".into(), ValueType::Bool), Value::Bool(true));
+        assert_eq!(ok(" 255 ".into(), ValueType::U8), Value::U64(255));
+        assert_eq!(ok("-5".into(), ValueType::I128), Value::I128(-5));
+        assert_eq!(ok("1e3".into(), ValueType::U64), Value::U64(1000));
+        assert_eq!(ok(dec!(7.00).into(), ValueType::U16), Value::U64(7));
+        assert_eq!(ok(Value::F64(3.0), ValueType::I32), Value::I64(3));
+        assert_eq!(ok(Value::U64(3), ValueType::I128), Value::U64(3));
+        assert_eq!(
+            ok(u128::MAX.into(), ValueType::U128),
+            Value::U128(u128::MAX)
+        );
+        assert_eq!(
+            ok(ethnum::U256::new(7).into(), ValueType::U8),
+            Value::U256(ethnum::U256::new(7))
+        );
+        assert_eq!(
+            ok(ethnum::I256::new(-7).into(), ValueType::Decimal),
+            dec!(-7).into()
+        );
+        assert_eq!(
+            err(ethnum::U256::MAX.into(), ValueType::U128),
+            format!("{} is out of range of u128", ethnum::U256::MAX)
+        );
+        assert_eq!(
+            err("256".into(), ValueType::U8),
+            "256 is out of range of u8"
+        );
+        assert_eq!(
+            err((-1i64).into(), ValueType::U64),
+            "-1 is out of range of u64"
+        );
+        assert_eq!(
+            err(Value::F64(1.5), ValueType::U64),
+            "1.5 can not be converted to u64 without losing precision"
+        );
+        assert_eq!(
+            err(Value::F64(1e300), ValueType::U128),
+            format!(
+                "{} can not be converted to u128 without losing precision",
+                1e300
+            )
+        );
+
+        assert_eq!(ok("1.25".into(), ValueType::Decimal), dec!(1.25).into());
+        assert_eq!(ok(Value::F64(0.1), ValueType::Decimal), dec!(0.1).into());
+        assert_eq!(
+            ok(u64::MAX.into(), ValueType::Decimal),
+            Decimal::from(u64::MAX).into()
+        );
+        assert_eq!(ok(dec!(0.5).into(), ValueType::F32), Value::F64(0.5));
+        assert_eq!(ok("0.1".into(), ValueType::F32), Value::F64(0.1));
+        assert_eq!(
+            err(Value::F64(1e39), ValueType::F32),
+            "1000000000000000000000000000000000000000 is out of range of f32"
+        );
+        assert_eq!(
+            err(Value::U64((1 << 24) + 1), ValueType::F32),
+            "16777217 can not be converted to f32 without losing precision"
+        );
+        assert_eq!(
+            err(Value::F64(0.1234567891), ValueType::F32),
+            "0.1234567891 can not be converted to f32 without losing precision"
+        );
+        assert_eq!(
+            ok(Value::U64(1 << 53), ValueType::F64),
+            Value::F64(2f64.powi(53))
+        );
+        assert!(coerce(Value::U64((1 << 53) + 1), &ValueType::F64).is_err());
+        assert!(coerce(dec!(0.1000000000000000000001).into(), &ValueType::F64).is_err());
+
+        let pubkey = [3u8; 32];
+        let bs58 = bs58::encode(pubkey).into_string();
+        assert_eq!(ok(bs58.into(), ValueType::Pubkey), Value::B32(pubkey));
+        use solana_sdk::signer::{keypair::keypair_from_seed, Signer};
+        let signer = keypair_from_seed(&[1u8; 32]).unwrap();
+        let keypair = signer.to_bytes();
+        assert_eq!(
+            ok(Value::B64(keypair), ValueType::Pubkey),
+            Value::B32(signer.pubkey().to_bytes())
+        );
+        let mut signature = keypair;
+        signature[32..].copy_from_slice(&pubkey);
+        assert_eq!(
+            err(Value::B64(signature), ValueType::Pubkey),
+            "invalid pubkey: 64 bytes are not a keypair"
+        );
+        let bs58 = bs58::encode(keypair).into_string();
+        assert_eq!(ok(bs58.into(), ValueType::Keypair), Value::B64(keypair));
+        assert_eq!(
+            err("abc".into(), ValueType::Signature),
+            "invalid signature: need length 64, got 3"
+        );
+
+        assert_eq!(
+            ok("AQID".into(), ValueType::Bytes),
+            Value::Bytes(vec![1, 2, 3].into())
+        );
+        assert_eq!(
+            ok(
+                Value::Array(vec![1u64.into(), 2u64.into()]),
+                ValueType::Bytes
+            ),
+            Value::Bytes(vec![1, 2].into())
+        );
+        assert_eq!(
+            ok(Value::U64(1), ValueType::String),
+            Value::String("1".into())
+        );
+        assert_eq!(ok(dec!(1.50).into(), ValueType::String), "1.50".into());
+        assert_eq!(ok(Value::F64(0.1), ValueType::String), "0.1".into());
+        assert_eq!(
+            ok(
+                crate::timestamp::from_unix(1_706_702_400).unwrap().into(),
+                ValueType::String
+            ),
+            "2024-01-31T12:00:00Z".into()
+        );
+        assert_eq!(
+            ok(chrono::Duration::minutes(90).into(), ValueType::String),
+            "1h 30m".into()
+        );
+        assert_eq!(
+            ok(chrono::Duration::nanoseconds(1).into(), ValueType::String),
+            "1ns".into()
+        );
+        assert_eq!(
+            err(Value::Bool(true), ValueType::String),
+            "expected string, found boolean"
+        );
+        assert_eq!(ok(Value::Null, ValueType::Pubkey), Value::Null);
+        assert_eq!(ok(Value::U64(1), ValueType::Free), Value::U64(1));
+    }
+
+    #[test]
+    fn test_address() {
+        let ok = |value: Value, ty: ValueType| coerce(value, &ty).unwrap();
+        let err = |value: Value, ty: ValueType| coerce(value, &ty).unwrap_err().to_string();
+        assert_eq!(
+            ok(
+                "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".into(),
+                ValueType::Address
+            ),
+            Value::EvmAddress(
+                crate::evm_address::parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap()
+            )
+        );
+        assert_eq!(
+            err(
+                "0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".into(),
+                ValueType::Address
+            ),
+            "invalid address: invalid EIP-55 checksum"
+        );
+        assert_eq!(
+            ok(Value::B32([3u8; 32]), ValueType::Address),
+            Value::B32([3u8; 32])
+        );
+        let wormhole = format!("0x{}", "00".repeat(32));
+        assert_eq!(
+            ok(wormhole.clone().into(), ValueType::Address),
+            Value::String(wormhole)
+        );
+    }
+
+    #[test]
+    fn test_coerce_any() {
+        let keypair = Value::B64([1u8; 64]);
+        let types = [ValueType::Pubkey, ValueType::Keypair];
+        assert_eq!(coerce_any(keypair.clone(), &types).unwrap(), keypair);
+        assert_eq!(
+            coerce_any(Value::Bool(true), &types)
+                .unwrap_err()
+                .to_string(),
+            "expected pubkey or keypair, found boolean"
+        );
+        assert_eq!(
+            coerce_any("300".into(), &[ValueType::String, ValueType::U8]).unwrap(),
+            Value::String("300".into())
+        );
+        assert_eq!(
+            coerce_any("300".into(), &[ValueType::Bool, ValueType::U8])
+                .unwrap_err()
+                .to_string(),
+            "300 is out of range of u8"
+        );
+    }
+}
@@ -43,6 +43,21 @@ impl<'de> serde::de::Visitor<'de> for ValueVisitor {
             Variant::Bytes => Ok(Value::Bytes(a.newtype_variant()?)),
             Variant::Array => Ok(Value::Array(a.newtype_variant()?)),
             Variant::Map => Ok(Value::Map(a.newtype_variant()?)),
+            Variant::EvmAddress => Ok(Value::EvmAddress(a.newtype_variant::<ConstBytes<20>>()?.0)),
+            Variant::U256 => Ok(Value::U256(ethnum::U256::from_le_bytes(
+                a.newtype_variant::<ConstBytes<32>>()?.0,
+            ))),
+            Variant::I256 => Ok(Value::I256(ethnum::I256::from_le_bytes(
+                a.newtype_variant::<ConstBytes<32>>()?.0,
+            ))),
+            Variant::Timestamp => Ok(Value::Timestamp(
+                crate::timestamp::from_bytes(&a.newtype_variant::<ConstBytes<12>>()?.0)
+                    .map_err(serde::de::Error::custom)?,
+            )),
+            Variant::Duration => Ok(Value::Duration(
+                crate::duration::from_bytes(&a.newtype_variant::<ConstBytes<12>>()?.0)
+                    .map_err(serde::de::Error::custom)?,
+            )),
         }
     }
 }
@@ -88,6 +103,27 @@ impl<'de> serde::Deserializer<'de> for Value {
             Value::B32(x) => visit_bytes(&x, visitor),
             Value::B64(x) => visit_bytes(&x, visitor),
             Value::Bytes(x) => visit_bytes(&x, visitor),
+            Value::EvmAddress(x) => visit_bytes(&x, visitor),
+            Value::U256(u) => {
+                if let Ok(u) = u64::try_from(u) {
+                    visitor.visit_u64(u)
+                } else if let Ok(u) = u128::try_from(u) {
+                    visitor.visit_u128(u)
+                } else {
+                    visitor.visit_string(u.to_string())
+                }
+            }
+            Value::I256(i) => {
+                if let Ok(i) = i64::try_from(i) {
+                    visitor.visit_i64(i)
+                } else if let Ok(i) = i128::try_from(i) {
+                    visitor.visit_i128(i)
+                } else {
+                    visitor.visit_string(i.to_string())
+                }
+            }
+            Value::Timestamp(t) => visitor.visit_string(crate::timestamp::format(&t)),
+            Value::Duration(d) => visitor.visit_string(crate::duration::format(&d)),
         }
     }
 
@@ -143,6 +179,47 @@ impl<'de> serde::Deserializer<'de> for Value {
                     &"bytes or base58 string",
                 )),
             },
+            crate::evm_address::TOKEN => match self {
+                Value::EvmAddress(b) => visitor.visit_bytes(&b),
+                Value::B32(b) => visitor.visit_bytes(&b),
+                Value::Bytes(b) => visitor.visit_bytes(&b),
+                Value::String(s) => visitor.visit_str(&s),
+                Value::Array(_) => visitor.visit_newtype_struct(self),
+                _ => Err(serde::de::Error::invalid_type(
+                    self.unexpected(),
+                    &"bytes or hex string",
+                )),
+            },
+            crate::u256::TOKEN => match self {
+                Value::U256(n) => visitor.visit_bytes(&n.to_le_bytes()),
+                _ => self.deserialize_any(visitor),
+            },
+            crate::i256::TOKEN => match self {
+                Value::I256(n) => visitor.visit_bytes(&n.to_le_bytes()),
+                _ => self.deserialize_any(visitor),
+            },
+            crate::timestamp::TOKEN => match self {
+                Value::Timestamp(t) => visitor.visit_bytes(&crate::timestamp::to_bytes(&t)),
+                Value::String(_) | Value::U64(_) | Value::I64(_) | Value::F64(_) => {
+                    self.deserialize_any(visitor)
+                }
+                Value::Decimal(d) => visit_decimal(d, visitor),
+                _ => Err(serde::de::Error::invalid_type(
+                    self.unexpected(),
+                    &"RFC 3339 string or Unix seconds",
+                )),
+            },
+            crate::duration::TOKEN => match self {
+                Value::Duration(d) => visitor.visit_bytes(&crate::duration::to_bytes(&d)),
+                Value::String(_) | Value::U64(_) | Value::I64(_) | Value::F64(_) => {
+                    self.deserialize_any(visitor)
+                }
+                Value::Decimal(d) => visit_decimal(d, visitor),
+                _ => Err(serde::de::Error::invalid_type(
+                    self.unexpected(),
+                    &"duration string or seconds",
+                )),
+            },
             _ => visitor.visit_newtype_struct(self),
         }
     }
@@ -201,6 +278,11 @@ impl<'de> serde::Deserializer<'de> for Value {
             Value::B32(v) => visitor.visit_bytes(&v),
             Value::B64(v) => visitor.visit_bytes(&v),
             Value::Bytes(v) => visitor.visit_bytes(&v),
+            Value::EvmAddress(v) => visitor.visit_bytes(&v),
+            Value::U256(v) => visitor.visit_bytes(&v.to_le_bytes()),
+            Value::I256(v) => visitor.visit_bytes(&v.to_le_bytes()),
+            Value::Timestamp(v) => visitor.visit_bytes(&crate::timestamp::to_bytes(&v)),
+            Value::Duration(v) => visitor.visit_bytes(&crate::duration::to_bytes(&v)),
             _ => self.deserialize_any(visitor),
         }
     }
@@ -261,6 +343,11 @@ impl Value {
             Value::B32(_) => Unexpected::Other("[u8; 32]"),
             Value::B64(_) => Unexpected::Other("[u8; 64]"),
             Value::Bytes(_) => Unexpected::Other("bytes"),
+            Value::EvmAddress(_) => Unexpected::Other("EVM address"),
+            Value::U256(_) => Unexpected::Other("u256"),
+            Value::I256(_) => Unexpected::Other("i256"),
+            Value::Timestamp(_) => Unexpected::Other("timestamp"),
+            Value::Duration(_) => Unexpected::Other("duration"),
         }
     }
 }
@@ -48,6 +48,26 @@ impl<'de> serde::de::Visitor<'de> for EnumVisitor {
             Variant::Bytes => Ok(Value::Bytes(b64_str(a)?)),
             Variant::Array => Ok(Value::Array(a.newtype_variant::<Array>()?.0)),
             Variant::Map => Ok(Value::Map(a.newtype_variant::<Map>()?.0)),
+            Variant::EvmAddress => {
+                let s = a.newtype_variant::<Cow<'_, str>>()?;
+                Ok(Value::EvmAddress(
+                    crate::evm_address::parse(&s).map_err(serde::de::Error::custom)?,
+                ))
+            }
+            Variant::U256 => Ok(Value::U256(number_from_str(a)?)),
+            Variant::I256 => Ok(Value::I256(number_from_str(a)?)),
+            Variant::Timestamp => {
+                let s = a.newtype_variant::<Cow<'_, str>>()?;
+                Ok(Value::Timestamp(
+                    crate::timestamp::parse(&s).map_err(serde::de::Error::custom)?,
+                ))
+            }
+            Variant::Duration => {
+                let s = a.newtype_variant::<Cow<'_, str>>()?;
+                Ok(Value::Duration(
+                    crate::duration::parse(&s).map_err(serde::de::Error::custom)?,
+                ))
+            }
         }
     }
 }
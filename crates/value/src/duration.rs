@@ -0,0 +1,327 @@
+//! Serde helper for durations.
+//!
+//! Durations are serialized to [`Value::Duration`][crate::Value::Duration], and to strings such as
+//! `1d 2h 30m` or `-1m 30s 500ms` in human-readable formats. Strings have units `w`, `d`, `h`,
+//! `m`, `s`, `ms`, `us` and `ns`, and keep every nanosecond. Durations can be deserialized from
+//! these strings, with or without spaces between parts, and from seconds as numbers or numeric
+//! strings.
+
+use thiserror::Error as ThisError;
+
+pub(crate) const TOKEN: &str = "$$u";
+
+pub type Target = chrono::Duration;
+
+#[derive(ThisError, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("expected duration such as \"1h 30m\" or seconds")]
+    Format,
+    #[error("unknown unit {0:?}")]
+    Unit(String),
+    #[error("duration out of range")]
+    OutOfRange,
+}
+
+const UNITS: &[(&str, i128)] = &[
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1000),
+    ("ns", 1),
+];
+
+fn unit(s: &str) -> Option<i128> {
+    match s {
+        "w" => Some(7 * 86_400_000_000_000),
+        s => UNITS.iter().find(|(u, _)| *u == s).map(|(_, ns)| *ns),
+    }
+}
+
+/// Parse a duration string or seconds.
+pub fn parse(s: &str) -> Result<Target, ParseError> {
+    let s = s.trim();
+    if let Ok(secs) = s.parse::<f64>() {
+        return from_secs_f64(secs);
+    }
+    let (negative, mut rest) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, s),
+    };
+    if rest.is_empty() {
+        return Err(ParseError::Format);
+    }
+    let mut nanos: i128 = 0;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let letters = rest[digits..]
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .map_or(rest.len(), |i| digits + i);
+        if digits == 0 || letters == digits {
+            return Err(ParseError::Format);
+        }
+        let n = rest[..digits]
+            .parse::<i64>()
+            .map_err(|_| ParseError::OutOfRange)?;
+        let unit = &rest[digits..letters];
+        let unit = self::unit(unit).ok_or_else(|| ParseError::Unit(unit.to_owned()))?;
+        nanos = i128::from(n)
+            .checked_mul(unit)
+            .and_then(|n| nanos.checked_add(n))
+            .ok_or(ParseError::OutOfRange)?;
+        rest = rest[letters..].trim_start();
+    }
+    from_nanos(if negative { -nanos } else { nanos })
+}
+
+/// Format with the largest units first, `0s` if the duration is zero.
+pub fn format(d: &Target) -> String {
+    let nanos = to_nanos(d);
+    if nanos == 0 {
+        return "0s".to_owned();
+    }
+    let mut rest = nanos.unsigned_abs();
+    let mut parts = Vec::new();
+    for (unit, size) in UNITS {
+        let n = rest / *size as u128;
+        rest %= *size as u128;
+        if n > 0 {
+            parts.push(format!("{n}{unit}"));
+        }
+    }
+    let s = parts.join(" ");
+    if nanos < 0 {
+        format!("-{s}")
+    } else {
+        s
+    }
+}
+
+pub fn from_secs_f64(secs: f64) -> Result<Target, ParseError> {
+    let nanos = (secs * 1e9).round();
+    if !nanos.is_finite() || nanos.abs() >= i128::MAX as f64 {
+        return Err(ParseError::OutOfRange);
+    }
+    from_nanos(nanos as i128)
+}
+
+fn to_nanos(d: &Target) -> i128 {
+    let (secs, nanos) = to_secs_nanos(d);
+    i128::from(secs) * 1_000_000_000 + i128::from(nanos)
+}
+
+fn from_nanos(nanos: i128) -> Result<Target, ParseError> {
+    let secs = i64::try_from(nanos / 1_000_000_000).map_err(|_| ParseError::OutOfRange)?;
+    from_secs_nanos(secs, (nanos % 1_000_000_000) as i32)
+}
+
+pub fn from_secs_nanos(secs: i64, nanos: i32) -> Result<Target, ParseError> {
+    // range of `Target::seconds`, which panics outside of it
+    const MAX_SECS: i64 = i64::MAX / 1000;
+    if !(-MAX_SECS..=MAX_SECS).contains(&secs) {
+        return Err(ParseError::OutOfRange);
+    }
+    Target::seconds(secs)
+        .checked_add(&Target::nanoseconds(nanos.into()))
+        .ok_or(ParseError::OutOfRange)
+}
+
+/// Whole seconds and the remaining nanoseconds, both with the sign of the duration.
+pub(crate) fn to_secs_nanos(d: &Target) -> (i64, i32) {
+    let secs = d.num_seconds();
+    let nanos = (*d - Target::seconds(secs))
+        .num_nanoseconds()
+        .expect("less than a second") as i32;
+    (secs, nanos)
+}
+
+/// Seconds as `i64` followed by nanoseconds as `i32`, little-endian, see [`to_secs_nanos`].
+pub(crate) fn to_bytes(d: &Target) -> [u8; 12] {
+    let (secs, nanos) = to_secs_nanos(d);
+    let mut buf = [0; 12];
+    buf[..8].copy_from_slice(&secs.to_le_bytes());
+    buf[8..].copy_from_slice(&nanos.to_le_bytes());
+    buf
+}
+
+pub(crate) fn from_bytes(buf: &[u8; 12]) -> Result<Target, ParseError> {
+    let (secs, nanos) = buf.split_at(8);
+    from_secs_nanos(
+        i64::from_le_bytes(secs.try_into().unwrap()),
+        i32::from_le_bytes(nanos.try_into().unwrap()),
+    )
+}
+
+pub mod opt {
+    pub fn serialize<S>(d: &Option<super::Target>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match d {
+            Some(d) => super::serialize(d, s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<super::Target>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        d.deserialize_option(crate::OptionVisitor(super::Visitor))
+    }
+}
+
+pub fn serialize<S>(d: &Target, s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    if s.is_human_readable() {
+        s.serialize_str(&format(d))
+    } else {
+        s.serialize_newtype_struct(TOKEN, &crate::Bytes(&to_bytes(d)))
+    }
+}
+
+struct Visitor;
+
+impl<'de> serde::de::Visitor<'de> for Visitor {
+    type Value = Target;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("duration string or seconds")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        i64::try_from(v)
+            .ok()
+            .and_then(|v| v.checked_mul(1000))
+            .map(Target::milliseconds)
+            .ok_or_else(|| serde::de::Error::custom(ParseError::OutOfRange))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        v.checked_mul(1000)
+            .map(Target::milliseconds)
+            .ok_or_else(|| serde::de::Error::custom(ParseError::OutOfRange))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        from_secs_f64(v).map_err(serde::de::Error::custom)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        parse(v).map_err(serde::de::Error::custom)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let buf = v
+            .try_into()
+            .map_err(|_| serde::de::Error::invalid_length(v.len(), &"12"))?;
+        from_bytes(buf).map_err(serde::de::Error::custom)
+    }
+
+    fn visit_newtype_struct<D>(self, d: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        d.deserialize_any(self)
+    }
+}
+
+pub fn deserialize<'de, D>(d: D) -> Result<Target, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    d.deserialize_newtype_struct(TOKEN, Visitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    #[test]
+    fn test_parse() {
+        let d = Target::seconds(5400);
+        assert_eq!(format(&d), "1h 30m");
+        assert_eq!(parse("1h 30m").unwrap(), d);
+        assert_eq!(parse("1h30m").unwrap(), d);
+        assert_eq!(parse("90m").unwrap(), d);
+        assert_eq!(parse("5400").unwrap(), d);
+        assert_eq!(parse("1w").unwrap(), Target::days(7));
+        assert_eq!(
+            parse("-1m 30s 500ms").unwrap(),
+            Target::milliseconds(-90_500)
+        );
+        assert_eq!(format(&Target::milliseconds(-90_500)), "-1m 30s 500ms");
+        assert_eq!(format(&Target::days(8)), "8d");
+        assert_eq!(format(&Target::zero()), "0s");
+        assert_eq!(parse("1.5").unwrap(), Target::milliseconds(1500));
+        assert_eq!(
+            parse("1s 2us 3ns").unwrap(),
+            Target::nanoseconds(1_000_002_003)
+        );
+        assert_eq!(format(&Target::nanoseconds(-1_000_002_003)), "-1s 2us 3ns");
+        for d in [Target::max_value(), Target::min_value()] {
+            assert_eq!(parse(&format(&d)).unwrap(), d);
+        }
+        assert_eq!(parse("1y"), Err(ParseError::Unit("y".to_owned())));
+        assert_eq!(parse("h"), Err(ParseError::Format));
+        assert_eq!(parse(""), Err(ParseError::Format));
+    }
+
+    #[test]
+    fn test_serde() {
+        let d = Target::seconds(86_400 + 60);
+        let value = serialize(&d, crate::ser::Serializer).unwrap();
+        assert_eq!(value, Value::Duration(d));
+        assert_eq!(deserialize(value).unwrap(), d);
+        assert_eq!(deserialize(Value::String("1d 1m".to_owned())).unwrap(), d);
+        assert_eq!(
+            serialize(&d, serde_json::value::Serializer).unwrap(),
+            serde_json::json!("1d 1m")
+        );
+        for d in [
+            Target::nanoseconds(-1_500_000_001),
+            Target::max_value(),
+            Target::min_value(),
+        ] {
+            assert_eq!(from_bytes(&to_bytes(&d)).unwrap(), d);
+        }
+
+        let value = Value::Duration(Target::nanoseconds(1_500));
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, r#"{"DU":"1us 500ns"}"#);
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), value);
+        assert_eq!(from_secs_nanos(i64::MAX, 0), Err(ParseError::OutOfRange));
+        assert_eq!(deserialize(Value::U64(86_460)).unwrap(), d);
+        assert_eq!(deserialize(Value::I64(-1)).unwrap(), Target::seconds(-1));
+        assert_eq!(
+            deserialize(Value::F64(0.25)).unwrap(),
+            Target::milliseconds(250)
+        );
+        assert!(deserialize(Value::U64(u64::MAX)).is_err());
+        assert_eq!(
+            deserialize(Value::Bool(true)).unwrap_err().to_string(),
+            "invalid type: boolean `true`, expected duration string or seconds"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Serde helper for 20-byte EVM addresses.
+//!
+//! Addresses are serialized to [`Value::EvmAddress`][crate::Value::EvmAddress]. They can be
+//! deserialized from 20 bytes, from hex strings, which must have a valid [EIP-55] checksum if they
+//! are mixed-case, and from 32-byte Wormhole addresses that are left-padded with zeros.
+//!
+//! [EIP-55]: https://eips.ethereum.org/EIPS/eip-55
+
+use sha3::{Digest, Keccak256};
+use thiserror::Error as ThisError;
+
+pub(crate) const TOKEN: &str = "$$e";
+
+pub type Target = [u8; 20];
+
+#[derive(ThisError, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("invalid hex string")]
+    Hex,
+    #[error("expected 20 bytes, got {0}")]
+    Length(usize),
+    #[error("invalid EIP-55 checksum")]
+    Checksum,
+}
+
+/// Parse a hex address, with or without `0x`.
+pub fn parse(s: &str) -> Result<Target, ParseError> {
+    let hex = s.strip_prefix("0x").unwrap_or(s);
+    if !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ParseError::Hex);
+    }
+    if hex.len() != 40 {
+        return Err(ParseError::Length(hex.len() / 2));
+    }
+    let mut address = [0u8; 20];
+    for (i, byte) in address.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| ParseError::Hex)?;
+    }
+    let mixed_case =
+        hex.bytes().any(|c| c.is_ascii_lowercase()) && hex.bytes().any(|c| c.is_ascii_uppercase());
+    if mixed_case && to_checksum(&address)[2..] != *hex {
+        return Err(ParseError::Checksum);
+    }
+    Ok(address)
+}
+
+/// Format an address as `0x`-prefixed hex with EIP-55 checksum.
+pub fn to_checksum(address: &Target) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let lower = address
+        .iter()
+        .flat_map(|b| [DIGITS[(b >> 4) as usize], DIGITS[(b & 0xf) as usize]])
+        .collect::<Vec<u8>>();
+    let hash = Keccak256::digest(&lower);
+    let mut s = String::with_capacity(42);
+    s.push_str("0x");
+    for (i, c) in lower.into_iter().enumerate() {
+        let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0xf;
+        s.push(if nibble >= 8 {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        } as char);
+    }
+    s
+}
+
+/// Left-pad to a 32-byte Wormhole address.
+pub fn to_universal(address: &Target) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[12..].copy_from_slice(address);
+    bytes
+}
+
+/// Convert a 32-byte Wormhole address, `None` if it is not left-padded with zeros.
+pub fn from_universal(bytes: &[u8; 32]) -> Option<Target> {
+    bytes[..12]
+        .iter()
+        .all(|b| *b == 0)
+        .then(|| bytes[12..].try_into().unwrap())
+}
+
+pub mod opt {
+    pub fn serialize<S>(a: &Option<super::Target>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match a {
+            Some(a) => super::serialize(a, s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<super::Target>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        d.deserialize_option(crate::OptionVisitor(super::Visitor))
+    }
+}
+
+pub fn serialize<S>(a: &Target, s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    s.serialize_newtype_struct(TOKEN, &crate::Bytes(a))
+}
+
+struct Visitor;
+
+impl<'de> serde::de::Visitor<'de> for Visitor {
+    type Value = Target;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("EVM address as bytes or hex string")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match v.len() {
+            20 => Ok(v.try_into().unwrap()),
+            32 => from_universal(v.try_into().unwrap()).ok_or_else(|| {
+                serde::de::Error::invalid_value(
+                    serde::de::Unexpected::Bytes(v),
+                    &"32 bytes left-padded with zeros",
+                )
+            }),
+            l => Err(serde::de::Error::invalid_length(l, &"20 or 32")),
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        parse(v).map_err(serde::de::Error::custom)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let mut buf = [0u8; 32];
+        let mut size = 0;
+        while let Some(byte) = seq.next_element()? {
+            if size == buf.len() {
+                return Err(serde::de::Error::custom("array has more than 32 elements"));
+            }
+            buf[size] = byte;
+            size += 1;
+        }
+        self.visit_bytes(&buf[..size])
+    }
+
+    fn visit_newtype_struct<D>(self, d: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        d.deserialize_any(self)
+    }
+}
+
+pub fn deserialize<'de, D>(d: D) -> Result<Target, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    d.deserialize_newtype_struct(TOKEN, Visitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    // from EIP-55
+    const ADDRESSES: &[&str] = &[
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn test_checksum() {
+        for s in ADDRESSES {
+            let address = parse(s).unwrap();
+            assert_eq!(to_checksum(&address), *s);
+            assert_eq!(parse(&s.to_lowercase()).unwrap(), address);
+            assert_eq!(parse(&s[2..].to_uppercase()).unwrap(), address);
+        }
+        let wrong = ADDRESSES[0].replace('a', "A");
+        assert_eq!(parse(&wrong), Err(ParseError::Checksum));
+        assert_eq!(parse("0x1234"), Err(ParseError::Length(2)));
+        assert_eq!(parse("0xzz"), Err(ParseError::Hex));
+    }
+
+    #[test]
+    fn test_serde() {
+        let address = parse(ADDRESSES[1]).unwrap();
+        let value = serialize(&address, crate::ser::Serializer).unwrap();
+        assert_eq!(value, Value::EvmAddress(address));
+        assert_eq!(deserialize(value).unwrap(), address);
+        assert_eq!(
+            deserialize(Value::String(ADDRESSES[1].to_owned())).unwrap(),
+            address
+        );
+        assert_eq!(
+            deserialize(Value::B32(to_universal(&address))).unwrap(),
+            address
+        );
+        assert_eq!(
+            deserialize(Value::Bytes(address.to_vec().into())).unwrap(),
+            address
+        );
+        assert!(deserialize(Value::B32([1; 32])).is_err());
+    }
+}
@@ -15,7 +15,10 @@ use thiserror::Error as ThisError;
 pub use rust_decimal::Decimal;
 
 pub(crate) mod value_type;
-pub use value_type::keys;
+pub use value_type::{keys, ValueType};
+
+mod coerce;
+pub use coerce::{coerce, coerce_any, CoerceError};
 
 pub(crate) const TOKEN: &str = "$V";
 
@@ -24,17 +27,28 @@ pub use de::const_bytes::ConstBytes;
 
 mod ser;
 
+pub mod binary;
+pub mod canonical;
 pub mod crud;
 pub mod macros;
+pub mod num;
+pub mod schema;
+#[cfg(feature = "table")]
+pub mod table;
 
 // custom serialize and deserialize modules
 pub mod decimal;
+pub mod duration;
+pub mod evm_address;
+pub mod i256;
 #[cfg(feature = "solana")]
 pub mod keypair;
 #[cfg(feature = "solana")]
 pub mod pubkey;
 #[cfg(feature = "solana")]
 pub mod signature;
+pub mod timestamp;
+pub mod u256;
 
 /// Interpret a [`Value`] as an instance of type `T`
 ///
@@ -145,9 +159,11 @@ pub type Map = self::HashMap<Key, Value>;
 ///     - Null: [`Value::Null`].
 ///     - Boolean: [`Value::Bool`].
 ///     - Numbers: [`Value::U64`], [`Value::I64`], [`Value::U128`], [`Value::I128`], [`Value::Decimal`],
-///     [`Value::F64`].
+///     [`Value::F64`], [`Value::U256`], [`Value::I256`].
 ///     - String: [`Value::String`].
 ///     - Binary: [`Value::B32`], [`Value::B64`], [`Value::Bytes`].
+///     - EVM address: [`Value::EvmAddress`].
+///     - Time: [`Value::Timestamp`], [`Value::Duration`].
 /// - Array: [`Value::Array`]
 /// - Map: [`Value::Map`]
 ///
@@ -217,6 +233,11 @@ pub type Map = self::HashMap<Key, Value>;
 /// - **BY**: [`Value::Bytes`]
 /// - **A**: [`Value::Array`]
 /// - **M**: [`Value::Map`]
+/// - **EA**: [`Value::EvmAddress`]
+/// - **U2**: [`Value::U256`]
+/// - **I2**: [`Value::I256`]
+/// - **T**: [`Value::Timestamp`]
+/// - **DU**: [`Value::Duration`]
 ///
 /// See variant's documentation to see how data are encoded.
 ///
@@ -342,6 +363,46 @@ pub enum Value {
     /// }
     /// ```
     Map(Map),
+    /// 20-bytes EVM address, see [`evm_address`].
+    ///
+    /// JSON representation: encoded as a hex string with [EIP-55] checksum
+    /// ```json
+    /// { "EA": "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed" }
+    /// ```
+    ///
+    /// [EIP-55]: https://eips.ethereum.org/EIPS/eip-55
+    EvmAddress([u8; 20]),
+    /// Unsigned 256-bit integer, such as an ERC-20 amount.
+    ///
+    /// JSON representation:
+    /// ```json
+    /// { "U2": "115792089237316195423570985008687907853269984665640564039457584007913129639935" }
+    /// ```
+    U256(ethnum::U256),
+    /// Signed 256-bit integer.
+    ///
+    /// JSON representation:
+    /// ```json
+    /// { "I2": "-57896044618658097711785492504343953926634992332820282019728792003956564819968" }
+    /// ```
+    I256(ethnum::I256),
+    /// Point in time in UTC, see [`timestamp`].
+    ///
+    /// JSON representation: encoded as an [RFC 3339] string, with fractional seconds only if
+    /// there are any
+    /// ```json
+    /// { "T": "2024-01-31T12:00:00.250Z" }
+    /// ```
+    ///
+    /// [RFC 3339]: https://www.rfc-editor.org/rfc/rfc3339
+    Timestamp(chrono::DateTime<chrono::Utc>),
+    /// Signed duration, see [`duration`].
+    ///
+    /// JSON representation: encoded as a duration string, with nanosecond precision
+    /// ```json
+    /// { "DU": "1d 2h 30m 500ms 250us" }
+    /// ```
+    Duration(chrono::Duration),
 }
 
 impl Value {
@@ -370,7 +431,10 @@ impl Value {
             | Value::F64(_)
             | Value::B32(_)
             | Value::B64(_)
-            | Value::Bytes(_) => self,
+            | Value::Bytes(_)
+            | Value::EvmAddress(_)
+            | Value::Timestamp(_)
+            | Value::Duration(_) => self,
             Value::Decimal(mut d) => {
                 d.normalize_assign();
                 if d.scale() == 0 {
@@ -386,6 +450,12 @@ impl Value {
             }
             .unwrap_or(self),
             Value::U128(u) => u64::try_from(u).map(Value::U64).unwrap_or(self),
+            Value::U256(u) => u128::try_from(u)
+                .map(|u| Value::U128(u).normalize())
+                .unwrap_or(self),
+            Value::I256(i) => i128::try_from(i)
+                .map(|i| Value::I128(i).normalize())
+                .unwrap_or(self),
             Value::Array(mut a) => {
                 for v in &mut a {
                     *v = std::mem::take(v).normalize();
@@ -491,6 +561,15 @@ mod json {
                 Value::B32(b) => (&b[..]).into(),
                 Value::B64(b) => (&b[..]).into(),
                 Value::Bytes(b) => (&b[..]).into(),
+                Value::EvmAddress(a) => crate::evm_address::to_checksum(&a).into(),
+                Value::U256(value) => u64::try_from(value)
+                    .map(u64::into)
+                    .unwrap_or_else(|_| value.to_string().into()),
+                Value::I256(value) => i64::try_from(value)
+                    .map(i64::into)
+                    .unwrap_or_else(|_| value.to_string().into()),
+                Value::Timestamp(t) => crate::timestamp::format(&t).into(),
+                Value::Duration(d) => crate::duration::format(&d).into(),
             }
         }
     }
@@ -592,6 +671,36 @@ impl From<f64> for Value {
     }
 }
 
+impl From<ethnum::U256> for Value {
+    fn from(x: ethnum::U256) -> Self {
+        Self::U256(x)
+    }
+}
+
+impl From<ethnum::I256> for Value {
+    fn from(x: ethnum::I256) -> Self {
+        Self::I256(x)
+    }
+}
+
+impl From<chrono::DateTime<chrono::Utc>> for Value {
+    fn from(x: chrono::DateTime<chrono::Utc>) -> Self {
+        Self::Timestamp(x)
+    }
+}
+
+impl From<chrono::Duration> for Value {
+    fn from(x: chrono::Duration) -> Self {
+        Self::Duration(x)
+    }
+}
+
+impl From<[u8; 20]> for Value {
+    fn from(x: [u8; 20]) -> Self {
+        Self::EvmAddress(x)
+    }
+}
+
 impl From<[u8; 32]> for Value {
     fn from(x: [u8; 32]) -> Self {
         Self::B32(x)
@@ -625,6 +734,58 @@ impl From<solana_sdk::signature::Signature> for Value {
     }
 }
 
+#[cfg(feature = "wormhole")]
+impl From<wormhole_sdk::Address> for Value {
+    fn from(x: wormhole_sdk::Address) -> Self {
+        Self::B32(x.0)
+    }
+}
+
+/// Read a 32-byte Wormhole address. [`Value::EvmAddress`] is left-padded with zeros, see
+/// [`evm_address::to_universal`], shorter bytes and hex strings are also left-padded.
+#[cfg(feature = "wormhole")]
+impl TryFrom<Value> for wormhole_sdk::Address {
+    type Error = Error;
+
+    fn try_from(x: Value) -> Result<Self, Self::Error> {
+        let bytes = match x {
+            Value::B32(x) => return Ok(Self(x)),
+            Value::EvmAddress(x) => return Ok(Self(evm_address::to_universal(&x))),
+            Value::Bytes(x) => x.to_vec(),
+            Value::String(x) => {
+                let hex = x.trim().strip_prefix("0x").unwrap_or(x.trim());
+                if hex.len() == 40 {
+                    let address =
+                        evm_address::parse(hex).map_err(|e| Error::Custom(e.to_string()))?;
+                    return Ok(Self(evm_address::to_universal(&address)));
+                }
+                if hex.len() % 2 != 0 || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(Error::Custom("invalid hex string".to_owned()));
+                }
+                (0..hex.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                    .collect()
+            }
+            x => {
+                return Err(Error::Custom(format!(
+                    "expected an address, got {:?}",
+                    x.kind()
+                )))
+            }
+        };
+        if bytes.len() > 32 {
+            return Err(Error::InvalidLenght {
+                need: 32,
+                got: bytes.len(),
+            });
+        }
+        let mut address = [0u8; 32];
+        address[32 - bytes.len()..].copy_from_slice(&bytes);
+        Ok(Self(address))
+    }
+}
+
 impl From<bytes::Bytes> for Value {
     fn from(x: bytes::Bytes) -> Self {
         match x.len() {
@@ -690,6 +851,17 @@ impl std::fmt::Debug for Value {
                 .debug_tuple("B64")
                 .field(&bs58::encode(x).into_string())
                 .finish(),
+            Value::EvmAddress(x) => f
+                .debug_tuple("EvmAddress")
+                .field(&evm_address::to_checksum(x))
+                .finish(),
+            Value::U256(x) => f.debug_tuple("U256").field(x).finish(),
+            Value::I256(x) => f.debug_tuple("I256").field(x).finish(),
+            Value::Timestamp(x) => f
+                .debug_tuple("Timestamp")
+                .field(&timestamp::format(x))
+                .finish(),
+            Value::Duration(x) => f.debug_tuple("Duration").field(x).finish(),
         }
     }
 }
@@ -862,6 +1034,28 @@ mod tests {
             ])),
             r#"{"BY":"aGVsbG8gd29ybGQ="}"#,
         );
+        t(
+            Value::EvmAddress(
+                evm_address::parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap(),
+            ),
+            r#"{"EA":"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"}"#,
+        );
+        t(
+            Value::U256(ethnum::U256::MAX),
+            r#"{"U2":"115792089237316195423570985008687907853269984665640564039457584007913129639935"}"#,
+        );
+        t(
+            Value::I256(ethnum::I256::MIN),
+            r#"{"I2":"-57896044618658097711785492504343953926634992332820282019728792003956564819968"}"#,
+        );
+        t(
+            Value::Timestamp(timestamp::parse("2024-01-31T12:00:00.250Z").unwrap()),
+            r#"{"T":"2024-01-31T12:00:00.250Z"}"#,
+        );
+        t(
+            Value::Duration(chrono::Duration::milliseconds(-95_400_500)),
+            r#"{"DU":"-1d 2h 30m 500ms"}"#,
+        );
     }
 
     #[test]
@@ -881,6 +1075,37 @@ mod tests {
         )
     }
 
+    #[cfg(feature = "wormhole")]
+    #[test]
+    fn test_wormhole_address() {
+        let evm = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let universal = evm_address::to_universal(&evm_address::parse(evm).unwrap());
+        let read = |v: Value| wormhole_sdk::Address::try_from(v).map(|a| a.0);
+
+        assert_eq!(read(Value::from(evm)).unwrap(), universal);
+        assert_eq!(
+            read(Value::EvmAddress(evm_address::parse(evm).unwrap())).unwrap(),
+            universal
+        );
+        let hex = universal
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+        assert_eq!(read(Value::from(hex)).unwrap(), universal);
+        assert_eq!(read(Value::B32(universal)).unwrap(), universal);
+        assert_eq!(
+            read(Value::Bytes(universal[12..].to_vec().into())).unwrap(),
+            universal
+        );
+        assert_eq!(
+            Value::from(wormhole_sdk::Address(universal)),
+            Value::B32(universal)
+        );
+        assert!(read(Value::from("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD")).is_err());
+        assert!(read(Value::Bytes(vec![0; 33].into())).is_err());
+        assert!(read(Value::U64(1)).is_err());
+    }
+
     #[cfg(feature = "json")]
     #[test]
     fn test_number_into_json() {
@@ -0,0 +1,482 @@
+//! Checked arithmetic and comparison of numeric [`Value`]s.
+//!
+//! Operands are promoted without losing precision:
+//! - Integers (`U64`, `I64`, `U128`, `I128`) are computed as `i128`, or `u128` if the result
+//!   only fits there, then as [`Decimal`]. Integer results are normalized to the smallest
+//!   variant, like [`Value::normalize`], non-negative results are unsigned. `U256` and `I256`
+//!   are computed the same way if they are in range of `u128` or `i128`.
+//! - Division of integers that are not divisible, and operations with a `Decimal`, produce a
+//!   `Decimal`.
+//! - `F64` is only computed as `f64` with another `F64`, it is converted to `Decimal` by its
+//!   shortest representation when combined with an exact number.
+//!
+//! Overflow, division by zero and non-numeric operands are errors, never wrapped or rounded to
+//! `f64`.
+
+use crate::{Decimal, Value};
+use rust_decimal::prelude::ToPrimitive;
+use std::cmp::Ordering;
+use thiserror::Error as ThisError;
+
+#[derive(ThisError, Debug, Clone, PartialEq)]
+pub enum NumError {
+    #[error("expected a number, found {0}")]
+    NotANumber(&'static str),
+    #[error("arithmetic overflow")]
+    Overflow,
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("exponent must be an integer")]
+    InvalidExponent,
+    #[error("{0} is not a finite number")]
+    NotFinite(f64),
+    #[error("invalid number: {0}")]
+    Parse(String),
+}
+
+/// Arithmetic operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+/// Parse a string to the smallest integer variant that fits, or a [`Decimal`].
+/// Scientific notation is supported.
+pub fn parse(s: &str) -> Result<Value, NumError> {
+    let s = s.trim();
+    if let Ok(n) = s.parse::<i128>() {
+        return Ok(Value::I128(n).normalize());
+    }
+    if let Ok(n) = s.parse::<u128>() {
+        return Ok(Value::U128(n).normalize());
+    }
+    let d = if s.bytes().any(|c| c == b'e' || c == b'E') {
+        Decimal::from_scientific(s)
+    } else {
+        Decimal::from_str_exact(s)
+    };
+    d.map(Value::Decimal)
+        .map_err(|e| NumError::Parse(e.to_string()))
+}
+
+/// A numeric value.
+#[derive(Debug, Clone, Copy)]
+enum Num {
+    Int(i128),
+    /// Only used for values greater than `i128::MAX`.
+    BigUint(u128),
+    Decimal(Decimal),
+    F64(f64),
+}
+
+fn num(value: &Value) -> Result<Num, NumError> {
+    Ok(match value {
+        Value::U64(n) => Num::Int((*n).into()),
+        Value::I64(n) => Num::Int((*n).into()),
+        Value::I128(n) => Num::Int(*n),
+        Value::U128(n) => match i128::try_from(*n) {
+            Ok(n) => Num::Int(n),
+            Err(_) => Num::BigUint(*n),
+        },
+        Value::U256(n) => match (i128::try_from(*n), u128::try_from(*n)) {
+            (Ok(n), _) => Num::Int(n),
+            (_, Ok(n)) => Num::BigUint(n),
+            _ => return Err(NumError::Overflow),
+        },
+        Value::I256(n) => Num::Int(i128::try_from(*n).map_err(|_| NumError::Overflow)?),
+        Value::Decimal(d) => Num::Decimal(*d),
+        Value::F64(x) => Num::F64(*x),
+        Value::Null => return Err(NumError::NotANumber("null")),
+        Value::String(_) => return Err(NumError::NotANumber("string")),
+        Value::Bool(_) => return Err(NumError::NotANumber("boolean")),
+        Value::B32(_) | Value::B64(_) | Value::Bytes(_) | Value::EvmAddress(_) => {
+            return Err(NumError::NotANumber("bytes"))
+        }
+        Value::Timestamp(_) => return Err(NumError::NotANumber("timestamp")),
+        Value::Duration(_) => return Err(NumError::NotANumber("duration")),
+        Value::Array(_) => return Err(NumError::NotANumber("array")),
+        Value::Map(_) => return Err(NumError::NotANumber("map")),
+    })
+}
+
+/// Non-negative results are unsigned.
+fn int_value(n: i128) -> Value {
+    match u128::try_from(n) {
+        Ok(n) => uint_value(n),
+        Err(_) => Value::I128(n).normalize(),
+    }
+}
+
+fn uint_value(n: u128) -> Value {
+    Value::U128(n).normalize()
+}
+
+impl Num {
+    fn to_u128(self) -> Option<u128> {
+        match self {
+            Num::Int(n) => u128::try_from(n).ok(),
+            Num::BigUint(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Exact conversion to `Decimal`, floats are converted by their shortest representation.
+    fn to_decimal(self) -> Result<Decimal, NumError> {
+        match self {
+            Num::Int(n) => Decimal::try_from_i128_with_scale(n, 0).map_err(|_| NumError::Overflow),
+            Num::BigUint(_) => Err(NumError::Overflow),
+            Num::Decimal(d) => Ok(d),
+            Num::F64(x) => {
+                if !x.is_finite() {
+                    return Err(NumError::NotFinite(x));
+                }
+                Decimal::from_str_exact(&x.to_string()).map_err(|_| NumError::Overflow)
+            }
+        }
+    }
+
+    fn is_zero(self) -> bool {
+        match self {
+            Num::Int(n) => n == 0,
+            Num::BigUint(_) => false,
+            Num::Decimal(d) => d.is_zero(),
+            Num::F64(x) => x == 0.0,
+        }
+    }
+}
+
+fn apply(op: Op, a: &Value, b: &Value) -> Result<Value, NumError> {
+    let (x, y) = (num(a)?, num(b)?);
+    if matches!(op, Op::Div | Op::Rem) && y.is_zero() {
+        return Err(NumError::DivisionByZero);
+    }
+    match (x, y) {
+        (Num::F64(x), Num::F64(y)) => Ok(Value::F64(match op {
+            Op::Add => x + y,
+            Op::Sub => x - y,
+            Op::Mul => x * y,
+            Op::Div => x / y,
+            Op::Rem => x % y,
+            Op::Pow => x.powf(y),
+        })),
+        (Num::Int(_) | Num::BigUint(_), Num::Int(_) | Num::BigUint(_)) => integer(op, x, y),
+        _ => decimal(op, x.to_decimal()?, y),
+    }
+}
+
+fn integer(op: Op, x: Num, y: Num) -> Result<Value, NumError> {
+    if let (Num::Int(a), Num::Int(b)) = (x, y) {
+        let result = match op {
+            Op::Add => a.checked_add(b),
+            Op::Sub => a.checked_sub(b),
+            Op::Mul => a.checked_mul(b),
+            Op::Div if a.checked_rem(b) == Some(0) => a.checked_div(b),
+            Op::Div => None,
+            Op::Rem => a.checked_rem(b),
+            Op::Pow => match u32::try_from(b) {
+                Ok(b) => a.checked_pow(b),
+                Err(_) if b < 0 => None,
+                Err(_) => return Err(NumError::Overflow),
+            },
+        };
+        if let Some(n) = result {
+            return Ok(int_value(n));
+        }
+    }
+    let unsigned = match (x, y) {
+        // products and quotients of two negatives may only fit in u128
+        (Num::Int(a), Num::Int(b)) if a < 0 && b < 0 && matches!(op, Op::Mul | Op::Div) => {
+            (Some(a.unsigned_abs()), Some(b.unsigned_abs()))
+        }
+        _ => (x.to_u128(), y.to_u128()),
+    };
+    if let (Some(a), Some(b)) = unsigned {
+        let result = match op {
+            Op::Add => a.checked_add(b),
+            Op::Sub => a.checked_sub(b),
+            Op::Mul => a.checked_mul(b),
+            Op::Div if a % b == 0 => a.checked_div(b),
+            Op::Div => None,
+            Op::Rem => a.checked_rem(b),
+            Op::Pow => u32::try_from(b).ok().and_then(|b| a.checked_pow(b)),
+        };
+        if let Some(n) = result {
+            return Ok(uint_value(n));
+        }
+    }
+    decimal(op, x.to_decimal()?, y)
+}
+
+fn decimal(op: Op, x: Decimal, y: Num) -> Result<Value, NumError> {
+    if op == Op::Pow {
+        return pow(x, y).map(Value::Decimal);
+    }
+    let y = y.to_decimal()?;
+    match op {
+        Op::Add => x.checked_add(y),
+        Op::Sub => x.checked_sub(y),
+        Op::Mul => x.checked_mul(y),
+        Op::Div => x.checked_div(y),
+        Op::Rem => x.checked_rem(y),
+        Op::Pow => unreachable!(),
+    }
+    .map(Value::Decimal)
+    .ok_or(NumError::Overflow)
+}
+
+fn pow(x: Decimal, y: Num) -> Result<Decimal, NumError> {
+    let y = match y {
+        Num::Int(n) => n,
+        Num::BigUint(_) => return Err(NumError::Overflow),
+        y => {
+            let d = y.to_decimal()?.normalize();
+            if d.scale() != 0 {
+                return Err(NumError::InvalidExponent);
+            }
+            d.mantissa()
+        }
+    };
+    let mut exponent = y.unsigned_abs();
+    let mut base = x;
+    let mut result = Decimal::ONE;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.checked_mul(base).ok_or(NumError::Overflow)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = base.checked_mul(base).ok_or(NumError::Overflow)?;
+        }
+    }
+    if y < 0 {
+        if result.is_zero() {
+            return Err(NumError::DivisionByZero);
+        }
+        result = Decimal::ONE.checked_div(result).ok_or(NumError::Overflow)?;
+    }
+    Ok(result)
+}
+
+/// Compare a float to an exact number, `x` must not be NaN.
+fn cmp_float(x: f64, y: Num) -> Ordering {
+    // out of range of Decimal, or an integer that does not fit Decimal
+    let exact = match y {
+        Num::Int(n) => Decimal::try_from_i128_with_scale(n, 0).ok(),
+        Num::BigUint(_) => None,
+        Num::Decimal(d) => Some(d),
+        Num::F64(_) => unreachable!(),
+    };
+    match (Decimal::from_f64_retain(x), exact) {
+        (Some(x), Some(y)) => x.cmp(&y),
+        _ => {
+            // one of them is beyond the range of Decimal, so it has the larger magnitude
+            let y = match y {
+                Num::Int(n) => n as f64,
+                Num::BigUint(n) => n as f64,
+                Num::Decimal(d) => d.to_f64().unwrap_or_default(),
+                Num::F64(_) => unreachable!(),
+            };
+            x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+        }
+    }
+}
+
+/// Compare a decimal to an integer that may not fit in `Decimal`.
+fn cmp_decimal(a: Decimal, b: i128) -> Ordering {
+    match Decimal::try_from_i128_with_scale(b, 0) {
+        Ok(b) => a.cmp(&b),
+        Err(_) if b > 0 => Ordering::Less,
+        Err(_) => Ordering::Greater,
+    }
+}
+
+impl Value {
+    /// `self + other`, see [`crate::num`] for promotion rules.
+    pub fn checked_add(&self, other: &Value) -> Result<Value, NumError> {
+        apply(Op::Add, self, other)
+    }
+
+    /// `self - other`
+    pub fn checked_sub(&self, other: &Value) -> Result<Value, NumError> {
+        apply(Op::Sub, self, other)
+    }
+
+    /// `self * other`
+    pub fn checked_mul(&self, other: &Value) -> Result<Value, NumError> {
+        apply(Op::Mul, self, other)
+    }
+
+    /// `self / other`, exact unless the result is a `Decimal` with more than 28 digits.
+    pub fn checked_div(&self, other: &Value) -> Result<Value, NumError> {
+        apply(Op::Div, self, other)
+    }
+
+    /// `self % other`
+    pub fn checked_rem(&self, other: &Value) -> Result<Value, NumError> {
+        apply(Op::Rem, self, other)
+    }
+
+    /// `self` to the power of `other`, `other` must be an integer unless both are `F64`.
+    pub fn checked_pow(&self, other: &Value) -> Result<Value, NumError> {
+        apply(Op::Pow, self, other)
+    }
+
+    /// Apply `op` to `self` and `other`.
+    pub fn checked_op(&self, op: Op, other: &Value) -> Result<Value, NumError> {
+        apply(op, self, other)
+    }
+
+    /// `-self`
+    pub fn checked_neg(&self) -> Result<Value, NumError> {
+        match num(self)? {
+            Num::Int(n) => n.checked_neg().map(int_value).ok_or(NumError::Overflow),
+            Num::BigUint(n) => (n == 1 << 127)
+                .then_some(Value::I128(i128::MIN))
+                .ok_or(NumError::Overflow),
+            Num::Decimal(d) => Ok(Value::Decimal(-d)),
+            Num::F64(x) => Ok(Value::F64(-x)),
+        }
+    }
+
+    /// Compare numbers of any variant by their exact values.
+    pub fn num_cmp(&self, other: &Value) -> Result<Ordering, NumError> {
+        let (x, y) = (num(self)?, num(other)?);
+        Ok(match (x, y) {
+            (Num::F64(a), _) | (_, Num::F64(a)) if a.is_nan() => {
+                return Err(NumError::NotFinite(a))
+            }
+            (Num::F64(a), Num::F64(b)) => a.partial_cmp(&b).unwrap(),
+            (Num::F64(a), y) => cmp_float(a, y),
+            (x, Num::F64(b)) => cmp_float(b, x).reverse(),
+            (Num::Int(a), Num::Int(b)) => a.cmp(&b),
+            (Num::BigUint(a), Num::BigUint(b)) => a.cmp(&b),
+            (Num::BigUint(_), _) => Ordering::Greater,
+            (_, Num::BigUint(_)) => Ordering::Less,
+            (Num::Decimal(a), Num::Decimal(b)) => a.cmp(&b),
+            (Num::Decimal(a), Num::Int(b)) => cmp_decimal(a, b),
+            (Num::Int(a), Num::Decimal(b)) => cmp_decimal(b, a).reverse(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_arithmetic() {
+        let v = |n: u64| Value::U64(n);
+        assert_eq!(v(1).checked_add(&v(2)).unwrap(), v(3));
+        assert_eq!(v(1).checked_sub(&v(2)).unwrap(), Value::I64(-1));
+        assert_eq!(
+            v(u64::MAX).checked_add(&v(1)).unwrap(),
+            Value::U128(u64::MAX as u128 + 1)
+        );
+        assert_eq!(
+            Value::U128(u128::MAX)
+                .checked_sub(&Value::U128(u128::MAX - 1))
+                .unwrap(),
+            v(1)
+        );
+        assert_eq!(
+            Value::U128(u128::MAX).checked_add(&v(1)),
+            Err(NumError::Overflow)
+        );
+        assert_eq!(v(6).checked_div(&v(3)).unwrap(), v(2));
+        assert_eq!(v(7).checked_div(&v(2)).unwrap(), Value::Decimal(dec!(3.5)));
+        assert_eq!(v(7).checked_rem(&v(2)).unwrap(), v(1));
+        assert_eq!(v(7).checked_div(&v(0)), Err(NumError::DivisionByZero));
+        assert_eq!(
+            Value::I128(i128::MIN).checked_div(&Value::I64(-1)).unwrap(),
+            Value::U128(1 << 127)
+        );
+        assert_eq!(v(2).checked_pow(&v(100)).unwrap(), Value::U128(1 << 100));
+        assert_eq!(
+            v(2).checked_pow(&Value::I64(-2)).unwrap(),
+            Value::Decimal(dec!(0.25))
+        );
+
+        // lamports to SOL stay exact
+        let lamports = v(1_234_567_891_234_567_891);
+        let sol = lamports.checked_div(&v(1_000_000_000)).unwrap();
+        assert_eq!(sol, Value::Decimal(dec!(1234567891.234567891)));
+        assert_eq!(
+            sol.checked_mul(&v(1_000_000_000)).unwrap(),
+            Value::Decimal(dec!(1234567891234567891.000000000))
+        );
+
+        assert_eq!(
+            Value::F64(0.1).checked_add(&Value::F64(0.2)).unwrap(),
+            Value::F64(0.1 + 0.2)
+        );
+        assert_eq!(
+            Value::F64(0.1)
+                .checked_add(&Value::Decimal(dec!(0.2)))
+                .unwrap(),
+            Value::Decimal(dec!(0.3))
+        );
+        assert_eq!(
+            Value::String("1".into()).checked_add(&v(1)),
+            Err(NumError::NotANumber("string"))
+        );
+        assert_eq!(
+            Value::I64(i64::MIN).checked_neg().unwrap(),
+            Value::U64(1 << 63)
+        );
+    }
+
+    #[test]
+    fn test_cmp() {
+        let cases = [
+            (Value::U64(1), Value::I64(1), Ordering::Equal),
+            (Value::U64(1), Value::Decimal(dec!(1.0)), Ordering::Equal),
+            (Value::I64(-1), Value::U128(u128::MAX), Ordering::Less),
+            (
+                Value::U128(u128::MAX),
+                Value::Decimal(Decimal::MAX),
+                Ordering::Greater,
+            ),
+            (
+                Value::F64(0.1),
+                Value::Decimal(dec!(0.1)),
+                Ordering::Greater,
+            ),
+            (
+                Value::F64(1e30),
+                Value::Decimal(Decimal::MAX),
+                Ordering::Greater,
+            ),
+            (Value::F64(1e40), Value::U128(u128::MAX), Ordering::Greater),
+            (Value::F64(-0.5), Value::I64(0), Ordering::Less),
+            (
+                Value::Decimal(Decimal::MIN),
+                Value::I128(i128::MIN),
+                Ordering::Greater,
+            ),
+        ];
+        for (a, b, ordering) in cases {
+            assert_eq!(a.num_cmp(&b).unwrap(), ordering, "{a:?} {b:?}");
+            assert_eq!(b.num_cmp(&a).unwrap(), ordering.reverse(), "{b:?} {a:?}");
+        }
+        assert!(Value::F64(f64::NAN).num_cmp(&Value::U64(0)).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse(" 10 ").unwrap(), Value::U64(10));
+        assert_eq!(parse("-10").unwrap(), Value::I64(-10));
+        assert_eq!(
+            parse(&u128::MAX.to_string()).unwrap(),
+            Value::U128(u128::MAX)
+        );
+        assert_eq!(parse("1.50").unwrap(), Value::Decimal(dec!(1.50)));
+        assert_eq!(parse("1e-3").unwrap(), Value::Decimal(dec!(0.001)));
+        assert!(parse("abc").is_err());
+    }
+}
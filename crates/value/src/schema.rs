@@ -0,0 +1,824 @@
+//! Encode and decode [`Value`] to Borsh or bincode bytes, according to a [`Schema`].
+//!
+//! Schemas are written in JSON, primitive types use the same names as node's `type_bounds`:
+//!
+//! ```json
+//! {
+//!     "struct": [
+//!         { "name": "amount", "type": "u64" },
+//!         { "name": "owner", "type": "pubkey" },
+//!         { "name": "memo", "type": { "option": "string" } },
+//!         { "name": "seeds", "type": { "array": "bytes" } },
+//!         { "name": "hash", "type": { "array": ["u8", 32] } },
+//!         { "name": "side", "type": { "enum": ["Bid", { "name": "Ask", "fields": [] }] } },
+//!         { "name": "pair", "type": { "tuple": ["u8", "pubkey"] } }
+//!     ]
+//! }
+//! ```
+//!
+//! Decoding produces the same [`Value`] shapes that encoding accepts: structs are maps, tuples
+//! are arrays, enums are maps with a single key, the variant name, whose value is a map of the
+//! variant's fields, or an array if the fields are a tuple such as `"fields": ["u8", "u64"]`.
+//! Errors are prefixed with the path of the field that failed, e.g. `items.2.owner: expected
+//! public key, found number`.
+
+use crate::{Map, Value};
+use serde::{Deserialize, Serialize};
+use thiserror::Error as ThisError;
+
+#[derive(ThisError, Debug)]
+pub enum Error {
+    #[error("expected {expected}, found {found}")]
+    Expected {
+        expected: &'static str,
+        found: &'static str,
+    },
+    #[error("value is out of range of {0}")]
+    OutOfRange(&'static str),
+    #[error("invalid {ty}: {message}")]
+    Invalid { ty: &'static str, message: String },
+    #[error("expected an array of {expected} elements, found {found}")]
+    ArrayLength { expected: usize, found: usize },
+    #[error("missing field {0}")]
+    MissingField(String),
+    #[error("unknown variant {0}")]
+    UnknownVariant(String),
+    #[error("variant {0} does not exist")]
+    InvalidVariant(u32),
+    #[error("unexpected end of data")]
+    UnexpectedEnd,
+    #[error("{0} bytes left after decoding")]
+    TrailingBytes(usize),
+    #[error("{path}: {error}")]
+    At { path: String, error: Box<Error> },
+}
+
+impl Error {
+    /// Add the name of a field or an array index to the path of the error.
+    pub fn at(self, name: impl std::fmt::Display) -> Self {
+        match self {
+            Error::At { path, error } => Error::At {
+                path: format!("{name}.{path}"),
+                error,
+            },
+            error => Error::At {
+                path: name.to_string(),
+                error: Box::new(error),
+            },
+        }
+    }
+}
+
+/// Binary format to encode to or decode from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// [Borsh](https://borsh.io): `u32` lengths and `u8` enum tags.
+    #[default]
+    Borsh,
+    /// Default options of [bincode](https://docs.rs/bincode/1) 1.x: `u64` lengths and `u32`
+    /// enum tags, little-endian and fixed-size integers.
+    Bincode,
+}
+
+impl Format {
+    fn len_size(self) -> usize {
+        match self {
+            Format::Borsh => 4,
+            Format::Bincode => 8,
+        }
+    }
+
+    fn tag_size(self) -> usize {
+        match self {
+            Format::Borsh => 1,
+            Format::Bincode => 4,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Schema {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    F32,
+    F64,
+    #[serde(alias = "address", alias = "publicKey")]
+    Pubkey,
+    String,
+    Bytes,
+    /// `{ "array": T }` is a length-prefixed array, `{ "array": [T, N] }` is an array of
+    /// `N` elements without prefix.
+    Array(Array),
+    Option(Box<Schema>),
+    Struct(Vec<Field>),
+    /// Elements without prefix, as an array.
+    Tuple(Vec<Schema>),
+    Enum(Vec<Variant>),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Array {
+    Fixed(Box<Schema>, usize),
+    Dynamic(Box<Schema>),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub schema: Schema,
+}
+
+/// Variant of an enum, a unit variant can be written as a string.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "VariantDef")]
+pub struct Variant {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Fields::is_empty")]
+    pub fields: Fields,
+}
+
+/// Fields of an enum variant, named or a tuple.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Fields {
+    Named(Vec<Field>),
+    Tuple(Vec<Schema>),
+}
+
+impl Default for Fields {
+    fn default() -> Self {
+        Fields::Named(Vec::new())
+    }
+}
+
+impl Fields {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Fields::Named(fields) => fields.is_empty(),
+            Fields::Tuple(schemas) => schemas.is_empty(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum VariantDef {
+    Unit(String),
+    Variant {
+        name: String,
+        #[serde(default)]
+        fields: Fields,
+    },
+}
+
+impl From<VariantDef> for Variant {
+    fn from(def: VariantDef) -> Self {
+        match def {
+            VariantDef::Unit(name) => Variant {
+                name,
+                fields: Fields::default(),
+            },
+            VariantDef::Variant { name, fields } => Variant { name, fields },
+        }
+    }
+}
+
+impl Schema {
+    /// Encode `value` to bytes.
+    pub fn encode(&self, format: Format, value: &Value) -> Result<Vec<u8>, Error> {
+        let mut encoder = Encoder {
+            format,
+            data: Vec::new(),
+        };
+        encoder.encode(self, value)?;
+        Ok(encoder.data)
+    }
+
+    /// Decode bytes to a [`Value`], all of `data` must be consumed.
+    pub fn decode(&self, format: Format, data: &[u8]) -> Result<Value, Error> {
+        let (value, rest) = self.decode_prefix(format, data)?;
+        if !rest.is_empty() {
+            return Err(Error::TrailingBytes(rest.len()));
+        }
+        Ok(value)
+    }
+
+    /// Decode a [`Value`] from the start of `data`, returns the bytes after it.
+    pub fn decode_prefix<'a>(
+        &self,
+        format: Format,
+        data: &'a [u8],
+    ) -> Result<(Value, &'a [u8]), Error> {
+        let mut decoder = Decoder { format, data };
+        let value = decoder.decode(self)?;
+        Ok((value, decoder.data))
+    }
+}
+
+struct Encoder {
+    format: Format,
+    data: Vec<u8>,
+}
+
+impl Encoder {
+    fn uint(&mut self, n: u64, size: usize) {
+        self.data.extend(&n.to_le_bytes()[..size]);
+    }
+
+    fn len(&mut self, len: usize) -> Result<(), Error> {
+        if self.format == Format::Borsh && u32::try_from(len).is_err() {
+            return Err(Error::OutOfRange("length"));
+        }
+        self.uint(len as u64, self.format.len_size());
+        Ok(())
+    }
+
+    fn encode(&mut self, schema: &Schema, value: &Value) -> Result<(), Error> {
+        macro_rules! int {
+            ($t:ty, $name:literal) => {{
+                let n = integer(value, $name)?;
+                let n = <$t>::try_from(n).map_err(|_| Error::OutOfRange($name))?;
+                self.data.extend(n.to_le_bytes());
+            }};
+        }
+        match schema {
+            Schema::Bool => match value {
+                Value::Bool(b) => self.data.push(*b as u8),
+                value => return Err(expected("boolean", value)),
+            },
+            Schema::U8 => int!(u8, "u8"),
+            Schema::U16 => int!(u16, "u16"),
+            Schema::U32 => int!(u32, "u32"),
+            Schema::U64 => int!(u64, "u64"),
+            Schema::U128 => match value {
+                Value::U128(n) => self.data.extend(n.to_le_bytes()),
+                Value::String(s) if s.trim().parse::<u128>().is_ok() => self
+                    .data
+                    .extend(s.trim().parse::<u128>().unwrap().to_le_bytes()),
+                _ => int!(u128, "u128"),
+            },
+            Schema::I8 => int!(i8, "i8"),
+            Schema::I16 => int!(i16, "i16"),
+            Schema::I32 => int!(i32, "i32"),
+            Schema::I64 => int!(i64, "i64"),
+            Schema::I128 => int!(i128, "i128"),
+            Schema::F32 => self
+                .data
+                .extend((float(value, "f32")? as f32).to_le_bytes()),
+            Schema::F64 => self.data.extend(float(value, "f64")?.to_le_bytes()),
+            Schema::Pubkey => self.data.extend(pubkey(value)?),
+            Schema::String => match value {
+                Value::String(s) => {
+                    self.len(s.len())?;
+                    self.data.extend(s.as_bytes());
+                }
+                value => return Err(expected("string", value)),
+            },
+            Schema::Bytes => {
+                let bytes = bytes(value)?;
+                self.len(bytes.len())?;
+                self.data.extend(bytes);
+            }
+            Schema::Array(Array::Fixed(schema, len)) => {
+                let elements = elements(value)?;
+                if elements.len() != *len {
+                    return Err(Error::ArrayLength {
+                        expected: *len,
+                        found: elements.len(),
+                    });
+                }
+                self.elements(schema, &elements)?;
+            }
+            Schema::Array(Array::Dynamic(schema)) => {
+                let elements = elements(value)?;
+                self.len(elements.len())?;
+                self.elements(schema, &elements)?;
+            }
+            Schema::Option(schema) => match value {
+                Value::Null => self.data.push(0),
+                value => {
+                    self.data.push(1);
+                    self.encode(schema, value)?;
+                }
+            },
+            Schema::Struct(fields) => match value {
+                Value::Map(map) => self.fields(fields, map)?,
+                value => return Err(expected("map", value)),
+            },
+            Schema::Tuple(schemas) => self.tuple(schemas, value)?,
+            Schema::Enum(variants) => {
+                let (name, fields) = match value {
+                    Value::String(name) => (name, None),
+                    Value::Map(map) if map.len() == 1 => {
+                        let (name, fields) = map.first().unwrap();
+                        (name, Some(fields))
+                    }
+                    value => return Err(expected("enum variant", value)),
+                };
+                let (index, variant) = variants
+                    .iter()
+                    .enumerate()
+                    .find(|(_, v)| v.name == *name)
+                    .ok_or_else(|| Error::UnknownVariant(name.clone()))?;
+                self.uint(index as u64, self.format.tag_size());
+                match (&variant.fields, fields) {
+                    (Fields::Named(named), None | Some(Value::Null)) => {
+                        self.fields(named, &Map::new())
+                    }
+                    (Fields::Named(named), Some(Value::Map(map))) => self.fields(named, map),
+                    (Fields::Named(_), Some(value)) => Err(expected("map", value)),
+                    (Fields::Tuple(schemas), None | Some(Value::Null)) => {
+                        self.tuple(schemas, &Value::Array(Vec::new()))
+                    }
+                    (Fields::Tuple(schemas), Some(value)) => self.tuple(schemas, value),
+                }
+                .map_err(|e| e.at(name))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn elements(&mut self, schema: &Schema, elements: &[Value]) -> Result<(), Error> {
+        for (i, value) in elements.iter().enumerate() {
+            self.encode(schema, value).map_err(|e| e.at(i))?;
+        }
+        Ok(())
+    }
+
+    fn tuple(&mut self, schemas: &[Schema], value: &Value) -> Result<(), Error> {
+        let elements = elements(value)?;
+        if elements.len() != schemas.len() {
+            return Err(Error::ArrayLength {
+                expected: schemas.len(),
+                found: elements.len(),
+            });
+        }
+        for (i, (schema, value)) in schemas.iter().zip(&elements).enumerate() {
+            self.encode(schema, value).map_err(|e| e.at(i))?;
+        }
+        Ok(())
+    }
+
+    fn fields(&mut self, fields: &[Field], map: &Map) -> Result<(), Error> {
+        for field in fields {
+            match map.get(&field.name) {
+                Some(value) => self.encode(&field.schema, value),
+                None if matches!(field.schema, Schema::Option(_)) => {
+                    self.encode(&field.schema, &Value::Null)
+                }
+                None => Err(Error::MissingField(field.name.clone())),
+            }
+            .map_err(|e| e.at(&field.name))?;
+        }
+        Ok(())
+    }
+}
+
+struct Decoder<'a> {
+    format: Format,
+    /// Remaining data.
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err(Error::UnexpectedEnd);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn uint(&mut self, size: usize) -> Result<u64, Error> {
+        let mut bytes = [0u8; 8];
+        bytes[..size].copy_from_slice(self.take(size)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn len(&mut self) -> Result<usize, Error> {
+        let len = self.uint(self.format.len_size())?;
+        usize::try_from(len).map_err(|_| Error::OutOfRange("length"))
+    }
+
+    fn decode(&mut self, schema: &Schema) -> Result<Value, Error> {
+        Ok(match schema {
+            Schema::Bool => match self.array::<1>()? {
+                [0] => Value::Bool(false),
+                [1] => Value::Bool(true),
+                [b] => {
+                    return Err(Error::Invalid {
+                        ty: "boolean",
+                        message: format!("invalid byte {b}"),
+                    })
+                }
+            },
+            Schema::U8 => Value::U64(u8::from_le_bytes(self.array()?).into()),
+            Schema::U16 => Value::U64(u16::from_le_bytes(self.array()?).into()),
+            Schema::U32 => Value::U64(u32::from_le_bytes(self.array()?).into()),
+            Schema::U64 => Value::U64(u64::from_le_bytes(self.array()?)),
+            Schema::U128 => Value::U128(u128::from_le_bytes(self.array()?)),
+            Schema::I8 => Value::I64(i8::from_le_bytes(self.array()?).into()),
+            Schema::I16 => Value::I64(i16::from_le_bytes(self.array()?).into()),
+            Schema::I32 => Value::I64(i32::from_le_bytes(self.array()?).into()),
+            Schema::I64 => Value::I64(i64::from_le_bytes(self.array()?)),
+            Schema::I128 => Value::I128(i128::from_le_bytes(self.array()?)),
+            Schema::F32 => Value::F64(f32::from_le_bytes(self.array()?).into()),
+            Schema::F64 => Value::F64(f64::from_le_bytes(self.array()?)),
+            Schema::Pubkey => Value::B32(self.array()?),
+            Schema::String => {
+                let len = self.len()?;
+                let bytes = self.take(len)?;
+                let s = std::str::from_utf8(bytes).map_err(|e| Error::Invalid {
+                    ty: "string",
+                    message: e.to_string(),
+                })?;
+                Value::String(s.to_owned())
+            }
+            Schema::Bytes => {
+                let len = self.len()?;
+                Value::Bytes(bytes::Bytes::copy_from_slice(self.take(len)?))
+            }
+            Schema::Array(Array::Fixed(schema, len)) => self.elements(schema, *len)?,
+            Schema::Array(Array::Dynamic(schema)) => {
+                let len = self.len()?;
+                self.elements(schema, len)?
+            }
+            Schema::Option(schema) => match self.array::<1>()? {
+                [0] => Value::Null,
+                [1] => self.decode(schema)?,
+                [b] => {
+                    return Err(Error::Invalid {
+                        ty: "option",
+                        message: format!("invalid tag {b}"),
+                    })
+                }
+            },
+            Schema::Struct(fields) => Value::Map(self.fields(fields)?),
+            Schema::Tuple(schemas) => self.tuple(schemas)?,
+            Schema::Enum(variants) => {
+                let index = self.uint(self.format.tag_size())?;
+                let variant = usize::try_from(index)
+                    .ok()
+                    .and_then(|i| variants.get(i))
+                    .ok_or(Error::InvalidVariant(index as u32))?;
+                let fields = match &variant.fields {
+                    Fields::Named(named) => self.fields(named).map(Value::Map),
+                    Fields::Tuple(schemas) => self.tuple(schemas),
+                }
+                .map_err(|e| e.at(&variant.name))?;
+                Value::Map(Map::from([(variant.name.clone(), fields)]))
+            }
+        })
+    }
+
+    fn elements(&mut self, schema: &Schema, len: usize) -> Result<Value, Error> {
+        // do not allocate more than the data can hold
+        let mut array = Vec::with_capacity(len.min(self.data.len()));
+        for i in 0..len {
+            let remaining = self.data.len();
+            array.push(self.decode(schema).map_err(|e| e.at(i))?);
+            // elements of 0 bytes could make an array of any length
+            if self.data.len() == remaining && len > remaining {
+                return Err(Error::Invalid {
+                    ty: "array",
+                    message: format!("{len} elements of 0 bytes"),
+                });
+            }
+        }
+        Ok(Value::Array(array))
+    }
+
+    fn tuple(&mut self, schemas: &[Schema]) -> Result<Value, Error> {
+        schemas
+            .iter()
+            .enumerate()
+            .map(|(i, schema)| self.decode(schema).map_err(|e| e.at(i)))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array)
+    }
+
+    fn fields(&mut self, fields: &[Field]) -> Result<Map, Error> {
+        fields
+            .iter()
+            .map(|field| {
+                let value = self.decode(&field.schema).map_err(|e| e.at(&field.name))?;
+                Ok((field.name.clone(), value))
+            })
+            .collect()
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::String(_) => "string",
+        Value::Bool(_) => "boolean",
+        Value::U64(_)
+        | Value::I64(_)
+        | Value::U128(_)
+        | Value::I128(_)
+        | Value::U256(_)
+        | Value::I256(_)
+        | Value::F64(_)
+        | Value::Decimal(_) => "number",
+        Value::B32(_) => "public key",
+        Value::B64(_) => "keypair",
+        Value::Bytes(_) => "bytes",
+        Value::EvmAddress(_) => "EVM address",
+        Value::Timestamp(_) => "timestamp",
+        Value::Duration(_) => "duration",
+        Value::Array(_) => "array",
+        Value::Map(_) => "map",
+    }
+}
+
+fn expected(expected: &'static str, value: &Value) -> Error {
+    Error::Expected {
+        expected,
+        found: kind(value),
+    }
+}
+
+fn integer(value: &Value, ty: &'static str) -> Result<i128, Error> {
+    match value {
+        Value::U64(n) => Ok((*n).into()),
+        Value::I64(n) => Ok((*n).into()),
+        Value::U128(n) => i128::try_from(*n).map_err(|_| Error::OutOfRange(ty)),
+        Value::I128(n) => Ok(*n),
+        Value::U256(n) => i128::try_from(*n).map_err(|_| Error::OutOfRange(ty)),
+        Value::I256(n) => i128::try_from(*n).map_err(|_| Error::OutOfRange(ty)),
+        Value::Decimal(d) => {
+            let d = d.normalize();
+            if d.scale() == 0 {
+                Ok(d.mantissa())
+            } else {
+                Err(Error::Expected {
+                    expected: ty,
+                    found: "decimal",
+                })
+            }
+        }
+        Value::String(s) => s.trim().parse().map_err(|_| Error::Expected {
+            expected: ty,
+            found: "string",
+        }),
+        value => Err(expected(ty, value)),
+    }
+}
+
+fn float(value: &Value, ty: &'static str) -> Result<f64, Error> {
+    match value {
+        Value::F64(n) => Ok(*n),
+        Value::U64(n) => Ok(*n as f64),
+        Value::I64(n) => Ok(*n as f64),
+        Value::Decimal(d) => f64::try_from(*d).map_err(|_| Error::OutOfRange(ty)),
+        Value::String(s) => s.trim().parse().map_err(|_| Error::Expected {
+            expected: ty,
+            found: "string",
+        }),
+        value => Err(expected(ty, value)),
+    }
+}
+
+fn pubkey(value: &Value) -> Result<[u8; 32], Error> {
+    match value {
+        Value::B32(bytes) => Ok(*bytes),
+        Value::B64(bytes) => Ok(bytes[32..].try_into().unwrap()),
+        Value::String(s) => {
+            let mut bytes = [0u8; 32];
+            let len = bs58::decode(s.trim())
+                .into(&mut bytes)
+                .map_err(|e| Error::Invalid {
+                    ty: "public key",
+                    message: e.to_string(),
+                })?;
+            if len != 32 {
+                return Err(Error::Invalid {
+                    ty: "public key",
+                    message: format!("need length 32, got {len}"),
+                });
+            }
+            Ok(bytes)
+        }
+        value => Err(expected("public key", value)),
+    }
+}
+
+fn bytes(value: &Value) -> Result<Vec<u8>, Error> {
+    match value {
+        Value::Bytes(bytes) => Ok(bytes.to_vec()),
+        Value::B32(bytes) => Ok(bytes.to_vec()),
+        Value::B64(bytes) => Ok(bytes.to_vec()),
+        Value::String(s) => Ok(s.as_bytes().to_vec()),
+        Value::Array(array) => array
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let n = integer(v, "u8").map_err(|e| e.at(i))?;
+                u8::try_from(n).map_err(|_| Error::OutOfRange("u8").at(i))
+            })
+            .collect(),
+        value => Err(expected("bytes", value)),
+    }
+}
+
+fn elements(value: &Value) -> Result<Vec<Value>, Error> {
+    match value {
+        Value::Array(array) => Ok(array.clone()),
+        Value::Bytes(bytes) => Ok(bytes.iter().map(|b| Value::U64((*b).into())).collect()),
+        Value::B32(bytes) => Ok(bytes.iter().map(|b| Value::U64((*b).into())).collect()),
+        Value::B64(bytes) => Ok(bytes.iter().map(|b| Value::U64((*b).into())).collect()),
+        value => Err(expected("array", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema(json: serde_json::Value) -> Schema {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let schema = schema(json!({
+            "struct": [
+                { "name": "amount", "type": "u64" },
+                { "name": "delta", "type": "i16" },
+                { "name": "big", "type": "u128" },
+                { "name": "owner", "type": "pubkey" },
+                { "name": "memo", "type": { "option": "string" } },
+                { "name": "seeds", "type": { "array": "bytes" } },
+                { "name": "hash", "type": { "array": ["u8", 4] } },
+                { "name": "side", "type": { "enum": [
+                    "Bid",
+                    { "name": "Ask", "fields": [{ "name": "price", "type": "f64" }] },
+                ] } },
+            ]
+        }));
+        let value = Value::Map(crate::map! {
+            "amount" => 10u64,
+            "delta" => -2i64,
+            "big" => u128::MAX,
+            "owner" => [7u8; 32],
+            "memo" => Value::Null,
+            "seeds" => Value::Array(vec!["ab".into(), Value::Bytes(vec![1, 2, 3].into())]),
+            "hash" => Value::Array(vec![1u64.into(), 2u64.into(), 3u64.into(), 4u64.into()]),
+            "side" => crate::map! { "Ask" => crate::map! { "price" => 1.5f64 } },
+        });
+
+        let borsh = schema.encode(Format::Borsh, &value).unwrap();
+        let mut expected = Vec::new();
+        expected.extend(10u64.to_le_bytes());
+        expected.extend((-2i16).to_le_bytes());
+        expected.extend(u128::MAX.to_le_bytes());
+        expected.extend([7u8; 32]);
+        expected.push(0);
+        expected.extend(2u32.to_le_bytes());
+        expected.extend(2u32.to_le_bytes());
+        expected.extend(b"ab");
+        expected.extend(3u32.to_le_bytes());
+        expected.extend([1, 2, 3, 1, 2, 3, 4]);
+        expected.push(1);
+        expected.extend(1.5f64.to_le_bytes());
+        assert_eq!(borsh, expected);
+
+        let bincode = schema.encode(Format::Bincode, &value).unwrap();
+        assert_eq!(bincode.len(), borsh.len() + 3 * 4 + 3);
+        assert_eq!(&bincode[59..67], &2u64.to_le_bytes());
+        assert_eq!(&bincode[bincode.len() - 12..][..4], &1u32.to_le_bytes());
+
+        let mut value = value;
+        if let Value::Map(map) = &mut value {
+            map.insert(
+                "seeds".into(),
+                Value::Array(vec![
+                    Value::Bytes("ab".into()),
+                    Value::Bytes(vec![1, 2, 3].into()),
+                ]),
+            );
+        }
+        for (format, data) in [(Format::Borsh, &borsh), (Format::Bincode, &bincode)] {
+            assert_eq!(schema.decode(format, data).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_tuple() {
+        let schema = schema(json!({
+            "struct": [
+                { "name": "pair", "type": { "tuple": ["u8", "string"] } },
+                { "name": "kind", "type": { "enum": [
+                    { "name": "Empty" },
+                    { "name": "Tuple", "fields": ["u8", "u128"] },
+                ] } },
+            ]
+        }));
+        let value = Value::Map(crate::map! {
+            "pair" => Value::Array(vec![1u64.into(), "a".into()]),
+            "kind" => crate::map! { "Tuple" => Value::Array(vec![2u64.into(), u128::MAX.into()]) },
+        });
+        let data = schema.encode(Format::Borsh, &value).unwrap();
+        let mut expected = vec![1];
+        expected.extend(1u32.to_le_bytes());
+        expected.extend(b"a");
+        expected.extend([1, 2]);
+        expected.extend(u128::MAX.to_le_bytes());
+        assert_eq!(data, expected);
+        assert_eq!(schema.decode(Format::Borsh, &data).unwrap(), value);
+
+        let padded = [&data[..], &[9]].concat();
+        let (value, rest) = schema.decode_prefix(Format::Borsh, &padded).unwrap();
+        assert_eq!(rest, [9]);
+        assert_eq!(schema.encode(Format::Borsh, &value).unwrap(), data);
+    }
+
+    #[test]
+    fn test_empty_elements() {
+        let schema = schema(json!({ "array": { "struct": [] } }));
+        let empty = Value::Map(Map::new());
+        let (value, _) = schema
+            .decode_prefix(Format::Borsh, &[2, 0, 0, 0, 0, 0])
+            .unwrap();
+        assert_eq!(value, Value::Array(vec![empty.clone(), empty]));
+        // an array of empty elements can not be longer than the data
+        let error = schema
+            .decode(Format::Borsh, &u32::MAX.to_le_bytes())
+            .unwrap_err();
+        assert!(matches!(error, Error::Invalid { ty: "array", .. }));
+    }
+
+    #[test]
+    fn test_errors() {
+        let schema = schema(json!({
+            "struct": [
+                { "name": "items", "type": { "array": { "struct": [
+                    { "name": "owner", "type": "pubkey" },
+                ] } } },
+                { "name": "kind", "type": { "enum": [
+                    { "name": "A", "fields": [{ "name": "x", "type": "u8" }] },
+                ] } },
+            ]
+        }));
+        let error = |value: Value| {
+            schema
+                .encode(Format::Borsh, &value)
+                .unwrap_err()
+                .to_string()
+        };
+        let item = Value::Map(crate::map! { "owner" => [0u8; 32] });
+        assert_eq!(
+            error(Value::Map(crate::map! {
+                "items" => Value::Array(vec![item.clone(), crate::map! { "owner" => 1u64 }.into()]),
+            })),
+            "items.1.owner: expected public key, found number"
+        );
+        assert_eq!(
+            error(Value::Map(crate::map! {
+                "items" => Value::Array(vec![item.clone()]),
+                "kind" => crate::map! { "A" => crate::map! { "x" => 256u64 } },
+            })),
+            "kind.A.x: value is out of range of u8"
+        );
+        assert_eq!(
+            error(Value::Map(crate::map! {
+                "items" => Value::Array(vec![]),
+                "kind" => "B",
+            })),
+            "kind: unknown variant B"
+        );
+
+        let data = [1, 0, 0, 0, 0];
+        assert_eq!(
+            schema.decode(Format::Borsh, &data).unwrap_err().to_string(),
+            "items.0.owner: unexpected end of data"
+        );
+        let mut data = vec![1, 0, 0, 0];
+        data.extend([0u8; 32]);
+        data.extend([0, 5, 9]);
+        assert_eq!(
+            schema.decode(Format::Borsh, &data).unwrap_err().to_string(),
+            "1 bytes left after decoding"
+        );
+    }
+}
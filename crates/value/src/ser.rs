@@ -39,6 +39,25 @@ impl serde::Serialize for Value {
                 Value::Bytes(v) => s.serialize_newtype_variant(TOKEN, i, k, &crate::Bytes(v)),
                 Value::Array(v) => s.serialize_newtype_variant(TOKEN, i, k, &v),
                 Value::Map(v) => s.serialize_newtype_variant(TOKEN, i, k, &v),
+                Value::EvmAddress(v) => s.serialize_newtype_variant(TOKEN, i, k, &crate::Bytes(v)),
+                Value::U256(v) => {
+                    s.serialize_newtype_variant(TOKEN, i, k, &crate::Bytes(&v.to_le_bytes()))
+                }
+                Value::I256(v) => {
+                    s.serialize_newtype_variant(TOKEN, i, k, &crate::Bytes(&v.to_le_bytes()))
+                }
+                Value::Timestamp(v) => s.serialize_newtype_variant(
+                    TOKEN,
+                    i,
+                    k,
+                    &crate::Bytes(&crate::timestamp::to_bytes(v)),
+                ),
+                Value::Duration(v) => s.serialize_newtype_variant(
+                    TOKEN,
+                    i,
+                    k,
+                    &crate::Bytes(&crate::duration::to_bytes(v)),
+                ),
             }
         }
     }
@@ -165,6 +184,11 @@ impl serde::Serializer for Serializer {
             crate::keypair::TOKEN | crate::signature::TOKEN | crate::pubkey::TOKEN => {
                 v.serialize(TaggedBytes::Bytes)
             }
+            crate::evm_address::TOKEN => v.serialize(TaggedBytes::EvmAddress),
+            crate::u256::TOKEN => v.serialize(TaggedBytes::U256),
+            crate::i256::TOKEN => v.serialize(TaggedBytes::I256),
+            crate::timestamp::TOKEN => v.serialize(TaggedBytes::Timestamp),
+            crate::duration::TOKEN => v.serialize(TaggedBytes::Duration),
             _ => v.serialize(self),
         }
     }
@@ -234,6 +258,11 @@ impl serde::Serializer for Serializer {
                 9..=11 => value.serialize(TaggedBytes::Bytes),
                 // Array
                 12 => value.serialize(SerializeSeqNoBytes::default()),
+                14 => value.serialize(TaggedBytes::EvmAddress),
+                15 => value.serialize(TaggedBytes::U256),
+                16 => value.serialize(TaggedBytes::I256),
+                17 => value.serialize(TaggedBytes::Timestamp),
+                18 => value.serialize(TaggedBytes::Duration),
                 // Other variants can map directly to serde's data model
                 _ => value.serialize(Serializer),
             }
@@ -286,6 +315,9 @@ mod tests {
         )));
         t(Value::Array(Vec::new()));
         t(Value::Map(Map::new()));
+        t(Value::EvmAddress([1u8; 20]));
+        t(Value::U256(ethnum::U256::MAX));
+        t(Value::I256(ethnum::I256::MIN));
     }
 
     fn s<T: Serialize>(t: T) -> Value {
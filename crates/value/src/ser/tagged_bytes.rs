@@ -5,6 +5,18 @@ use serde::ser::Impossible;
 pub enum TaggedBytes {
     Decimal,
     Bytes,
+    EvmAddress,
+    U256,
+    I256,
+    Timestamp,
+    Duration,
+}
+
+fn le_bytes<const N: usize>(value: &[u8]) -> Result<[u8; N], Error> {
+    value.try_into().map_err(|_| Error::InvalidLenght {
+        need: N,
+        got: value.len(),
+    })
 }
 
 impl serde::Serializer for TaggedBytes {
@@ -32,6 +44,20 @@ impl serde::Serializer for TaggedBytes {
                 })?,
             ))),
             Self::Bytes => Ok(Value::from(value)),
+            Self::EvmAddress => Ok(Value::EvmAddress(value.try_into().map_err(|_| {
+                Error::InvalidLenght {
+                    need: 20,
+                    got: value.len(),
+                }
+            })?)),
+            Self::U256 => Ok(Value::U256(ethnum::U256::from_le_bytes(le_bytes(value)?))),
+            Self::I256 => Ok(Value::I256(ethnum::I256::from_le_bytes(le_bytes(value)?))),
+            Self::Timestamp => crate::timestamp::from_bytes(&le_bytes(value)?)
+                .map(Value::Timestamp)
+                .map_err(|e| Error::Custom(e.to_string())),
+            Self::Duration => crate::duration::from_bytes(&le_bytes(value)?)
+                .map(Value::Duration)
+                .map_err(|e| Error::Custom(e.to_string())),
         }
     }
 
@@ -46,6 +46,17 @@ impl<'a> serde::Serialize for TextRepr<'a> {
                 s.serialize_newtype_variant(NAME, i, k, &bs58::encode(v).into_string())
             }
             Value::Bytes(v) => s.serialize_newtype_variant(NAME, i, k, &base64::encode(v)),
+            Value::EvmAddress(v) => {
+                s.serialize_newtype_variant(NAME, i, k, &crate::evm_address::to_checksum(v))
+            }
+            Value::U256(v) => s.serialize_newtype_variant(NAME, i, k, &v.to_string()),
+            Value::I256(v) => s.serialize_newtype_variant(NAME, i, k, &v.to_string()),
+            Value::Timestamp(v) => {
+                s.serialize_newtype_variant(NAME, i, k, &crate::timestamp::format(v))
+            }
+            Value::Duration(v) => {
+                s.serialize_newtype_variant(NAME, i, k, &crate::duration::format(v))
+            }
             Value::Array(v) => s.serialize_newtype_variant(
                 NAME,
                 i,
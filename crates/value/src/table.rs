@@ -0,0 +1,388 @@
+//! Flatten values into tables, for display and CSV export.
+//!
+//! An array of maps becomes one row per map, nested maps become columns with dotted paths
+//! (`{"a": {"b": 1}}` has column `a.b`), and columns are the union of keys of all rows, in the
+//! order they are first seen. Items that are not maps are put in a `value` column. Dots and
+//! backslashes in keys are escaped with a backslash, `{"a.b": 1}` has column `a\.b`.
+
+use crate::{Map, Value};
+use indexmap::IndexSet;
+use serde::{Deserialize, Serialize};
+use thiserror::Error as ThisError;
+
+/// Column of items that are not maps.
+pub const VALUE_COLUMN: &str = "value";
+
+#[derive(ThisError, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("column {0} conflicts with a nested column")]
+    Conflict(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Options {
+    /// Levels of nested maps to flatten, deeper maps are put in a single cell.
+    /// `None` flattens all levels.
+    #[serde(default)]
+    pub depth: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Table {
+    pub columns: Vec<String>,
+    /// Rows have a cell for each column, missing values are [`Value::Null`].
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// Flatten `value`, see the [module documentation][self].
+    pub fn from_value(value: Value, options: &Options) -> Self {
+        let items = match value {
+            Value::Array(items) => items,
+            value => vec![value],
+        };
+        let flat_rows = items
+            .into_iter()
+            .map(|item| {
+                let mut row = Map::new();
+                match item {
+                    Value::Map(map) => flatten(&mut row, None, map, options.depth),
+                    value => {
+                        row.insert(VALUE_COLUMN.to_owned(), value);
+                    }
+                }
+                row
+            })
+            .collect::<Vec<_>>();
+
+        let columns = flat_rows
+            .iter()
+            .flat_map(|row| row.keys())
+            .collect::<IndexSet<_>>()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let rows = flat_rows
+            .into_iter()
+            .map(|mut row| {
+                columns
+                    .iter()
+                    .map(|c| row.swap_remove(c).unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        Self { columns, rows }
+    }
+
+    /// Convert to an array of maps. If `nested` is true, dotted columns become nested maps, and
+    /// a column with a value where another column has nested values is an error.
+    pub fn into_value(self, nested: bool) -> Result<Value, Error> {
+        let columns = self.columns;
+        let paths = columns.iter().map(|c| split_path(c)).collect::<Vec<_>>();
+        let rows = self
+            .rows
+            .into_iter()
+            .map(|row| {
+                let mut map = Map::with_capacity(columns.len());
+                for ((column, path), value) in columns.iter().zip(&paths).zip(row) {
+                    if !nested {
+                        map.insert(column.clone(), value);
+                    } else if !insert_path(&mut map, path, value) {
+                        return Err(Error::Conflict(column.clone()));
+                    }
+                }
+                Ok(Value::Map(map))
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Value::Array(rows))
+    }
+
+    /// Write as CSV with a header row, cells are rendered with [`cell`].
+    pub fn write_csv<W: std::io::Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(&self.columns)?;
+        for row in &self.rows {
+            writer.write_record(row.iter().map(cell))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn to_csv(&self) -> Result<String, Error> {
+        let mut data = Vec::new();
+        self.write_csv(&mut data)?;
+        Ok(String::from_utf8(data).expect("cells are valid UTF-8"))
+    }
+
+    /// Read CSV with a header row. Cells are strings, empty cells are [`Value::Null`].
+    pub fn read_csv<R: std::io::Read>(reader: R) -> Result<Self, Error> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+        let columns = reader
+            .headers()?
+            .iter()
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        let rows = reader
+            .records()
+            .map(|record| {
+                let record = record?;
+                Ok((0..columns.len())
+                    .map(|i| match record.get(i) {
+                        None | Some("") => Value::Null,
+                        Some(s) => Value::String(s.to_owned()),
+                    })
+                    .collect())
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Self { columns, rows })
+    }
+}
+
+fn flatten(row: &mut Map, prefix: Option<&str>, map: Map, depth: Option<usize>) {
+    for (key, value) in map {
+        let key = key.replace('\\', "\\\\").replace('.', "\\.");
+        let key = match prefix {
+            Some(prefix) => format!("{prefix}.{key}"),
+            None => key,
+        };
+        match value {
+            Value::Map(map) if !map.is_empty() && depth != Some(0) => {
+                flatten(row, Some(&key), map, depth.map(|d| d - 1))
+            }
+            value => {
+                row.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Split a column into keys at dots that are not escaped.
+fn split_path(column: &str) -> Vec<String> {
+    let mut keys = vec![String::new()];
+    let mut chars = column.chars();
+    while let Some(c) = chars.next() {
+        let key = keys.last_mut().unwrap();
+        match c {
+            '\\' => key.extend(chars.next()),
+            '.' => keys.push(String::new()),
+            c => key.push(c),
+        }
+    }
+    keys
+}
+
+/// Null, or a map of nulls.
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Map(map) => map.values().all(is_empty),
+        _ => false,
+    }
+}
+
+/// Insert `value` at `path`, returns false if a value and nested values are at the same key.
+/// Empty values do not replace other values.
+fn insert_path(map: &mut Map, path: &[String], value: Value) -> bool {
+    let (key, rest) = path.split_first().expect("path is not empty");
+    if rest.is_empty() {
+        match map.get(key) {
+            Some(old) if !is_empty(old) => return is_empty(&value),
+            _ => {
+                map.insert(key.clone(), value);
+            }
+        }
+        return true;
+    }
+    let entry = map.entry(key.clone()).or_insert(Value::Null);
+    if !matches!(entry, Value::Map(_)) {
+        if !is_empty(entry) {
+            return is_empty(&value);
+        }
+        *entry = Value::Map(Map::new());
+    }
+    match entry {
+        Value::Map(map) => insert_path(map, rest, value),
+        _ => unreachable!(),
+    }
+}
+
+/// Render a cell: public keys and signatures as base58, EVM addresses as checksummed hex, other
+/// bytes as base64, numbers in full precision, timestamps as RFC 3339, durations as strings of
+/// [`duration`][crate::duration], and arrays and maps as JSON with the same rules for their
+/// elements.
+pub fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::U64(n) => n.to_string(),
+        Value::I64(n) => n.to_string(),
+        Value::U128(n) => n.to_string(),
+        Value::I128(n) => n.to_string(),
+        Value::F64(n) => n.to_string(),
+        Value::Decimal(d) => d.to_string(),
+        Value::B32(b) => bs58::encode(b).into_string(),
+        Value::B64(b) => bs58::encode(b).into_string(),
+        Value::Bytes(b) => base64::encode(b),
+        Value::EvmAddress(a) => crate::evm_address::to_checksum(a),
+        Value::U256(n) => n.to_string(),
+        Value::I256(n) => n.to_string(),
+        Value::Timestamp(t) => crate::timestamp::format(t),
+        Value::Duration(d) => crate::duration::format(d),
+        Value::Array(_) | Value::Map(_) => json(value).to_string(),
+    }
+}
+
+fn json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(b) => (*b).into(),
+        Value::U64(n) => (*n).into(),
+        Value::I64(n) => (*n).into(),
+        Value::Array(a) => a.iter().map(json).collect(),
+        Value::Map(m) => m
+            .iter()
+            .map(|(k, v)| (k.clone(), json(v)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+        value => cell(value).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn holders() -> Value {
+        Value::Array(vec![
+            Value::Map(crate::map! {
+                "owner" => [1u8; 32],
+                "amount" => dec!(1.50),
+                "account" => crate::map! {
+                    "mint" => [2u8; 32],
+                    "state" => crate::map! { "frozen" => false },
+                },
+            }),
+            Value::Map(crate::map! {
+                "owner" => [3u8; 32],
+                "tags" => Value::Array(vec!["a".into(), 1u64.into(), u128::MAX.into()]),
+            }),
+        ])
+    }
+
+    #[test]
+    fn test_flatten() {
+        let table = Table::from_value(holders(), &Options::default());
+        assert_eq!(
+            table.columns,
+            [
+                "owner",
+                "amount",
+                "account.mint",
+                "account.state.frozen",
+                "tags"
+            ]
+        );
+        assert_eq!(table.rows[1][1], Value::Null);
+        assert_eq!(
+            cell(&table.rows[1][4]),
+            r#"["a",1,"340282366920938463463374607431768211455"]"#
+        );
+
+        let table = Table::from_value(holders(), &Options { depth: Some(1) });
+        assert_eq!(table.columns[3], "account.state");
+        assert_eq!(cell(&table.rows[0][3]), r#"{"frozen":false}"#);
+
+        assert_eq!(
+            table.into_value(true).unwrap(),
+            Value::Array(vec![
+                Value::Map(crate::map! {
+                    "owner" => [1u8; 32],
+                    "amount" => dec!(1.50),
+                    "account" => crate::map! {
+                        "mint" => [2u8; 32],
+                        "state" => crate::map! { "frozen" => false },
+                    },
+                    "tags" => Value::Null,
+                }),
+                Value::Map(crate::map! {
+                    "owner" => [3u8; 32],
+                    "amount" => Value::Null,
+                    "account" => crate::map! {
+                        "mint" => Value::Null,
+                        "state" => Value::Null,
+                    },
+                    "tags" => Value::Array(vec!["a".into(), 1u64.into(), u128::MAX.into()]),
+                }),
+            ])
+        );
+        assert_eq!(
+            Table::from_value(Value::Array(vec![1u64.into()]), &Options::default()).columns,
+            [VALUE_COLUMN]
+        );
+    }
+
+    #[test]
+    fn test_dotted_keys() {
+        let value = Value::Array(vec![
+            Value::Map(crate::map! {
+                "a.b" => 1u64,
+                "a" => crate::map! { "b" => 2u64, "c\\" => 3u64 },
+            }),
+            Value::Map(crate::map! { "a.b" => 4u64, "a" => 5u64 }),
+        ]);
+        let table = Table::from_value(value.clone(), &Options::default());
+        assert_eq!(table.columns, ["a\\.b", "a.b", "a.c\\\\", "a"]);
+        assert_eq!(table.into_value(true).unwrap(), value);
+
+        let table = Table {
+            columns: vec!["a".to_owned(), "a.b".to_owned()],
+            rows: vec![vec![Value::from(1u64), Value::from(2u64)]],
+        };
+        assert!(matches!(
+            table.into_value(true),
+            Err(Error::Conflict(column)) if column == "a.b"
+        ));
+    }
+
+    #[test]
+    fn test_csv() {
+        let table = Table::from_value(holders(), &Options::default());
+        let csv = table.to_csv().unwrap();
+        let owner = bs58::encode([1u8; 32]).into_string();
+        let mint = bs58::encode([2u8; 32]).into_string();
+        assert_eq!(
+            csv.lines().take(2).collect::<Vec<_>>(),
+            [
+                "owner,amount,account.mint,account.state.frozen,tags".to_owned(),
+                format!("{owner},1.50,{mint},false,"),
+            ]
+        );
+
+        let read = Table::read_csv(csv.as_bytes()).unwrap();
+        assert_eq!(read.columns, table.columns);
+        assert_eq!(read.rows[0][1], Value::String("1.50".to_owned()));
+        assert_eq!(read.rows[0][4], Value::Null);
+
+        let Value::Array(rows) = read.into_value(true).unwrap() else {
+            panic!("expected array");
+        };
+        assert_eq!(
+            rows[0],
+            Value::Map(crate::map! {
+                "owner" => owner,
+                "amount" => "1.50",
+                "account" => crate::map! {
+                    "mint" => mint,
+                    "state" => crate::map! { "frozen" => "false" },
+                },
+                "tags" => Value::Null,
+            })
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! Serde helper for timestamps.
+//!
+//! Timestamps are serialized to [`Value::Timestamp`][crate::Value::Timestamp], and to [RFC 3339]
+//! strings in UTC, such as `2024-01-31T12:00:00Z`, in human-readable formats. They can be
+//! deserialized from RFC 3339 strings with any offset, and from Unix seconds as
+//! numbers or numeric strings, fractional seconds are kept.
+//!
+//! Use [`unix`] for fields that store Unix seconds as `i64` but should accept the same inputs.
+//!
+//! [RFC 3339]: https://www.rfc-editor.org/rfc/rfc3339
+
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use thiserror::Error as ThisError;
+
+pub(crate) const TOKEN: &str = "$$t";
+
+pub type Target = DateTime<Utc>;
+
+#[derive(ThisError, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("expected RFC 3339 date and time or Unix seconds")]
+    Format,
+    #[error("timestamp out of range")]
+    OutOfRange,
+}
+
+/// Parse an RFC 3339 string or Unix seconds.
+pub fn parse(s: &str) -> Result<Target, ParseError> {
+    let s = s.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    if let Ok(secs) = s.parse::<i64>() {
+        return from_unix(secs);
+    }
+    match s.parse::<f64>() {
+        Ok(secs) => from_unix_f64(secs),
+        Err(_) => Err(ParseError::Format),
+    }
+}
+
+/// Format as RFC 3339 in UTC, with fractional seconds only if there are any.
+pub fn format(t: &Target) -> String {
+    t.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+pub fn from_unix(secs: i64) -> Result<Target, ParseError> {
+    from_unix_nanos(secs, 0)
+}
+
+pub fn from_unix_nanos(secs: i64, nanos: u32) -> Result<Target, ParseError> {
+    Utc.timestamp_opt(secs, nanos)
+        .single()
+        .ok_or(ParseError::OutOfRange)
+}
+
+/// Unix seconds as `i64` followed by nanoseconds as `u32`, little-endian.
+pub(crate) fn to_bytes(t: &Target) -> [u8; 12] {
+    let mut buf = [0; 12];
+    buf[..8].copy_from_slice(&t.timestamp().to_le_bytes());
+    buf[8..].copy_from_slice(&t.timestamp_subsec_nanos().to_le_bytes());
+    buf
+}
+
+pub(crate) fn from_bytes(buf: &[u8; 12]) -> Result<Target, ParseError> {
+    let (secs, nanos) = buf.split_at(8);
+    from_unix_nanos(
+        i64::from_le_bytes(secs.try_into().unwrap()),
+        u32::from_le_bytes(nanos.try_into().unwrap()),
+    )
+}
+
+pub fn from_unix_f64(secs: f64) -> Result<Target, ParseError> {
+    if !secs.is_finite() || secs.abs() >= i64::MAX as f64 {
+        return Err(ParseError::OutOfRange);
+    }
+    let whole = secs.floor();
+    let nanos = (((secs - whole) * 1e9).round() as u32).min(999_999_999);
+    Utc.timestamp_opt(whole as i64, nanos)
+        .single()
+        .ok_or(ParseError::OutOfRange)
+}
+
+pub mod opt {
+    pub fn serialize<S>(t: &Option<super::Target>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match t {
+            Some(t) => super::serialize(t, s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<super::Target>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        d.deserialize_option(crate::OptionVisitor(super::Visitor))
+    }
+}
+
+/// Unix seconds as `i64`, serialized as a number.
+pub mod unix {
+    pub fn serialize<S>(secs: &i64, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.serialize_i64(*secs)
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<i64, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        super::deserialize(d).map(|t| t.timestamp())
+    }
+}
+
+pub fn serialize<S>(t: &Target, s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    if s.is_human_readable() {
+        s.serialize_str(&format(t))
+    } else {
+        s.serialize_newtype_struct(TOKEN, &crate::Bytes(&to_bytes(t)))
+    }
+}
+
+struct Visitor;
+
+impl<'de> serde::de::Visitor<'de> for Visitor {
+    type Value = Target;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("RFC 3339 string or Unix seconds")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        i64::try_from(v)
+            .map_err(|_| ParseError::OutOfRange)
+            .and_then(from_unix)
+            .map_err(serde::de::Error::custom)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        from_unix(v).map_err(serde::de::Error::custom)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        from_unix_f64(v).map_err(serde::de::Error::custom)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        parse(v).map_err(serde::de::Error::custom)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let buf = v
+            .try_into()
+            .map_err(|_| serde::de::Error::invalid_length(v.len(), &"12"))?;
+        from_bytes(buf).map_err(serde::de::Error::custom)
+    }
+
+    fn visit_newtype_struct<D>(self, d: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        d.deserialize_any(self)
+    }
+}
+
+pub fn deserialize<'de, D>(d: D) -> Result<Target, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    d.deserialize_newtype_struct(TOKEN, Visitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse() {
+        let t = from_unix(1_706_702_400).unwrap();
+        assert_eq!(format(&t), "2024-01-31T12:00:00Z");
+        assert_eq!(parse("2024-01-31T12:00:00Z").unwrap(), t);
+        assert_eq!(parse("2024-01-31T14:00:00+02:00").unwrap(), t);
+        assert_eq!(parse(" 1706702400 ").unwrap(), t);
+        assert_eq!(
+            format(&parse("1706702400.25").unwrap()),
+            "2024-01-31T12:00:00.250Z"
+        );
+        assert_eq!(parse("tomorrow"), Err(ParseError::Format));
+        assert_eq!(from_unix_f64(f64::NAN), Err(ParseError::OutOfRange));
+    }
+
+    #[test]
+    fn test_serde() {
+        let t = from_unix(1_706_702_400).unwrap();
+        let value = serialize(&t, crate::ser::Serializer).unwrap();
+        assert_eq!(value, Value::Timestamp(t));
+        assert_eq!(deserialize(value).unwrap(), t);
+        assert_eq!(
+            deserialize(Value::String("2024-01-31T12:00:00Z".to_owned())).unwrap(),
+            t
+        );
+        assert_eq!(
+            serialize(&t, serde_json::value::Serializer).unwrap(),
+            serde_json::json!("2024-01-31T12:00:00Z")
+        );
+        let nanos = parse("2024-01-31T12:00:00.000000001Z").unwrap();
+        assert_eq!(from_bytes(&to_bytes(&nanos)).unwrap(), nanos);
+        assert_eq!(deserialize(Value::U64(1_706_702_400)).unwrap(), t);
+        assert_eq!(deserialize(Value::Decimal(dec!(1706702400))).unwrap(), t);
+        assert_eq!(
+            deserialize(Value::Bool(true)).unwrap_err().to_string(),
+            "invalid type: boolean `true`, expected RFC 3339 string or Unix seconds"
+        );
+
+        assert_eq!(
+            unix::deserialize(Value::String("2024-01-31T12:00:00Z".to_owned())).unwrap(),
+            1_706_702_400
+        );
+        assert_eq!(
+            unix::serialize(&1_706_702_400, crate::ser::Serializer).unwrap(),
+            Value::I64(1_706_702_400)
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Serde helper for 256-bit unsigned integers.
+//!
+//! Numbers are serialized to [`Value::U256`][crate::Value::U256], and to a string of decimal digits
+//! in human-readable formats. They can be deserialized from any integer, and from decimal or
+//! `0x`-prefixed hex strings.
+
+pub use ethnum::U256;
+
+pub(crate) const TOKEN: &str = "$$u256";
+
+pub type Target = U256;
+
+pub mod opt {
+    pub fn serialize<S>(n: &Option<super::Target>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match n {
+            Some(n) => super::serialize(n, s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<super::Target>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        d.deserialize_option(crate::OptionVisitor(super::Visitor))
+    }
+}
+
+pub fn serialize<S>(n: &Target, s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    if s.is_human_readable() {
+        s.collect_str(n)
+    } else {
+        s.serialize_newtype_struct(TOKEN, &crate::Bytes(&n.to_le_bytes()))
+    }
+}
+
+struct Visitor;
+
+impl<'de> serde::de::Visitor<'de> for Visitor {
+    type Value = Target;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("unsigned 256-bit integer")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(U256::from(v))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(U256::from(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.visit_i128(v.into())
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        U256::try_from(v).map_err(|_| {
+            serde::de::Error::invalid_value(serde::de::Unexpected::Other("negative integer"), &self)
+        })
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let bytes = v
+            .try_into()
+            .map_err(|_| serde::de::Error::invalid_length(v.len(), &"32"))?;
+        Ok(U256::from_le_bytes(bytes))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        U256::from_str_prefixed(v.trim())
+            .map_err(|_| serde::de::Error::invalid_value(serde::de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_newtype_struct<D>(self, d: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        d.deserialize_any(self)
+    }
+}
+
+pub fn deserialize<'de, D>(d: D) -> Result<Target, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    d.deserialize_newtype_struct(TOKEN, Visitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    #[test]
+    fn test_serde() {
+        let ser = |n: U256| serialize(&n, crate::ser::Serializer).unwrap();
+        assert_eq!(ser(U256::new(1)), Value::U256(U256::new(1)));
+        assert_eq!(ser(U256::MAX), Value::U256(U256::MAX));
+
+        for n in [U256::ZERO, U256::new(u64::MAX.into()), U256::MAX] {
+            assert_eq!(deserialize(ser(n)).unwrap(), n);
+        }
+        assert_eq!(
+            deserialize(Value::String("0xff".to_owned())).unwrap(),
+            U256::new(255)
+        );
+        assert_eq!(
+            deserialize(Value::U128(u128::MAX)).unwrap(),
+            U256::new(u128::MAX)
+        );
+        assert!(deserialize(Value::I64(-1)).is_err());
+        assert!(deserialize(Value::String("1.5".to_owned())).is_err());
+    }
+}
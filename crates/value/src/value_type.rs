@@ -1,4 +1,101 @@
 use crate::Value;
+use serde::{Deserialize, Serialize};
+
+/// Use to describe input types and output types of nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ValueType {
+    #[serde(rename = "bool")]
+    Bool,
+    #[serde(rename = "u8")]
+    U8,
+    #[serde(rename = "u16")]
+    U16,
+    #[serde(rename = "u32")]
+    U32,
+    #[serde(rename = "u64")]
+    U64,
+    #[serde(rename = "u128")]
+    U128,
+    #[serde(rename = "i8")]
+    I8,
+    #[serde(rename = "i16")]
+    I16,
+    #[serde(rename = "i32")]
+    I32,
+    #[serde(rename = "i64")]
+    I64,
+    #[serde(rename = "i128")]
+    I128,
+    #[serde(rename = "f32")]
+    F32,
+    #[serde(rename = "f64")]
+    F64,
+    #[serde(alias = "number")]
+    #[serde(rename = "decimal")]
+    Decimal,
+    #[serde(rename = "pubkey")]
+    Pubkey,
+    // Wormhole address
+    #[serde(rename = "address")]
+    Address,
+    #[serde(rename = "keypair")]
+    Keypair,
+    #[serde(rename = "signature")]
+    Signature,
+    #[serde(rename = "string")]
+    String,
+    #[serde(rename = "bytes")]
+    Bytes,
+    #[serde(rename = "array")]
+    Array,
+    #[serde(rename = "object")]
+    Map,
+    #[serde(rename = "json")]
+    Json,
+    #[serde(rename = "free")]
+    Free,
+    #[serde(other)]
+    Other,
+}
+
+impl ValueType {
+    /// Name used in node definitions.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ValueType::Bool => "bool",
+            ValueType::U8 => "u8",
+            ValueType::U16 => "u16",
+            ValueType::U32 => "u32",
+            ValueType::U64 => "u64",
+            ValueType::U128 => "u128",
+            ValueType::I8 => "i8",
+            ValueType::I16 => "i16",
+            ValueType::I32 => "i32",
+            ValueType::I64 => "i64",
+            ValueType::I128 => "i128",
+            ValueType::F32 => "f32",
+            ValueType::F64 => "f64",
+            ValueType::Decimal => "decimal",
+            ValueType::Pubkey => "pubkey",
+            ValueType::Address => "address",
+            ValueType::Keypair => "keypair",
+            ValueType::Signature => "signature",
+            ValueType::String => "string",
+            ValueType::Bytes => "bytes",
+            ValueType::Array => "array",
+            ValueType::Map => "object",
+            ValueType::Json => "json",
+            ValueType::Free => "free",
+            ValueType::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ValueType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
 
 #[derive(Clone, Copy, Debug)]
 #[repr(u32)]
@@ -17,6 +114,11 @@ pub enum Variant {
     Bytes = 11,
     Array = 12,
     Map = 13,
+    EvmAddress = 14,
+    U256 = 15,
+    I256 = 16,
+    Timestamp = 17,
+    Duration = 18,
 }
 
 impl Variant {
@@ -24,6 +126,31 @@ impl Variant {
         let idx = *self as u32;
         (idx, keys::ALL[idx as usize])
     }
+
+    pub const fn from_u32(idx: u32) -> Option<Self> {
+        Some(match idx {
+            0 => Variant::Null,
+            1 => Variant::String,
+            2 => Variant::Bool,
+            3 => Variant::U64,
+            4 => Variant::I64,
+            5 => Variant::F64,
+            6 => Variant::Decimal,
+            7 => Variant::I128,
+            8 => Variant::U128,
+            9 => Variant::B32,
+            10 => Variant::B64,
+            11 => Variant::Bytes,
+            12 => Variant::Array,
+            13 => Variant::Map,
+            14 => Variant::EvmAddress,
+            15 => Variant::U256,
+            16 => Variant::I256,
+            17 => Variant::Timestamp,
+            18 => Variant::Duration,
+            _ => return None,
+        })
+    }
 }
 
 pub mod keys {
@@ -41,9 +168,32 @@ pub mod keys {
     pub const BYTES: &str = "BY";
     pub const ARRAY: &str = "A";
     pub const MAP: &str = "M";
+    pub const EVM_ADDRESS: &str = "EA";
+    pub const U256: &str = "U2";
+    pub const I256: &str = "I2";
+    pub const TIMESTAMP: &str = "T";
+    pub const DURATION: &str = "DU";
 
     pub const ALL: &[&str] = &[
-        NULL, STRING, BOOL, U64, I64, F64, DECIMAL, I128, U128, B32, B64, BYTES, ARRAY, MAP,
+        NULL,
+        STRING,
+        BOOL,
+        U64,
+        I64,
+        F64,
+        DECIMAL,
+        I128,
+        U128,
+        B32,
+        B64,
+        BYTES,
+        ARRAY,
+        MAP,
+        EVM_ADDRESS,
+        U256,
+        I256,
+        TIMESTAMP,
+        DURATION,
     ];
 }
 
@@ -60,27 +210,11 @@ impl<'de> serde::de::Visitor<'de> for ValueTypeVisitor {
     where
         E: serde::de::Error,
     {
-        Ok(match v {
-            0 => Variant::Null,
-            1 => Variant::String,
-            2 => Variant::Bool,
-            3 => Variant::U64,
-            4 => Variant::I64,
-            5 => Variant::F64,
-            6 => Variant::Decimal,
-            7 => Variant::I128,
-            8 => Variant::U128,
-            9 => Variant::B32,
-            10 => Variant::B64,
-            11 => Variant::Bytes,
-            12 => Variant::Array,
-            13 => Variant::Map,
-            _ => {
-                return Err(serde::de::Error::invalid_value(
-                    serde::de::Unexpected::Unsigned(v as u64),
-                    &"value in [0, 13]",
-                ))
-            }
+        Variant::from_u32(v).ok_or_else(|| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Unsigned(v as u64),
+                &"value in [0, 18]",
+            )
         })
     }
 
@@ -103,6 +237,11 @@ impl<'de> serde::de::Visitor<'de> for ValueTypeVisitor {
             keys::BYTES => Variant::Bytes,
             keys::ARRAY => Variant::Array,
             keys::MAP => Variant::Map,
+            keys::EVM_ADDRESS => Variant::EvmAddress,
+            keys::U256 => Variant::U256,
+            keys::I256 => Variant::I256,
+            keys::TIMESTAMP => Variant::Timestamp,
+            keys::DURATION => Variant::Duration,
             _ => {
                 return Err(serde::de::Error::invalid_value(
                     serde::de::Unexpected::Str(v),
@@ -143,6 +282,11 @@ impl Value {
             Value::Bytes(_) => Variant::Bytes,
             Value::Array(_) => Variant::Array,
             Value::Map(_) => Variant::Map,
+            Value::EvmAddress(_) => Variant::EvmAddress,
+            Value::U256(_) => Variant::U256,
+            Value::I256(_) => Variant::I256,
+            Value::Timestamp(_) => Variant::Timestamp,
+            Value::Duration(_) => Variant::Duration,
         }
     }
 }